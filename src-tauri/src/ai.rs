@@ -0,0 +1,1348 @@
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use tokio::sync::Notify;
+use crate::database::MeetingSegment;
+
+// Shared chat message format (OpenAI and Ollama use the same shape)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatMessage {
+    pub role: String,
+    pub content: String,
+}
+
+impl ChatMessage {
+    pub fn system(content: impl Into<String>) -> Self {
+        Self { role: "system".to_string(), content: content.into() }
+    }
+
+    pub fn user(content: impl Into<String>) -> Self {
+        Self { role: "user".to_string(), content: content.into() }
+    }
+}
+
+// OpenAI API structures
+#[derive(Serialize)]
+struct OpenAIRequest<'a> {
+    model: &'a str,
+    messages: &'a [ChatMessage],
+    max_tokens: Option<u32>,
+    temperature: Option<f32>,
+    stream: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    response_format: Option<serde_json::Value>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct OpenAIChoice {
+    message: ChatMessage,
+    finish_reason: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Usage {
+    prompt_tokens: u32,
+    completion_tokens: u32,
+    total_tokens: u32,
+}
+
+#[derive(Serialize, Deserialize)]
+struct OpenAIResponse {
+    choices: Vec<OpenAIChoice>,
+    usage: Option<Usage>,
+}
+
+// OpenAI streaming structures (server-sent events)
+#[derive(Deserialize)]
+struct OpenAIStreamDelta {
+    content: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct OpenAIStreamChoice {
+    delta: OpenAIStreamDelta,
+}
+
+#[derive(Deserialize)]
+struct OpenAIStreamChunk {
+    choices: Vec<OpenAIStreamChoice>,
+}
+
+// Ollama API structures (/api/chat)
+#[derive(Serialize)]
+struct OllamaOptions {
+    temperature: f32,
+    num_predict: u32,
+    num_ctx: usize,
+}
+
+#[derive(Serialize)]
+struct OllamaRequest<'a> {
+    model: &'a str,
+    messages: &'a [ChatMessage],
+    stream: bool,
+    options: OllamaOptions,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    format: Option<&'a serde_json::Value>,
+}
+
+// Used for both the single response and each NDJSON line when streaming
+#[derive(Deserialize)]
+struct OllamaResponse {
+    message: ChatMessage,
+    #[serde(default)]
+    done: bool,
+}
+
+/// A chat-completion backend used for meeting minutes generation.
+pub trait ChatProvider {
+    /// Identifier stored in `Meeting.ai_provider` ("openai" or "ollama")
+    fn name(&self) -> &'static str;
+
+    fn model(&self) -> &str;
+
+    /// Maximum number of tokens generated per response
+    fn max_tokens(&self) -> u32;
+
+    /// Size of the model context window in tokens (prompt + response)
+    fn context_tokens(&self) -> usize;
+
+    async fn chat(&self, messages: &[ChatMessage]) -> Result<String, String>;
+
+    /// Like `chat`, but constrains the response to JSON matching `schema`.
+    async fn chat_json(&self, messages: &[ChatMessage], schema: &serde_json::Value) -> Result<String, String>;
+
+    /// Stream the completion, calling `on_token` for every piece of text as it
+    /// arrives. Returns the full text, or an error if `cancelled` was set.
+    async fn chat_stream<F>(&self, messages: &[ChatMessage], cancelled: &Cancellation, on_token: F) -> Result<String, String>
+    where
+        F: FnMut(&str) + Send;
+}
+
+pub struct OpenAIProvider {
+    client: reqwest::Client,
+    base_url: String,
+    api_key: String,
+    model: String,
+    max_tokens: u32,
+    temperature: f32,
+    context_tokens: usize,
+}
+
+impl OpenAIProvider {
+    pub fn new(base_url: String, api_key: String, model: String, max_tokens: u32, temperature: f32, context_tokens: usize) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            api_key,
+            model,
+            max_tokens,
+            temperature,
+            context_tokens,
+        }
+    }
+
+    pub fn from_env() -> Result<Self, String> {
+        let api_key = std::env::var("OPENAI_API_KEY")
+            .map_err(|_| "OPENAI_API_KEY not found in environment variables. Please add it to your .env file.".to_string())?;
+
+        let base_url = std::env::var("OPENAI_BASE_URL").unwrap_or_else(|_| "https://api.openai.com/v1".to_string());
+        let model = std::env::var("OPENAI_MODEL").unwrap_or_else(|_| "gpt-4.1".to_string());
+
+        Ok(Self::new(base_url, api_key, model, env_max_tokens(), env_temperature(), env_context_tokens(128_000)))
+    }
+
+    /// POST a chat completion request; error statuses become errors.
+    async fn send(&self, messages: &[ChatMessage], stream: bool, response_format: Option<serde_json::Value>) -> Result<reqwest::Response, String> {
+        let request = OpenAIRequest {
+            model: &self.model,
+            messages,
+            max_tokens: Some(self.max_tokens),
+            temperature: Some(self.temperature),
+            stream,
+            response_format,
+        };
+
+        let mut builder = self.client
+            .post(format!("{}/chat/completions", self.base_url))
+            .header("Authorization", format!("Bearer {}", self.api_key))
+            .header("Content-Type", "application/json");
+        if stream {
+            builder = builder.header("Accept", "text/event-stream");
+        }
+
+        let response = builder
+            .json(&request)
+            .send()
+            .await
+            .map_err(|e| format!("Failed to send request to OpenAI: {}", e))?;
+
+        if !response.status().is_success() {
+            let status_code = response.status();
+            let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(format!("OpenAI API error ({}): {}", status_code, error_text));
+        }
+
+        Ok(response)
+    }
+
+    async fn complete(&self, messages: &[ChatMessage], response_format: Option<serde_json::Value>) -> Result<String, String> {
+        let response = self.send(messages, false, response_format).await?;
+
+        // Get response text first for debugging
+        let response_text = response.text().await
+            .map_err(|e| format!("Failed to get response text: {}", e))?;
+
+        let openai_response: OpenAIResponse = serde_json::from_str(&response_text)
+            .map_err(|e| format!("Failed to parse OpenAI response: {}. Response was: {}", e, response_text))?;
+
+        openai_response.choices
+            .into_iter()
+            .next()
+            .map(|choice| choice.message.content)
+            .ok_or_else(|| "No response from OpenAI".to_string())
+    }
+}
+
+impl ChatProvider for OpenAIProvider {
+    fn name(&self) -> &'static str {
+        "openai"
+    }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    fn max_tokens(&self) -> u32 {
+        self.max_tokens
+    }
+
+    fn context_tokens(&self) -> usize {
+        self.context_tokens
+    }
+
+    async fn chat(&self, messages: &[ChatMessage]) -> Result<String, String> {
+        self.complete(messages, None).await
+    }
+
+    async fn chat_json(&self, messages: &[ChatMessage], schema: &serde_json::Value) -> Result<String, String> {
+        let response_format = serde_json::json!({
+            "type": "json_schema",
+            "json_schema": {
+                "name": "response",
+                "strict": true,
+                "schema": schema,
+            }
+        });
+        self.complete(messages, Some(response_format)).await
+    }
+
+    async fn chat_stream<F>(&self, messages: &[ChatMessage], cancelled: &Cancellation, mut on_token: F) -> Result<String, String>
+    where
+        F: FnMut(&str) + Send,
+    {
+        let response = self.send(messages, true, None).await?;
+
+        let mut full_text = String::new();
+        read_stream_lines(response, cancelled, |line| {
+            // SSE: only "data:" lines carry payloads; comments and blank lines are keep-alives
+            let data = match line.strip_prefix("data:") {
+                Some(data) => data.trim(),
+                None => return Ok(false),
+            };
+
+            if data == "[DONE]" {
+                return Ok(true);
+            }
+
+            let chunk: OpenAIStreamChunk = serde_json::from_str(data)
+                .map_err(|e| format!("Failed to parse OpenAI stream chunk: {}. Chunk was: {}", e, data))?;
+
+            for choice in chunk.choices {
+                if let Some(content) = choice.delta.content {
+                    if !content.is_empty() {
+                        on_token(&content);
+                        full_text.push_str(&content);
+                    }
+                }
+            }
+            Ok(false)
+        }).await?;
+
+        if full_text.trim().is_empty() {
+            return Err("No response from OpenAI".to_string());
+        }
+
+        Ok(full_text)
+    }
+}
+
+pub struct OllamaProvider {
+    client: reqwest::Client,
+    host: String,
+    model: String,
+    max_tokens: u32,
+    temperature: f32,
+    context_tokens: usize,
+}
+
+impl OllamaProvider {
+    pub fn new(host: String, model: String, max_tokens: u32, temperature: f32, context_tokens: usize) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            host: host.trim_end_matches('/').to_string(),
+            model,
+            max_tokens,
+            temperature,
+            context_tokens,
+        }
+    }
+
+    pub fn from_env() -> Self {
+        let host = std::env::var("OLLAMA_HOST").unwrap_or_else(|_| "http://localhost:11434".to_string());
+        let model = std::env::var("OLLAMA_MODEL").unwrap_or_else(|_| "gemma3:4b".to_string());
+
+        // Ollama silently truncates prompts beyond num_ctx, so keep the default modest
+        Self::new(host, model, env_max_tokens(), env_temperature(), env_context_tokens(8192))
+    }
+
+    /// POST a /api/chat request; error statuses become errors.
+    async fn send(&self, messages: &[ChatMessage], stream: bool, format: Option<&serde_json::Value>) -> Result<reqwest::Response, String> {
+        let request = OllamaRequest {
+            model: &self.model,
+            messages,
+            stream,
+            options: OllamaOptions {
+                temperature: self.temperature,
+                num_predict: self.max_tokens,
+                num_ctx: self.context_tokens,
+            },
+            format,
+        };
+
+        let response = self.client
+            .post(format!("{}/api/chat", self.host))
+            .json(&request)
+            .send()
+            .await
+            .map_err(|e| format!("Failed to send request to Ollama at {}: {}. Is `ollama serve` running?", self.host, e))?;
+
+        if !response.status().is_success() {
+            let status_code = response.status();
+            let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(format!("Ollama API error ({}): {}", status_code, error_text));
+        }
+
+        Ok(response)
+    }
+
+    async fn complete(&self, messages: &[ChatMessage], format: Option<&serde_json::Value>) -> Result<String, String> {
+        let response = self.send(messages, false, format).await?;
+
+        let response_text = response.text().await
+            .map_err(|e| format!("Failed to get response text: {}", e))?;
+
+        let ollama_response: OllamaResponse = serde_json::from_str(&response_text)
+            .map_err(|e| format!("Failed to parse Ollama response: {}. Response was: {}", e, response_text))?;
+
+        if ollama_response.message.content.trim().is_empty() {
+            return Err("No response from Ollama".to_string());
+        }
+
+        Ok(ollama_response.message.content)
+    }
+}
+
+impl ChatProvider for OllamaProvider {
+    fn name(&self) -> &'static str {
+        "ollama"
+    }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    fn max_tokens(&self) -> u32 {
+        self.max_tokens
+    }
+
+    fn context_tokens(&self) -> usize {
+        self.context_tokens
+    }
+
+    async fn chat(&self, messages: &[ChatMessage]) -> Result<String, String> {
+        self.complete(messages, None).await
+    }
+
+    async fn chat_json(&self, messages: &[ChatMessage], schema: &serde_json::Value) -> Result<String, String> {
+        self.complete(messages, Some(schema)).await
+    }
+
+    async fn chat_stream<F>(&self, messages: &[ChatMessage], cancelled: &Cancellation, mut on_token: F) -> Result<String, String>
+    where
+        F: FnMut(&str) + Send,
+    {
+        let response = self.send(messages, true, None).await?;
+
+        let mut full_text = String::new();
+        read_stream_lines(response, cancelled, |line| {
+            // NDJSON: one JSON object per line, the last one has "done": true
+            if line.trim().is_empty() {
+                return Ok(false);
+            }
+
+            let chunk: OllamaResponse = serde_json::from_str(line)
+                .map_err(|e| format!("Failed to parse Ollama stream chunk: {}. Chunk was: {}", e, line))?;
+
+            if !chunk.message.content.is_empty() {
+                on_token(&chunk.message.content);
+                full_text.push_str(&chunk.message.content);
+            }
+            Ok(chunk.done)
+        }).await?;
+
+        if full_text.trim().is_empty() {
+            return Err("No response from Ollama".to_string());
+        }
+
+        Ok(full_text)
+    }
+}
+
+/// Provider selected from configuration.
+pub enum AiProvider {
+    OpenAI(OpenAIProvider),
+    Ollama(OllamaProvider),
+}
+
+impl AiProvider {
+    /// Pick a provider: explicit request, then `AI_PROVIDER`, then OpenAI if
+    /// `OPENAI_API_KEY` is set, otherwise the local Ollama server.
+    pub fn from_env(requested: Option<&str>) -> Result<Self, String> {
+        let configured = requested
+            .map(|p| p.to_string())
+            .or_else(|| std::env::var("AI_PROVIDER").ok())
+            .map(|p| p.trim().to_lowercase())
+            .filter(|p| !p.is_empty());
+
+        match configured.as_deref() {
+            Some("openai") => Ok(AiProvider::OpenAI(OpenAIProvider::from_env()?)),
+            Some("ollama") => Ok(AiProvider::Ollama(OllamaProvider::from_env())),
+            Some(other) => Err(format!("Unknown AI provider '{}'. Use \"openai\" or \"ollama\".", other)),
+            None => {
+                if std::env::var("OPENAI_API_KEY").map(|k| !k.trim().is_empty()).unwrap_or(false) {
+                    Ok(AiProvider::OpenAI(OpenAIProvider::from_env()?))
+                } else {
+                    Ok(AiProvider::Ollama(OllamaProvider::from_env()))
+                }
+            }
+        }
+    }
+}
+
+impl ChatProvider for AiProvider {
+    fn name(&self) -> &'static str {
+        match self {
+            AiProvider::OpenAI(p) => p.name(),
+            AiProvider::Ollama(p) => p.name(),
+        }
+    }
+
+    fn model(&self) -> &str {
+        match self {
+            AiProvider::OpenAI(p) => p.model(),
+            AiProvider::Ollama(p) => p.model(),
+        }
+    }
+
+    fn max_tokens(&self) -> u32 {
+        match self {
+            AiProvider::OpenAI(p) => p.max_tokens(),
+            AiProvider::Ollama(p) => p.max_tokens(),
+        }
+    }
+
+    fn context_tokens(&self) -> usize {
+        match self {
+            AiProvider::OpenAI(p) => p.context_tokens(),
+            AiProvider::Ollama(p) => p.context_tokens(),
+        }
+    }
+
+    async fn chat(&self, messages: &[ChatMessage]) -> Result<String, String> {
+        match self {
+            AiProvider::OpenAI(p) => p.chat(messages).await,
+            AiProvider::Ollama(p) => p.chat(messages).await,
+        }
+    }
+
+    async fn chat_json(&self, messages: &[ChatMessage], schema: &serde_json::Value) -> Result<String, String> {
+        match self {
+            AiProvider::OpenAI(p) => p.chat_json(messages, schema).await,
+            AiProvider::Ollama(p) => p.chat_json(messages, schema).await,
+        }
+    }
+
+    async fn chat_stream<F>(&self, messages: &[ChatMessage], cancelled: &Cancellation, on_token: F) -> Result<String, String>
+    where
+        F: FnMut(&str) + Send,
+    {
+        match self {
+            AiProvider::OpenAI(p) => p.chat_stream(messages, cancelled, on_token).await,
+            AiProvider::Ollama(p) => p.chat_stream(messages, cancelled, on_token).await,
+        }
+    }
+}
+
+/// Cancels a generation in progress. Waiting tasks are woken at once instead of
+/// polling a flag.
+#[derive(Default)]
+pub struct Cancellation {
+    cancelled: AtomicBool,
+    notify: Notify,
+}
+
+impl Cancellation {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+        self.notify.notify_waiters();
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Resolves once `cancel` has been called.
+    pub async fn cancelled(&self) {
+        let notified = self.notify.notified();
+        tokio::pin!(notified);
+        // Register before checking, so a cancel in between still wakes us
+        notified.as_mut().enable();
+        if self.is_cancelled() {
+            return;
+        }
+        notified.await;
+    }
+}
+
+/// Read a streaming HTTP body line by line (SSE and NDJSON are both line based).
+/// `on_line` returns `Ok(true)` once the end-of-stream marker has been seen.
+async fn read_stream_lines<F>(mut response: reqwest::Response, cancelled: &Cancellation, mut on_line: F) -> Result<(), String>
+where
+    F: FnMut(&str) -> Result<bool, String>,
+{
+    let mut buffer: Vec<u8> = Vec::new();
+
+    loop {
+        let chunk = tokio::select! {
+            chunk = response.chunk() => chunk.map_err(|e| format!("Failed to read response stream: {}", e))?,
+            _ = cancelled.cancelled() => return Err("Meeting minutes generation cancelled".to_string()),
+        };
+
+        let Some(chunk) = chunk else {
+            break;
+        };
+        buffer.extend_from_slice(&chunk);
+
+        // Lines can be split across chunks, so only consume complete ones
+        while let Some(newline) = buffer.iter().position(|&b| b == b'\n') {
+            let line_bytes: Vec<u8> = buffer.drain(..=newline).collect();
+            let line = String::from_utf8_lossy(&line_bytes);
+            if on_line(line.trim_end_matches(['\r', '\n']))? {
+                return Ok(());
+            }
+        }
+    }
+
+    // Flush a final line that had no trailing newline
+    if !buffer.is_empty() {
+        let line = String::from_utf8_lossy(&buffer);
+        on_line(line.trim_end_matches(['\r', '\n']))?;
+    }
+
+    Ok(())
+}
+
+fn env_max_tokens() -> u32 {
+    std::env::var("OPENAI_MAX_TOKENS")
+        .unwrap_or_else(|_| "2000".to_string())
+        .parse::<u32>()
+        .unwrap_or(2000)
+}
+
+fn env_context_tokens(default: usize) -> usize {
+    std::env::var("AI_CONTEXT_TOKENS")
+        .ok()
+        .and_then(|v| v.parse::<usize>().ok())
+        .unwrap_or(default)
+}
+
+fn env_temperature() -> f32 {
+    std::env::var("OPENAI_TEMPERATURE")
+        .unwrap_or_else(|_| "0.3".to_string())
+        .parse::<f32>()
+        .unwrap_or(0.3)
+}
+
+fn language_instruction(language: Option<&str>) -> &'static str {
+    match language {
+        Some("id") => "Generate the meeting minutes in Indonesian (Bahasa Indonesia). Use professional Indonesian business language.",
+        Some("es") => "Generate the meeting minutes in Spanish. Use professional Spanish business language.",
+        Some("fr") => "Generate the meeting minutes in French. Use professional French business language.",
+        Some("de") => "Generate the meeting minutes in German. Use professional German business language.",
+        Some("it") => "Generate the meeting minutes in Italian. Use professional Italian business language.",
+        Some("pt") => "Generate the meeting minutes in Portuguese. Use professional Portuguese business language.",
+        Some("nl") => "Generate the meeting minutes in Dutch. Use professional Dutch business language.",
+        Some("ru") => "Generate the meeting minutes in Russian. Use professional Russian business language.",
+        Some("ja") => "Generate the meeting minutes in Japanese. Use professional Japanese business language.",
+        Some("ko") => "Generate the meeting minutes in Korean. Use professional Korean business language.",
+        Some("zh") => "Generate the meeting minutes in Chinese. Use professional Chinese business language.",
+        Some("ar") => "Generate the meeting minutes in Arabic. Use professional Arabic business language.",
+        Some("hi") => "Generate the meeting minutes in Hindi. Use professional Hindi business language.",
+        Some("tr") => "Generate the meeting minutes in Turkish. Use professional Turkish business language.",
+        _ => "Generate the meeting minutes in English. Use professional English business language.",
+    }
+}
+
+fn minutes_system_prompt(language: Option<&str>) -> String {
+    format!(r#"You are an expert meeting assistant. Transform the following meeting transcript into well-structured meeting minutes. {}
+
+Include the following sections:
+
+1. **Meeting Summary** - Brief overview of the meeting
+2. **Key Discussion Points** - Main topics discussed
+3. **Decisions Made** - Any decisions or conclusions reached
+4. **Action Items** - Tasks assigned with responsible parties (if mentioned)
+5. **Next Steps** - Follow-up actions or future meetings
+
+Format the output in clear, professional language with proper headings and bullet points. Use markdown formatting including:
+- **Bold text** for emphasis
+- Bullet points for lists
+- Tables for structured data (use proper markdown table syntax with | separators)
+- Code blocks for technical content
+
+When presenting structured information like action items, deadlines, or data comparisons, use markdown tables with this format:
+| Column 1 | Column 2 | Column 3 |
+|----------|----------|----------|
+| Data 1   | Data 2   | Data 3   |
+
+Transcript lines may be prefixed with a speaker name (e.g. "[03:12] Alice: ..."). Attribute statements, decisions and action items to those speakers. If specific names or roles aren't mentioned, use generic terms like "Participant A", "Team Member", etc. Maintain the same language throughout the entire document.
+
+IMPORTANT: End your response with exactly this format:
+---
+KEY_TOPICS: [comma-separated list of 3-5 topics]
+SENTIMENT: [Positive/Neutral/Negative]
+ENERGY: [High/Medium/Low]"#, language_instruction(language))
+}
+
+/// Build the system + user messages for a meeting minutes request.
+pub fn meeting_minutes_messages(transcript: &str, language: Option<&str>) -> Vec<ChatMessage> {
+    let user_prompt = format!("Please generate meeting minutes from this transcript:\n\n{}", transcript);
+
+    vec![ChatMessage::system(minutes_system_prompt(language)), ChatMessage::user(user_prompt)]
+}
+
+// Map-reduce summarization for transcripts that don't fit the context window
+
+/// A contiguous slice of the meeting (or of partial notes about it).
+#[derive(Debug, Clone)]
+pub struct TranscriptChunk {
+    pub start_time: Option<f64>,
+    pub end_time: Option<f64>,
+    pub text: String,
+}
+
+impl TranscriptChunk {
+    fn time_range(&self) -> String {
+        match (self.start_time, self.end_time) {
+            (Some(start), Some(end)) => format!(
+                " ({} - {})",
+                crate::format_time_readable(start),
+                crate::format_time_readable(end)
+            ),
+            _ => String::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MinutesProgress {
+    pub stage: String, // "summarizing", "condensing" or "merging"
+    pub current: usize,
+    pub total: usize,
+}
+
+/// How many transcript characters fit in one request, leaving room for the
+/// system prompt and the response. Uses a conservative 3 characters per token.
+pub fn chunk_budget_chars<P: ChatProvider>(provider: &P) -> usize {
+    let prompt_overhead_tokens = 1000;
+    let available_tokens = provider.context_tokens()
+        .saturating_sub(provider.max_tokens() as usize)
+        .saturating_sub(prompt_overhead_tokens);
+
+    (available_tokens * 3).max(2000)
+}
+
+/// Group consecutive pieces into chunks of at most `max_chars` characters.
+/// A single piece larger than the budget becomes its own chunk.
+fn pack_chunks(pieces: Vec<TranscriptChunk>, max_chars: usize) -> Vec<TranscriptChunk> {
+    let mut chunks: Vec<TranscriptChunk> = Vec::new();
+
+    for piece in pieces {
+        match chunks.last_mut() {
+            Some(current) if current.text.len() + piece.text.len() < max_chars => {
+                current.text.push('\n');
+                current.text.push_str(&piece.text);
+                if piece.end_time.is_some() {
+                    current.end_time = piece.end_time;
+                }
+            }
+            _ => chunks.push(piece),
+        }
+    }
+
+    chunks
+}
+
+/// Split a meeting along its transcription segment boundaries.
+pub fn chunk_segments(segments: &[MeetingSegment], max_chars: usize) -> Vec<TranscriptChunk> {
+    let pieces = segments.iter()
+        .filter(|segment| !segment.text.trim().is_empty())
+        .map(|segment| TranscriptChunk {
+            start_time: Some(segment.start_time),
+            end_time: Some(segment.end_time),
+            text: match segment.speaker_label() {
+                Some(speaker) => format!("[{}] {}: {}", crate::format_time_readable(segment.start_time), speaker, segment.text.trim()),
+                None => format!("[{}] {}", crate::format_time_readable(segment.start_time), segment.text.trim()),
+            },
+        })
+        .collect();
+
+    pack_chunks(pieces, max_chars)
+}
+
+/// Fallback when no segments are stored: split the plain transcript on sentence ends.
+pub fn chunk_text(transcript: &str, max_chars: usize) -> Vec<TranscriptChunk> {
+    let pieces = transcript
+        .split_inclusive(['.', '?', '!', '\n'])
+        .map(|sentence| sentence.trim())
+        .filter(|sentence| !sentence.is_empty())
+        .map(|sentence| TranscriptChunk {
+            start_time: None,
+            end_time: None,
+            text: sentence.to_string(),
+        })
+        .collect();
+
+    pack_chunks(pieces, max_chars)
+}
+
+fn chunk_notes_messages(chunk: &TranscriptChunk, part: usize, total: usize, language: Option<&str>) -> Vec<ChatMessage> {
+    let system_prompt = format!(
+        "You are an expert meeting assistant. You will receive part {} of {} of a long meeting{}. \
+        It is either raw transcript or notes condensed from earlier parts. {}\n\n\
+        Write concise notes for this part only, covering:\n\
+        - Topics discussed\n\
+        - Decisions made\n\
+        - Action items with owners and deadlines (if mentioned)\n\
+        - Open questions and follow-ups\n\n\
+        Keep names, numbers and timestamps exactly as given. Do not write an introduction, a conclusion \
+        or the KEY_TOPICS/SENTIMENT/ENERGY trailer.",
+        part, total, chunk.time_range(), language_instruction(language)
+    );
+
+    vec![ChatMessage::system(system_prompt), ChatMessage::user(chunk.text.clone())]
+}
+
+fn merge_notes_messages(notes: &[TranscriptChunk], language: Option<&str>) -> Vec<ChatMessage> {
+    let mut user_prompt = format!(
+        "This meeting was too long to process at once, so it was summarized in {} consecutive parts. \
+        Please generate the final meeting minutes from these partial notes:\n",
+        notes.len()
+    );
+
+    for (i, note) in notes.iter().enumerate() {
+        user_prompt.push_str(&format!("\n### Part {}{}\n{}\n", i + 1, note.time_range(), note.text.trim()));
+    }
+
+    vec![ChatMessage::system(minutes_system_prompt(language)), ChatMessage::user(user_prompt)]
+}
+
+/// Summarize each chunk and return the messages for the final minutes request.
+/// A single chunk is sent as-is; otherwise chunk notes are condensed until they
+/// fit in one request and then merged into the five-section minutes.
+pub async fn prepare_minutes_messages<P, F>(
+    provider: &P,
+    chunks: Vec<TranscriptChunk>,
+    transcript: &str,
+    language: Option<&str>,
+    cancelled: &Cancellation,
+    mut on_progress: F,
+) -> Result<Vec<ChatMessage>, String>
+where
+    P: ChatProvider,
+    F: FnMut(MinutesProgress) + Send,
+{
+    if chunks.len() <= 1 {
+        let text = chunks.first().map(|chunk| chunk.text.as_str()).unwrap_or(transcript);
+        return Ok(meeting_minutes_messages(text, language));
+    }
+
+    let budget = chunk_budget_chars(provider);
+    let mut stage = "summarizing";
+    let mut pending = chunks;
+
+    loop {
+        let total = pending.len();
+        let mut notes = Vec::with_capacity(total);
+
+        for (i, chunk) in pending.iter().enumerate() {
+            on_progress(MinutesProgress {
+                stage: stage.to_string(),
+                current: i + 1,
+                total,
+            });
+
+            let messages = chunk_notes_messages(chunk, i + 1, total, language);
+            let text = tokio::select! {
+                result = provider.chat(&messages) => result?,
+                _ = cancelled.cancelled() => return Err("Meeting minutes generation cancelled".to_string()),
+            };
+
+            notes.push(TranscriptChunk {
+                start_time: chunk.start_time,
+                end_time: chunk.end_time,
+                text,
+            });
+        }
+
+        let combined_len: usize = notes.iter().map(|note| note.text.len()).sum();
+        if combined_len <= budget || notes.len() <= 1 {
+            on_progress(MinutesProgress {
+                stage: "merging".to_string(),
+                current: 1,
+                total: 1,
+            });
+            return Ok(merge_notes_messages(&notes, language));
+        }
+
+        // Notes still don't fit: condense groups of consecutive notes and try again
+        let regrouped = pack_chunks(notes.clone(), budget);
+        if regrouped.len() >= notes.len() {
+            // Every note is already as large as the budget; merge what we have
+            return Ok(merge_notes_messages(&notes, language));
+        }
+
+        println!("🧩 Partial notes too long ({} chars), condensing {} notes into {} groups", combined_len, notes.len(), regrouped.len());
+        pending = regrouped;
+        stage = "condensing";
+    }
+}
+
+// KEY_TOPICS / SENTIMENT / ENERGY trailer requested by the minutes prompt
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MinutesMetadata {
+    pub key_topics: Vec<String>,
+    pub sentiment: Option<String>, // "Positive", "Neutral" or "Negative"
+    pub energy: Option<String>,    // "High", "Medium" or "Low"
+}
+
+/// Match `value` case-insensitively against `allowed`, returning the canonical spelling.
+fn normalize_choice(value: &str, allowed: &[&str]) -> Option<String> {
+    let cleaned = value.trim().trim_matches(|c: char| c == '[' || c == ']' || c == '*' || c == '.' || c.is_whitespace());
+    allowed.iter()
+        .find(|choice| choice.eq_ignore_ascii_case(cleaned))
+        .map(|choice| choice.to_string())
+}
+
+pub fn normalize_sentiment(value: &str) -> Option<String> {
+    normalize_choice(value, &["Positive", "Neutral", "Negative"])
+}
+
+pub fn normalize_energy(value: &str) -> Option<String> {
+    normalize_choice(value, &["High", "Medium", "Low"])
+}
+
+/// Clean up a topic list: trim, drop brackets/quotes, remove empties and duplicates.
+pub fn normalize_topics<S: AsRef<str>>(topics: &[S]) -> Vec<String> {
+    let mut result: Vec<String> = Vec::new();
+    for topic in topics {
+        let cleaned = topic.as_ref()
+            .trim()
+            .trim_matches(|c: char| c == '[' || c == ']' || c == '"' || c == '\'' || c == '*' || c.is_whitespace())
+            .to_string();
+
+        if cleaned.is_empty() || cleaned.len() > 100 {
+            continue;
+        }
+        if !result.iter().any(|t| t.eq_ignore_ascii_case(&cleaned)) {
+            result.push(cleaned);
+        }
+    }
+    result.truncate(10);
+    result
+}
+
+/// Split the metadata trailer off the minutes.
+///
+/// Returns the minutes body without the trailer, and the parsed metadata if
+/// a `KEY_TOPICS:` line was found. Unknown sentiment/energy values are dropped.
+pub fn split_minutes_trailer(minutes: &str) -> (String, Option<MinutesMetadata>) {
+    let lines: Vec<&str> = minutes.lines().collect();
+
+    // The trailer is at the end, so search backwards for KEY_TOPICS
+    let key_line = lines.iter().rposition(|line| {
+        trailer_value(line, "KEY_TOPICS").is_some()
+    });
+
+    let Some(key_line) = key_line else {
+        return (minutes.trim().to_string(), None);
+    };
+
+    let mut metadata = MinutesMetadata::default();
+    for line in &lines[key_line..] {
+        if let Some(value) = trailer_value(line, "KEY_TOPICS") {
+            let topics: Vec<&str> = value.split(',').collect();
+            metadata.key_topics = normalize_topics(&topics);
+        } else if let Some(value) = trailer_value(line, "SENTIMENT") {
+            metadata.sentiment = normalize_sentiment(value);
+        } else if let Some(value) = trailer_value(line, "ENERGY") {
+            metadata.energy = normalize_energy(value);
+        }
+    }
+
+    // Drop the "---" separator (and blank lines) in front of the trailer
+    let mut body_end = key_line;
+    while body_end > 0 {
+        let previous = lines[body_end - 1].trim();
+        if previous.is_empty() || previous.chars().all(|c| c == '-') {
+            body_end -= 1;
+        } else {
+            break;
+        }
+    }
+
+    (lines[..body_end].join("\n").trim().to_string(), Some(metadata))
+}
+
+/// Value of a `KEY: value` trailer line, tolerating markdown bold around the key.
+fn trailer_value<'a>(line: &'a str, key: &str) -> Option<&'a str> {
+    let trimmed = line.trim().trim_start_matches('*').trim_start();
+    let rest = trimmed.strip_prefix(key)?;
+    let rest = rest.trim_start_matches('*').trim_start();
+    let rest = rest.strip_prefix(':')?;
+    Some(rest.trim_start_matches('*').trim())
+}
+
+// Structured action item extraction (JSON output)
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExtractedActionItem {
+    pub description: String,
+    pub owner: Option<String>,
+    pub due_date: Option<String>,
+    pub timestamp: Option<String>, // "mm:ss" or "hh:mm:ss" as it appears in the transcript
+}
+
+impl ExtractedActionItem {
+    /// Seconds into the recording, parsed from the transcript timestamp.
+    pub fn source_time(&self) -> Option<f64> {
+        let timestamp = self.timestamp.as_deref()?.trim().trim_matches(|c| c == '[' || c == ']');
+        let mut seconds = 0.0;
+        for part in timestamp.split(':') {
+            seconds = seconds * 60.0 + part.trim().parse::<f64>().ok()?;
+        }
+        Some(seconds)
+    }
+}
+
+#[derive(Deserialize)]
+struct ActionItemsResponse {
+    action_items: Vec<ExtractedActionItem>,
+}
+
+fn action_items_schema() -> serde_json::Value {
+    let nullable_string = serde_json::json!({ "type": ["string", "null"] });
+
+    serde_json::json!({
+        "type": "object",
+        "properties": {
+            "action_items": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "properties": {
+                        "description": { "type": "string" },
+                        "owner": nullable_string,
+                        "due_date": nullable_string,
+                        "timestamp": nullable_string,
+                    },
+                    "required": ["description", "owner", "due_date", "timestamp"],
+                    "additionalProperties": false,
+                }
+            }
+        },
+        "required": ["action_items"],
+        "additionalProperties": false,
+    })
+}
+
+fn action_items_messages(chunk: &TranscriptChunk, part: usize, total: usize, language: Option<&str>) -> Vec<ChatMessage> {
+    let part_note = if total > 1 {
+        format!(" You will receive part {} of {} of the meeting{}.", part, total, chunk.time_range())
+    } else {
+        String::new()
+    };
+
+    let system_prompt = format!(
+        "You are an expert meeting assistant. Extract every action item from the meeting transcript.{}\n\n\
+        An action item is a concrete task someone agreed or was asked to do. Do not include decisions, \
+        opinions or general discussion. For each item return:\n\
+        - description: a short imperative sentence describing the task\n\
+        - owner: the person or team responsible, exactly as named in the transcript (lines may be prefixed \
+        with the speaker's name), or null\n\
+        - due_date: YYYY-MM-DD if an explicit calendar date is stated, otherwise the deadline as said \
+        (for example \"next Friday\"), or null\n\
+        - timestamp: the [mm:ss] timestamp of the line where the task was mentioned, without brackets, or null \
+        if the transcript has no timestamps\n\n\
+        {} This applies to the descriptions only; keep the JSON keys in English. \
+        Return an empty list if there are no action items.",
+        part_note,
+        language_instruction(language)
+    );
+
+    vec![ChatMessage::system(system_prompt), ChatMessage::user(chunk.text.clone())]
+}
+
+/// Parse the JSON reply, tolerating a markdown code fence around it.
+fn parse_action_items(response: &str) -> Result<Vec<ExtractedActionItem>, String> {
+    let json = response.trim()
+        .trim_start_matches("```json")
+        .trim_start_matches("```")
+        .trim_end_matches("```")
+        .trim();
+
+    let parsed: ActionItemsResponse = serde_json::from_str(json)
+        .map_err(|e| format!("Failed to parse action items: {}. Response was: {}", e, response))?;
+
+    let non_empty = |value: Option<String>| {
+        value.map(|v| v.trim().to_string()).filter(|v| !v.is_empty() && !v.eq_ignore_ascii_case("null"))
+    };
+
+    Ok(parsed.action_items.into_iter()
+        .filter(|item| !item.description.trim().is_empty())
+        .map(|item| ExtractedActionItem {
+            description: item.description.trim().to_string(),
+            owner: non_empty(item.owner),
+            due_date: non_empty(item.due_date),
+            timestamp: non_empty(item.timestamp),
+        })
+        .collect())
+}
+
+/// Extract action items chunk by chunk, dropping duplicates mentioned in several parts.
+pub async fn extract_action_items<P: ChatProvider>(
+    provider: &P,
+    chunks: &[TranscriptChunk],
+    language: Option<&str>,
+    cancelled: &Cancellation,
+) -> Result<Vec<ExtractedActionItem>, String> {
+    let schema = action_items_schema();
+    let mut items: Vec<ExtractedActionItem> = Vec::new();
+
+    for (i, chunk) in chunks.iter().enumerate() {
+        let messages = action_items_messages(chunk, i + 1, chunks.len(), language);
+        let response = tokio::select! {
+            result = provider.chat_json(&messages, &schema) => result?,
+            _ = cancelled.cancelled() => return Err("Action item extraction cancelled".to_string()),
+        };
+
+        for item in parse_action_items(&response)? {
+            if !items.iter().any(|existing| existing.description.eq_ignore_ascii_case(&item.description)) {
+                items.push(item);
+            }
+        }
+    }
+
+    Ok(items)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::Matcher;
+    use serde_json::json;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    fn messages() -> Vec<ChatMessage> {
+        vec![ChatMessage::system("You write minutes."), ChatMessage::user("Transcript")]
+    }
+
+    fn openai(server: &mockito::ServerGuard) -> OpenAIProvider {
+        // Trailing slash is trimmed from the base URL
+        OpenAIProvider::new(format!("{}/v1/", server.url()), "test-key".to_string(), "gpt-test".to_string(), 512, 0.2, 8000)
+    }
+
+    fn ollama(server: &mockito::ServerGuard) -> OllamaProvider {
+        OllamaProvider::new(server.url(), "llama-test".to_string(), 256, 0.5, 4096)
+    }
+
+    #[tokio::test]
+    async fn openai_chat_sends_completion_request_and_parses_reply() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server.mock("POST", "/v1/chat/completions")
+            .match_header("authorization", "Bearer test-key")
+            .match_header("content-type", "application/json")
+            .match_body(Matcher::Json(json!({
+                "model": "gpt-test",
+                "messages": [
+                    { "role": "system", "content": "You write minutes." },
+                    { "role": "user", "content": "Transcript" },
+                ],
+                "max_tokens": 512,
+                "temperature": 0.2,
+                "stream": false,
+            })))
+            .with_body(json!({
+                "choices": [{ "message": { "role": "assistant", "content": "## Minutes" }, "finish_reason": "stop" }],
+                "usage": { "prompt_tokens": 10, "completion_tokens": 2, "total_tokens": 12 },
+            }).to_string())
+            .create_async()
+            .await;
+
+        let reply = openai(&server).chat(&messages()).await.unwrap();
+
+        assert_eq!(reply, "## Minutes");
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn openai_chat_json_sends_schema_as_response_format() {
+        let mut server = mockito::Server::new_async().await;
+        let schema = json!({ "type": "object" });
+        let mock = server.mock("POST", "/v1/chat/completions")
+            .match_body(Matcher::PartialJson(json!({
+                "response_format": {
+                    "type": "json_schema",
+                    "json_schema": { "name": "response", "strict": true, "schema": schema },
+                },
+            })))
+            .with_body(json!({ "choices": [{ "message": { "role": "assistant", "content": "{}" }, "finish_reason": null }] }).to_string())
+            .create_async()
+            .await;
+
+        assert_eq!(openai(&server).chat_json(&messages(), &schema).await.unwrap(), "{}");
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn openai_error_status_is_reported_with_body() {
+        let mut server = mockito::Server::new_async().await;
+        server.mock("POST", "/v1/chat/completions")
+            .with_status(401)
+            .with_body("invalid api key")
+            .create_async()
+            .await;
+
+        let error = openai(&server).chat(&messages()).await.unwrap_err();
+
+        assert!(error.contains("OpenAI API error (401"), "{}", error);
+        assert!(error.contains("invalid api key"), "{}", error);
+    }
+
+    #[tokio::test]
+    async fn openai_unparseable_or_empty_reply_is_an_error() {
+        let mut server = mockito::Server::new_async().await;
+        let garbage = server.mock("POST", "/v1/chat/completions")
+            .with_body("<html>gateway</html>")
+            .create_async()
+            .await;
+        let error = openai(&server).chat(&messages()).await.unwrap_err();
+        assert!(error.starts_with("Failed to parse OpenAI response"), "{}", error);
+        garbage.remove_async().await;
+
+        server.mock("POST", "/v1/chat/completions")
+            .with_body(json!({ "choices": [] }).to_string())
+            .create_async()
+            .await;
+        assert_eq!(openai(&server).chat(&messages()).await.unwrap_err(), "No response from OpenAI");
+    }
+
+    #[tokio::test]
+    async fn openai_stream_collects_server_sent_events() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server.mock("POST", "/v1/chat/completions")
+            .match_header("accept", "text/event-stream")
+            .match_body(Matcher::PartialJson(json!({ "stream": true })))
+            .with_header("content-type", "text/event-stream")
+            .with_body(concat!(
+                ": keep-alive\n\n",
+                "data: {\"choices\":[{\"delta\":{\"role\":\"assistant\"}}]}\n\n",
+                "data: {\"choices\":[{\"delta\":{\"content\":\"Hello\"}}]}\r\n\r\n",
+                "data: {\"choices\":[{\"delta\":{\"content\":\" world\"}}]}\n\n",
+                "data: [DONE]\n\n",
+                "data: {\"choices\":[{\"delta\":{\"content\":\" ignored\"}}]}\n\n",
+            ))
+            .create_async()
+            .await;
+
+        let mut tokens = Vec::new();
+        let text = openai(&server)
+            .chat_stream(&messages(), &Cancellation::new(), |token| tokens.push(token.to_string()))
+            .await
+            .unwrap();
+
+        assert_eq!(text, "Hello world");
+        assert_eq!(tokens, ["Hello", " world"]);
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn ollama_chat_sends_options_and_parses_reply() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server.mock("POST", "/api/chat")
+            .match_body(Matcher::Json(json!({
+                "model": "llama-test",
+                "messages": [
+                    { "role": "system", "content": "You write minutes." },
+                    { "role": "user", "content": "Transcript" },
+                ],
+                "stream": false,
+                "options": { "temperature": 0.5, "num_predict": 256, "num_ctx": 4096 },
+            })))
+            .with_body(json!({ "message": { "role": "assistant", "content": "## Minutes" }, "done": true }).to_string())
+            .create_async()
+            .await;
+
+        assert_eq!(ollama(&server).chat(&messages()).await.unwrap(), "## Minutes");
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn ollama_chat_json_sends_schema_as_format() {
+        let mut server = mockito::Server::new_async().await;
+        let schema = json!({ "type": "object", "properties": {} });
+        let mock = server.mock("POST", "/api/chat")
+            .match_body(Matcher::PartialJson(json!({ "format": schema })))
+            .with_body(json!({ "message": { "role": "assistant", "content": "{\"items\":[]}" } }).to_string())
+            .create_async()
+            .await;
+
+        assert_eq!(ollama(&server).chat_json(&messages(), &schema).await.unwrap(), "{\"items\":[]}");
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn ollama_error_status_and_blank_reply_are_errors() {
+        let mut server = mockito::Server::new_async().await;
+        let missing = server.mock("POST", "/api/chat")
+            .with_status(404)
+            .with_body("{\"error\":\"model 'llama-test' not found\"}")
+            .create_async()
+            .await;
+        let error = ollama(&server).chat(&messages()).await.unwrap_err();
+        assert!(error.contains("Ollama API error (404"), "{}", error);
+        assert!(error.contains("not found"), "{}", error);
+        missing.remove_async().await;
+
+        server.mock("POST", "/api/chat")
+            .with_body(json!({ "message": { "role": "assistant", "content": "  " }, "done": true }).to_string())
+            .create_async()
+            .await;
+        assert_eq!(ollama(&server).chat(&messages()).await.unwrap_err(), "No response from Ollama");
+    }
+
+    #[tokio::test]
+    async fn ollama_unreachable_server_names_the_host() {
+        let provider = OllamaProvider::new("http://127.0.0.1:9".to_string(), "llama-test".to_string(), 256, 0.5, 4096);
+        let error = provider.chat(&messages()).await.unwrap_err();
+        assert!(error.contains("Ollama at http://127.0.0.1:9"), "{}", error);
+    }
+
+    #[tokio::test]
+    async fn ollama_stream_collects_ndjson_lines() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server.mock("POST", "/api/chat")
+            .match_body(Matcher::PartialJson(json!({ "stream": true })))
+            .with_body(concat!(
+                "{\"message\":{\"role\":\"assistant\",\"content\":\"Hello\"},\"done\":false}\n",
+                "\n",
+                "{\"message\":{\"role\":\"assistant\",\"content\":\" world\"},\"done\":false}\n",
+                "{\"message\":{\"role\":\"assistant\",\"content\":\"\"},\"done\":true}",
+            ))
+            .create_async()
+            .await;
+
+        let mut tokens = Vec::new();
+        let text = ollama(&server)
+            .chat_stream(&messages(), &Cancellation::new(), |token| tokens.push(token.to_string()))
+            .await
+            .unwrap();
+
+        assert_eq!(text, "Hello world");
+        assert_eq!(tokens, ["Hello", " world"]);
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn ollama_stream_rejects_malformed_lines() {
+        let mut server = mockito::Server::new_async().await;
+        server.mock("POST", "/api/chat")
+            .with_body("{\"message\":{\"role\":\"assistant\",\"content\":\"Hi\"}}\nnot json\n")
+            .create_async()
+            .await;
+
+        let error = ollama(&server)
+            .chat_stream(&messages(), &Cancellation::new(), |_| {})
+            .await
+            .unwrap_err();
+        assert!(error.starts_with("Failed to parse Ollama stream chunk"), "{}", error);
+    }
+
+    #[tokio::test]
+    async fn cancelling_stops_a_stalled_stream() {
+        let mut server = mockito::Server::new_async().await;
+        server.mock("POST", "/api/chat")
+            .with_chunked_body(|writer| {
+                writer.write_all(b"{\"message\":{\"role\":\"assistant\",\"content\":\"Hel\"},\"done\":false}\n")?;
+                writer.flush()?;
+                // The model goes quiet; only cancelling ends the request
+                std::thread::sleep(Duration::from_secs(3));
+                Ok(())
+            })
+            .create_async()
+            .await;
+
+        let cancellation = Arc::new(Cancellation::new());
+        let canceller = cancellation.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            canceller.cancel();
+        });
+
+        let started = std::time::Instant::now();
+        let mut tokens = Vec::new();
+        let error = ollama(&server)
+            .chat_stream(&messages(), &cancellation, |token| tokens.push(token.to_string()))
+            .await
+            .unwrap_err();
+
+        assert_eq!(error, "Meeting minutes generation cancelled");
+        assert_eq!(tokens, ["Hel"]);
+        assert!(started.elapsed() < Duration::from_secs(2), "took {:?}", started.elapsed());
+    }
+
+    #[tokio::test]
+    async fn cancellation_before_waiting_is_not_missed() {
+        let cancellation = Cancellation::new();
+        cancellation.cancel();
+        tokio::time::timeout(Duration::from_millis(100), cancellation.cancelled())
+            .await
+            .expect("an earlier cancel resolves immediately");
+    }
+}
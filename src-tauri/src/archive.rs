@@ -0,0 +1,163 @@
+// Compressed storage of recordings.
+//
+// With the FLAC policy a meeting's WAV files (the mix and any channel tracks)
+// are converted once the meeting has been transcribed; `compress_recordings`
+// converts the ones stored earlier. FLAC is lossless, so transcribing an
+// archived recording again gives the same result.
+//
+// Every FLAC file is decoded and compared with its WAV before the WAV is
+// deleted. Paths saved before the conversion keep working: `resolve` finds the
+// FLAC file that replaced a WAV.
+
+use crate::{decode, flac, recording};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+pub const STORAGE_SETTING: &str = "recording_storage";
+
+/// How recordings are kept once transcribed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RecordingStorage {
+    /// Leave the WAV files as recorded
+    #[default]
+    Wav,
+    /// Convert them to FLAC, about half the size
+    Flac,
+}
+
+// One conversion at a time, so the automatic and the bulk job never race on a file
+static CONVERTING: Mutex<()> = Mutex::new(());
+
+/// The file to read for a stored recording path: a WAV that was archived is
+/// found as the FLAC file next to it.
+pub fn resolve(path: &Path) -> PathBuf {
+    if !path.exists() && is_wav(path) {
+        let flac_path = path.with_extension("flac");
+        if flac_path.exists() {
+            return flac_path;
+        }
+    }
+    path.to_path_buf()
+}
+
+pub fn is_wav(path: &Path) -> bool {
+    path.extension().and_then(|ext| ext.to_str()).is_some_and(|ext| ext.eq_ignore_ascii_case("wav"))
+}
+
+/// Convert a recording and its channel tracks to FLAC and delete the WAV files.
+/// Returns the path of the FLAC mix and the number of bytes saved.
+pub fn compress_recording(path: &Path) -> Result<(PathBuf, u64), String> {
+    let _converting = CONVERTING.lock().map_err(|e| e.to_string())?;
+
+    // Another job may have converted it while we waited
+    let resolved = resolve(path);
+    if resolved != path {
+        return Ok((resolved, 0));
+    }
+    if !path.exists() {
+        return Err(format!("Recording not found: {}", path.display()));
+    }
+
+    let mut wav_files = vec![path.to_path_buf()];
+    for suffix in recording::CHANNEL_SUFFIXES {
+        let channel_path = recording::channel_file_path(path, suffix);
+        if channel_path.exists() {
+            wav_files.push(channel_path);
+        }
+    }
+
+    // Convert every file before deleting any, so a failure leaves the recording as it was
+    let mut flac_files = Vec::new();
+    for wav_path in &wav_files {
+        match convert(wav_path) {
+            Ok(flac_path) => flac_files.push(flac_path),
+            Err(e) => {
+                for flac_path in &flac_files {
+                    let _ = std::fs::remove_file(flac_path);
+                }
+                return Err(e);
+            }
+        }
+    }
+
+    let size = |paths: &[PathBuf]| paths.iter()
+        .filter_map(|path| std::fs::metadata(path).ok())
+        .map(|metadata| metadata.len())
+        .sum::<u64>();
+    let saved = size(&wav_files).saturating_sub(size(&flac_files));
+
+    for wav_path in &wav_files {
+        if let Err(e) = std::fs::remove_file(wav_path) {
+            println!("⚠️ Failed to delete {} after archiving: {}", wav_path.display(), e);
+        }
+    }
+
+    Ok((flac_files.swap_remove(0), saved))
+}
+
+/// Write a FLAC copy of a WAV file next to it and check that it decodes to the same samples.
+fn convert(wav_path: &Path) -> Result<PathBuf, String> {
+    let mut reader = hound::WavReader::open(wav_path)
+        .map_err(|e| format!("Failed to open {}: {}", wav_path.display(), e))?;
+    let spec = reader.spec();
+    if spec.sample_format != hound::SampleFormat::Int || spec.bits_per_sample > 24 {
+        return Err(format!("{} is not 8 to 24-bit PCM and cannot be archived", wav_path.display()));
+    }
+
+    let samples = reader.samples::<i32>()
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to read {}: {}", wav_path.display(), e))?;
+    if samples.is_empty() {
+        return Err(format!("{} contains no audio", wav_path.display()));
+    }
+
+    // Written under a temporary name, so a crash never leaves a partial file that `resolve` would pick
+    let flac_path = wav_path.with_extension("flac");
+    let partial_path = wav_path.with_extension("flac.part");
+    flac::write(&partial_path, &samples, spec.channels, spec.sample_rate, spec.bits_per_sample)?;
+
+    if let Err(e) = verify(&partial_path, &samples, spec.bits_per_sample) {
+        let _ = std::fs::remove_file(&partial_path);
+        return Err(e);
+    }
+
+    std::fs::rename(&partial_path, &flac_path)
+        .map_err(|e| format!("Failed to rename {}: {}", partial_path.display(), e))?;
+    Ok(flac_path)
+}
+
+fn verify(flac_path: &Path, samples: &[i32], bits_per_sample: u16) -> Result<(), String> {
+    let decoded = decode::decode(flac_path)?;
+    let scale = (1i64 << (bits_per_sample - 1)) as f32;
+
+    let identical = decoded.samples.len() == samples.len()
+        && decoded.samples.iter().zip(samples).all(|(&decoded, &original)| (decoded * scale).round() as i32 == original);
+    if !identical {
+        return Err(format!("{} does not decode to the original audio", flac_path.display()));
+    }
+    Ok(())
+}
+
+/// A FLAC recording as the bytes of a 16-bit WAV file, for playback in the webview.
+pub fn wav_bytes(flac_path: &Path) -> Result<Vec<u8>, String> {
+    let decoded = decode::decode(flac_path)?;
+    let spec = hound::WavSpec {
+        channels: decoded.info.channels,
+        sample_rate: decoded.info.sample_rate,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+
+    let mut bytes = std::io::Cursor::new(Vec::new());
+    let mut writer = hound::WavWriter::new(&mut bytes, spec)
+        .map_err(|e| format!("Failed to create WAV data: {}", e))?;
+    for &sample in &decoded.samples {
+        writer.write_sample((sample * 32768.0).round().clamp(-32768.0, 32767.0) as i16)
+            .map_err(|e| format!("Failed to write WAV data: {}", e))?;
+    }
+    writer.finalize().map_err(|e| format!("Failed to finalize WAV data: {}", e))?;
+
+    Ok(bytes.into_inner())
+}
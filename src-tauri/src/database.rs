@@ -3,6 +3,7 @@ use chrono::{DateTime, Local};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use uuid::Uuid;
+use crate::migrations;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Meeting {
@@ -16,6 +17,13 @@ pub struct Meeting {
     pub meeting_minutes: Option<String>,
     pub language: Option<String>,
     pub ai_provider: Option<String>, // "openai" or "ollama"
+    // Parsed from the KEY_TOPICS / SENTIMENT / ENERGY trailer of the minutes
+    #[serde(default)]
+    pub key_topics: Vec<String>,
+    #[serde(default)]
+    pub sentiment: Option<String>, // "Positive", "Neutral" or "Negative"
+    #[serde(default)]
+    pub energy: Option<String>, // "High", "Medium" or "Low"
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -26,64 +34,289 @@ pub struct MeetingSegment {
     pub end_time: f64,
     pub text: String,
     pub confidence: Option<f64>,
+    #[serde(default)]
+    pub speaker: Option<String>, // Diarization label, renamable per meeting
+    #[serde(default)]
+    pub source: Option<String>, // "local" (microphone) or "remote" (system audio)
+}
+
+impl MeetingSegment {
+    /// Who spoke, for transcripts and prompts, e.g. "Alice (remote)" or "Local".
+    pub fn speaker_label(&self) -> Option<String> {
+        match (&self.speaker, &self.source) {
+            (Some(speaker), Some(source)) => Some(format!("{} ({})", speaker, source)),
+            (Some(speaker), None) => Some(speaker.clone()),
+            (None, Some(source)) => {
+                let mut chars = source.chars();
+                chars.next().map(|first| first.to_uppercase().chain(chars).collect())
+            }
+            (None, None) => None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ActionItem {
+    pub id: String,
+    pub meeting_id: String,
+    pub meeting_title: Option<String>, // Filled in from the meetings table when loading
+    pub description: String,
+    pub owner: Option<String>,
+    pub due_date: Option<String>, // "YYYY-MM-DD" when known, otherwise as stated in the meeting
+    pub status: String, // "open" or "done"
+    pub source_time: Option<f64>, // Seconds into the recording where the item was mentioned
+    pub created_at: DateTime<Local>,
+    pub completed_at: Option<DateTime<Local>>,
+}
+
+impl ActionItem {
+    pub fn new(meeting_id: String, description: String, owner: Option<String>, due_date: Option<String>, source_time: Option<f64>) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            meeting_id,
+            meeting_title: None,
+            description,
+            owner,
+            due_date,
+            status: "open".to_string(),
+            source_time,
+            created_at: Local::now(),
+            completed_at: None,
+        }
+    }
+}
+
+/// A break in a recording. Paused audio is not written, so segment times
+/// continue from `audio_offset` after the pause.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RecordingPause {
+    pub meeting_id: String,
+    pub audio_offset: f64, // Seconds into the recording where the pause happened
+    pub paused_at: DateTime<Local>,
+    pub resumed_at: Option<DateTime<Local>>, // None while still paused
+}
+
+impl RecordingPause {
+    pub fn duration_seconds(&self) -> f64 {
+        let end = self.resumed_at.unwrap_or_else(Local::now);
+        (end - self.paused_at).num_milliseconds().max(0) as f64 / 1000.0
+    }
+}
+
+/// A word of a segment, with Whisper's timing and how sure it was of the word.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SegmentWord {
+    pub segment_id: String,
+    pub meeting_id: String,
+    pub start_time: f64,
+    pub end_time: f64,
+    pub text: String,
+    pub probability: f64, // Of the word's least likely token, 0-1
+}
+
+/// A word or name Whisper should spell correctly. Transcripts are corrected
+/// by replacing any of the `variants` with `term`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GlossaryTerm {
+    pub id: String,
+    pub meeting_id: Option<String>, // None for terms used in every meeting
+    pub term: String,
+    pub variants: Vec<String>, // Misrecognitions, e.g. "cube control" for "kubectl"
+    pub created_at: DateTime<Local>,
+}
+
+impl GlossaryTerm {
+    pub fn new(meeting_id: Option<String>, term: String, variants: Vec<String>) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            meeting_id,
+            term,
+            variants,
+            created_at: Local::now(),
+        }
+    }
+}
+
+/// A glossary correction applied to one segment, kept so it can be reviewed and undone.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TranscriptCorrection {
+    pub id: String,
+    pub meeting_id: String,
+    pub segment_id: String,
+    pub term_id: Option<String>, // None once the glossary term is deleted
+    pub term: String,
+    pub original_text: String,
+    pub corrected_text: String,
+    pub created_at: DateTime<Local>,
+    pub reverted_at: Option<DateTime<Local>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SearchResult {
+    pub meeting: Meeting,
+    pub segment_id: Option<String>, // None when the hit is in the title, transcript or minutes
+    pub start_time: Option<f64>,
+    pub end_time: Option<f64>,
+    pub snippet: String, // Matched terms are wrapped in <mark></mark>
+    pub rank: f64, // -1 for the best hits down to 0, comparable across titles, segments and topics
+}
+
+struct SearchHit {
+    segment_id: Option<String>,
+    start_time: Option<f64>,
+    end_time: Option<f64>,
+    snippet: String,
+    rank: f64,
+}
+
+const SEARCH_LIMIT: i64 = 100;
+
+/// Turn free text into an FTS5 query: every word must match, as a prefix.
+/// Words are quoted so punctuation and FTS5 operators are taken literally.
+fn fts_query(query: &str) -> Option<String> {
+    let terms: Vec<String> = query
+        .split_whitespace()
+        .filter(|word| word.chars().any(|c| c.is_alphanumeric()))
+        .map(|word| format!("\"{}\"*", word.replace('"', "\"\"")))
+        .collect();
+
+    if terms.is_empty() {
+        None
+    } else {
+        Some(terms.join(" "))
+    }
+}
+
+/// Escape `%`, `_` and `\` for a `LIKE ... ESCAPE '\'` pattern.
+fn escape_like(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '%' | '_' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Scale bm25 scores, which are only comparable within one FTS table, to -1..0:
+/// -1 for the best hit of the table, towards 0 for weaker ones.
+fn normalize_ranks(hits: &mut [(String, SearchHit)]) {
+    let best = hits.iter().map(|(_, hit)| hit.rank).fold(0.0, f64::min);
+    for (_, hit) in hits.iter_mut() {
+        hit.rank = if best < 0.0 { -(hit.rank / best) } else { -1.0 };
+    }
 }
 
 pub struct Database {
     conn: Connection,
 }
 
-impl Database {
-    pub fn new(db_path: PathBuf) -> Result<Self> {
-        let conn = Connection::open(db_path)?;
-        let db = Database { conn };
-        db.init_tables()?;
-        Ok(db)
-    }
+// Columns selected for every Meeting query; topics are folded into one string
+// separated by the ASCII unit separator and split again in meeting_from_row
+const MEETING_COLUMNS: &str = "id, title, created_at, updated_at, duration_seconds,
+    audio_file_path, transcript, meeting_minutes, language, ai_provider,
+    sentiment, energy,
+    (SELECT group_concat(topic, char(31) ORDER BY position) FROM meeting_topics WHERE meeting_topics.meeting_id = meetings.id)";
 
-    fn init_tables(&self) -> Result<()> {
-        // Create meetings table
-        self.conn.execute(
-            "CREATE TABLE IF NOT EXISTS meetings (
-                id TEXT PRIMARY KEY,
-                title TEXT NOT NULL,
-                created_at TEXT NOT NULL,
-                updated_at TEXT NOT NULL,
-                duration_seconds INTEGER,
-                audio_file_path TEXT,
-                transcript TEXT,
-                meeting_minutes TEXT,
-                language TEXT,
-                ai_provider TEXT
-            )",
-            [],
-        )?;
+const GLOSSARY_TERM_COLUMNS: &str = "id, meeting_id, term, variants, created_at";
 
-        // Create meeting_segments table for detailed transcription segments
-        self.conn.execute(
-            "CREATE TABLE IF NOT EXISTS meeting_segments (
-                id TEXT PRIMARY KEY,
-                meeting_id TEXT NOT NULL,
-                start_time REAL NOT NULL,
-                end_time REAL NOT NULL,
-                text TEXT NOT NULL,
-                confidence REAL,
-                FOREIGN KEY(meeting_id) REFERENCES meetings(id) ON DELETE CASCADE
-            )",
-            [],
-        )?;
+const CORRECTION_COLUMNS: &str = "id, meeting_id, segment_id, term_id, term, original_text, corrected_text, created_at, reverted_at";
 
-        // Create indexes for better performance
-        self.conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_meetings_created_at ON meetings(created_at)",
-            [],
-        )?;
+const ACTION_ITEM_COLUMNS: &str = "a.id, a.meeting_id, m.title, a.description, a.owner, a.due_date,
+    a.status, a.source_time, a.created_at, a.completed_at";
 
-        self.conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_segments_meeting_id ON meeting_segments(meeting_id)",
-            [],
-        )?;
+fn parse_timestamp(value: &str, column: usize, name: &str) -> Result<DateTime<Local>> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|dt| dt.with_timezone(&Local))
+        .map_err(|_| rusqlite::Error::InvalidColumnType(column, name.to_string(), rusqlite::types::Type::Text))
+}
 
-        Ok(())
+fn action_item_from_row(row: &rusqlite::Row) -> Result<ActionItem> {
+    let completed_at: Option<String> = row.get(9)?;
+
+    Ok(ActionItem {
+        id: row.get(0)?,
+        meeting_id: row.get(1)?,
+        meeting_title: row.get(2)?,
+        description: row.get(3)?,
+        owner: row.get(4)?,
+        due_date: row.get(5)?,
+        status: row.get(6)?,
+        source_time: row.get(7)?,
+        created_at: parse_timestamp(&row.get::<_, String>(8)?, 8, "created_at")?,
+        completed_at: completed_at
+            .map(|value| parse_timestamp(&value, 9, "completed_at"))
+            .transpose()?,
+    })
+}
+
+// Variants are stored separated by the ASCII unit separator, like topics
+fn glossary_term_from_row(row: &rusqlite::Row) -> Result<GlossaryTerm> {
+    let variants: String = row.get(3)?;
+
+    Ok(GlossaryTerm {
+        id: row.get(0)?,
+        meeting_id: row.get(1)?,
+        term: row.get(2)?,
+        variants: variants.split('\u{1f}').filter(|v| !v.is_empty()).map(|v| v.to_string()).collect(),
+        created_at: parse_timestamp(&row.get::<_, String>(4)?, 4, "created_at")?,
+    })
+}
+
+fn correction_from_row(row: &rusqlite::Row) -> Result<TranscriptCorrection> {
+    let reverted_at: Option<String> = row.get(8)?;
+
+    Ok(TranscriptCorrection {
+        id: row.get(0)?,
+        meeting_id: row.get(1)?,
+        segment_id: row.get(2)?,
+        term_id: row.get(3)?,
+        term: row.get(4)?,
+        original_text: row.get(5)?,
+        corrected_text: row.get(6)?,
+        created_at: parse_timestamp(&row.get::<_, String>(7)?, 7, "created_at")?,
+        reverted_at: reverted_at
+            .map(|value| parse_timestamp(&value, 8, "reverted_at"))
+            .transpose()?,
+    })
+}
+
+fn meeting_from_row(row: &rusqlite::Row) -> Result<Meeting> {
+    let key_topics: Option<String> = row.get(12)?;
+
+    Ok(Meeting {
+        id: row.get(0)?,
+        title: row.get(1)?,
+        created_at: DateTime::parse_from_rfc3339(&row.get::<_, String>(2)?)
+            .map_err(|_| rusqlite::Error::InvalidColumnType(2, "created_at".to_string(), rusqlite::types::Type::Text))?
+            .with_timezone(&Local),
+        updated_at: DateTime::parse_from_rfc3339(&row.get::<_, String>(3)?)
+            .map_err(|_| rusqlite::Error::InvalidColumnType(3, "updated_at".to_string(), rusqlite::types::Type::Text))?
+            .with_timezone(&Local),
+        duration_seconds: row.get(4)?,
+        audio_file_path: row.get(5)?,
+        transcript: row.get(6)?,
+        meeting_minutes: row.get(7)?,
+        language: row.get(8)?,
+        ai_provider: row.get(9)?,
+        sentiment: row.get(10)?,
+        energy: row.get(11)?,
+        key_topics: key_topics
+            .map(|topics| topics.split('\u{1f}').map(|t| t.to_string()).collect())
+            .unwrap_or_default(),
+    })
+}
+
+impl Database {
+    pub fn new(db_path: PathBuf) -> std::result::Result<Self, String> {
+        let mut conn = Connection::open(&db_path)
+            .map_err(|e| format!("Failed to open database: {}", e))?;
+        migrations::migrate(&mut conn, &db_path)?;
+        // Off by default in SQLite; the schema relies on ON DELETE CASCADE.
+        conn.pragma_update(None, "foreign_keys", true)
+            .map_err(|e| format!("Failed to enable foreign keys: {}", e))?;
+        Ok(Database { conn })
     }
 
     pub fn create_meeting(&self, title: String, language: Option<String>) -> Result<Meeting> {
@@ -101,6 +334,9 @@ impl Database {
             meeting_minutes: None,
             language,
             ai_provider: None,
+            key_topics: Vec::new(),
+            sentiment: None,
+            energy: None,
         };
 
         self.conn.execute(
@@ -144,8 +380,10 @@ impl Database {
                 transcript = ?5,
                 meeting_minutes = ?6,
                 language = ?7,
-                ai_provider = ?8
-             WHERE id = ?9",
+                ai_provider = ?8,
+                sentiment = ?9,
+                energy = ?10
+             WHERE id = ?11",
             params![
                 meeting.title,
                 updated_at.to_rfc3339(),
@@ -155,10 +393,14 @@ impl Database {
                 meeting.meeting_minutes,
                 meeting.language,
                 meeting.ai_provider,
+                meeting.sentiment,
+                meeting.energy,
                 meeting.id
             ],
         )?;
 
+        self.set_meeting_topics(&meeting.id, &meeting.key_topics)?;
+
         println!("✅ Database update completed, rows affected: {}", rows_affected);
         
         // Check what's in the database after the update
@@ -174,29 +416,10 @@ impl Database {
 
     pub fn get_meeting(&self, id: &str) -> Result<Option<Meeting>> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, title, created_at, updated_at, duration_seconds, 
-                    audio_file_path, transcript, meeting_minutes, language, ai_provider
-             FROM meetings WHERE id = ?1"
+            &format!("SELECT {} FROM meetings WHERE id = ?1", MEETING_COLUMNS)
         )?;
 
-        let meeting_iter = stmt.query_map([id], |row| {
-            Ok(Meeting {
-                id: row.get(0)?,
-                title: row.get(1)?,
-                created_at: DateTime::parse_from_rfc3339(&row.get::<_, String>(2)?)
-                    .map_err(|e| rusqlite::Error::InvalidColumnType(2, "created_at".to_string(), rusqlite::types::Type::Text))?
-                    .with_timezone(&Local),
-                updated_at: DateTime::parse_from_rfc3339(&row.get::<_, String>(3)?)
-                    .map_err(|e| rusqlite::Error::InvalidColumnType(3, "updated_at".to_string(), rusqlite::types::Type::Text))?
-                    .with_timezone(&Local),
-                duration_seconds: row.get(4)?,
-                audio_file_path: row.get(5)?,
-                transcript: row.get(6)?,
-                meeting_minutes: row.get(7)?,
-                language: row.get(8)?,
-                ai_provider: row.get(9)?,
-            })
-        })?;
+        let meeting_iter = stmt.query_map([id], meeting_from_row)?;
 
         for meeting in meeting_iter {
             return Ok(Some(meeting?));
@@ -207,29 +430,10 @@ impl Database {
 
     pub fn get_all_meetings(&self) -> Result<Vec<Meeting>> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, title, created_at, updated_at, duration_seconds, 
-                    audio_file_path, transcript, meeting_minutes, language, ai_provider
-             FROM meetings ORDER BY created_at DESC"
+            &format!("SELECT {} FROM meetings ORDER BY created_at DESC", MEETING_COLUMNS)
         )?;
 
-        let meeting_iter = stmt.query_map([], |row| {
-            Ok(Meeting {
-                id: row.get(0)?,
-                title: row.get(1)?,
-                created_at: DateTime::parse_from_rfc3339(&row.get::<_, String>(2)?)
-                    .map_err(|e| rusqlite::Error::InvalidColumnType(2, "created_at".to_string(), rusqlite::types::Type::Text))?
-                    .with_timezone(&Local),
-                updated_at: DateTime::parse_from_rfc3339(&row.get::<_, String>(3)?)
-                    .map_err(|e| rusqlite::Error::InvalidColumnType(3, "updated_at".to_string(), rusqlite::types::Type::Text))?
-                    .with_timezone(&Local),
-                duration_seconds: row.get(4)?,
-                audio_file_path: row.get(5)?,
-                transcript: row.get(6)?,
-                meeting_minutes: row.get(7)?,
-                language: row.get(8)?,
-                ai_provider: row.get(9)?,
-            })
-        })?;
+        let meeting_iter = stmt.query_map([], meeting_from_row)?;
 
         let mut meetings = Vec::new();
         for meeting in meeting_iter {
@@ -239,6 +443,37 @@ impl Database {
         Ok(meetings)
     }
 
+    fn set_meeting_topics(&self, meeting_id: &str, topics: &[String]) -> Result<()> {
+        self.conn.execute("DELETE FROM meeting_topics WHERE meeting_id = ?1", [meeting_id])?;
+
+        for (position, topic) in topics.iter().enumerate() {
+            self.conn.execute(
+                "INSERT OR IGNORE INTO meeting_topics (meeting_id, topic, position) VALUES (?1, ?2, ?3)",
+                params![meeting_id, topic, position as i64],
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// All distinct topics with the number of meetings tagged with each.
+    pub fn get_all_topics(&self) -> Result<Vec<(String, i64)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT topic, COUNT(*) FROM meeting_topics
+             GROUP BY topic COLLATE NOCASE
+             ORDER BY COUNT(*) DESC, topic COLLATE NOCASE"
+        )?;
+
+        let topic_iter = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+
+        let mut topics = Vec::new();
+        for topic in topic_iter {
+            topics.push(topic?);
+        }
+
+        Ok(topics)
+    }
+
     pub fn delete_meeting(&self, id: &str) -> Result<()> {
         self.conn.execute("DELETE FROM meetings WHERE id = ?1", [id])?;
         Ok(())
@@ -246,32 +481,46 @@ impl Database {
 
     pub fn add_meeting_segment(&self, segment: &MeetingSegment) -> Result<()> {
         self.conn.execute(
-            "INSERT INTO meeting_segments (id, meeting_id, start_time, end_time, text, confidence)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            "INSERT INTO meeting_segments (id, meeting_id, start_time, end_time, text, confidence, speaker, source)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
             params![
                 segment.id,
                 segment.meeting_id,
                 segment.start_time,
                 segment.end_time,
                 segment.text,
-                segment.confidence
+                segment.confidence,
+                segment.speaker,
+                segment.source
             ],
         )?;
 
         Ok(())
     }
 
+    /// Delete the segments of a meeting, their words and the corrections made to them.
     pub fn delete_meeting_segments(&self, meeting_id: &str) -> Result<()> {
         self.conn.execute(
             "DELETE FROM meeting_segments WHERE meeting_id = ?1",
             [meeting_id],
         )?;
+        self.conn.execute(
+            "DELETE FROM transcript_corrections WHERE meeting_id = ?1",
+            [meeting_id],
+        )?;
         Ok(())
     }
 
+    pub fn update_segment_text(&self, segment_id: &str, text: &str) -> Result<usize> {
+        self.conn.execute(
+            "UPDATE meeting_segments SET text = ?1 WHERE id = ?2",
+            params![text, segment_id],
+        )
+    }
+
     pub fn get_meeting_segments(&self, meeting_id: &str) -> Result<Vec<MeetingSegment>> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, meeting_id, start_time, end_time, text, confidence
+            "SELECT id, meeting_id, start_time, end_time, text, confidence, speaker, source
              FROM meeting_segments WHERE meeting_id = ?1 ORDER BY start_time"
         )?;
 
@@ -283,6 +532,8 @@ impl Database {
                 end_time: row.get(3)?,
                 text: row.get(4)?,
                 confidence: row.get(5)?,
+                speaker: row.get(6)?,
+                source: row.get(7)?,
             })
         })?;
 
@@ -294,40 +545,621 @@ impl Database {
         Ok(segments)
     }
 
-    pub fn search_meetings(&self, query: &str) -> Result<Vec<Meeting>> {
-        let search_query = format!("%{}%", query);
+    /// Store the words of one segment, in order.
+    pub fn add_segment_words(&self, words: &[SegmentWord]) -> Result<()> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, title, created_at, updated_at, duration_seconds, 
-                    audio_file_path, transcript, meeting_minutes, language, ai_provider
-             FROM meetings 
-             WHERE title LIKE ?1 OR transcript LIKE ?1 OR meeting_minutes LIKE ?1
-             ORDER BY created_at DESC"
+            "INSERT INTO segment_words (segment_id, meeting_id, position, start_time, end_time, text, probability)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)"
         )?;
 
-        let meeting_iter = stmt.query_map([&search_query], |row| {
-            Ok(Meeting {
-                id: row.get(0)?,
-                title: row.get(1)?,
-                created_at: DateTime::parse_from_rfc3339(&row.get::<_, String>(2)?)
-                    .map_err(|e| rusqlite::Error::InvalidColumnType(2, "created_at".to_string(), rusqlite::types::Type::Text))?
-                    .with_timezone(&Local),
-                updated_at: DateTime::parse_from_rfc3339(&row.get::<_, String>(3)?)
-                    .map_err(|e| rusqlite::Error::InvalidColumnType(3, "updated_at".to_string(), rusqlite::types::Type::Text))?
-                    .with_timezone(&Local),
-                duration_seconds: row.get(4)?,
-                audio_file_path: row.get(5)?,
-                transcript: row.get(6)?,
-                meeting_minutes: row.get(7)?,
-                language: row.get(8)?,
-                ai_provider: row.get(9)?,
+        for (position, word) in words.iter().enumerate() {
+            stmt.execute(params![
+                word.segment_id,
+                word.meeting_id,
+                position as i64,
+                word.start_time,
+                word.end_time,
+                word.text,
+                word.probability
+            ])?;
+        }
+
+        Ok(())
+    }
+
+    /// Replace the words of a segment, e.g. after its text was corrected.
+    pub fn set_segment_words(&self, segment_id: &str, words: &[SegmentWord]) -> Result<()> {
+        self.conn.execute("DELETE FROM segment_words WHERE segment_id = ?1", [segment_id])?;
+        self.add_segment_words(words)
+    }
+
+    /// Words of a meeting in transcript order. With `max_probability`, only
+    /// the words Whisper was less sure of.
+    pub fn get_meeting_words(&self, meeting_id: &str, max_probability: Option<f64>) -> Result<Vec<SegmentWord>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT w.segment_id, w.meeting_id, w.start_time, w.end_time, w.text, w.probability
+             FROM segment_words w JOIN meeting_segments s ON s.id = w.segment_id
+             WHERE w.meeting_id = ?1 AND (?2 IS NULL OR w.probability < ?2)
+             ORDER BY s.start_time, w.segment_id, w.position"
+        )?;
+
+        let word_iter = stmt.query_map(params![meeting_id, max_probability], |row| {
+            Ok(SegmentWord {
+                segment_id: row.get(0)?,
+                meeting_id: row.get(1)?,
+                start_time: row.get(2)?,
+                end_time: row.get(3)?,
+                text: row.get(4)?,
+                probability: row.get(5)?,
             })
         })?;
 
-        let mut meetings = Vec::new();
-        for meeting in meeting_iter {
-            meetings.push(meeting?);
+        let mut words = Vec::new();
+        for word in word_iter {
+            words.push(word?);
         }
 
-        Ok(meetings)
+        Ok(words)
     }
-}
\ No newline at end of file
+
+    /// Speakers of a meeting with their number of segments, in order of first appearance.
+    pub fn get_meeting_speakers(&self, meeting_id: &str) -> Result<Vec<(String, i64)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT speaker, COUNT(*) FROM meeting_segments
+             WHERE meeting_id = ?1 AND speaker IS NOT NULL
+             GROUP BY speaker
+             ORDER BY MIN(start_time)"
+        )?;
+
+        let speaker_iter = stmt.query_map([meeting_id], |row| Ok((row.get(0)?, row.get(1)?)))?;
+
+        let mut speakers = Vec::new();
+        for speaker in speaker_iter {
+            speakers.push(speaker?);
+        }
+
+        Ok(speakers)
+    }
+
+    /// Rename a speaker in one meeting. Renaming to an existing name merges the two.
+    pub fn rename_speaker(&self, meeting_id: &str, speaker: &str, new_name: &str) -> Result<usize> {
+        self.conn.execute(
+            "UPDATE meeting_segments SET speaker = ?1 WHERE meeting_id = ?2 AND speaker = ?3",
+            params![new_name, meeting_id, speaker],
+        )
+    }
+
+    /// Full-text search over titles, transcripts, minutes and segments, best match first.
+    ///
+    /// Every matching segment is its own hit so the caller can seek to it; meetings
+    /// that only match on their title, transcript or minutes get one meeting-level hit.
+    pub fn search_meetings(&self, query: &str) -> Result<Vec<SearchResult>> {
+        let Some(fts_query) = fts_query(query) else {
+            return Ok(Vec::new());
+        };
+
+        let mut segment_hits: Vec<(String, SearchHit)> = Vec::new();
+        let mut stmt = self.conn.prepare(
+            "SELECT s.meeting_id, s.id, s.start_time, s.end_time,
+                    snippet(segments_fts, 1, '<mark>', '</mark>', '…', 16),
+                    bm25(segments_fts)
+             FROM segments_fts
+             JOIN meeting_segments s ON s.id = segments_fts.id
+             WHERE segments_fts MATCH ?1
+             ORDER BY bm25(segments_fts)
+             LIMIT ?2"
+        )?;
+        let segment_iter = stmt.query_map(params![fts_query, SEARCH_LIMIT], |row| {
+            Ok((row.get::<_, String>(0)?, SearchHit {
+                segment_id: row.get(1)?,
+                start_time: row.get(2)?,
+                end_time: row.get(3)?,
+                snippet: row.get(4)?,
+                rank: row.get(5)?,
+            }))
+        })?;
+        for hit in segment_iter {
+            segment_hits.push(hit?);
+        }
+        normalize_ranks(&mut segment_hits);
+
+        // Title matches weigh the most, then minutes, then the raw transcript; the id is not indexed
+        let mut meeting_hits: Vec<(String, SearchHit)> = Vec::new();
+        let mut stmt = self.conn.prepare(
+            "SELECT m.id, snippet(meetings_fts, -1, '<mark>', '</mark>', '…', 16),
+                    bm25(meetings_fts, 0.0, 10.0, 1.0, 2.0)
+             FROM meetings_fts
+             JOIN meetings m ON m.id = meetings_fts.id
+             WHERE meetings_fts MATCH ?1
+             ORDER BY bm25(meetings_fts, 0.0, 10.0, 1.0, 2.0)
+             LIMIT ?2"
+        )?;
+        let meeting_iter = stmt.query_map(params![fts_query, SEARCH_LIMIT], |row| {
+            Ok((row.get::<_, String>(0)?, SearchHit {
+                segment_id: None,
+                start_time: None,
+                end_time: None,
+                snippet: row.get(1)?,
+                rank: row.get(2)?,
+            }))
+        })?;
+        for hit in meeting_iter {
+            meeting_hits.push(hit?);
+        }
+        normalize_ranks(&mut meeting_hits);
+
+        let mut hits = segment_hits;
+        for (meeting_id, hit) in meeting_hits {
+            if !hits.iter().any(|(id, _)| *id == meeting_id) {
+                hits.push((meeting_id, hit));
+            }
+        }
+
+        // Topics are kept outside the FTS index and have no bm25 score: an exact topic
+        // ranks with the best hit of any source, a partial one halfway
+        let mut stmt = self.conn.prepare(
+            "SELECT meeting_id, topic FROM meeting_topics WHERE topic LIKE ?1 ESCAPE '\\' ORDER BY position"
+        )?;
+        let topic_iter = stmt.query_map([format!("%{}%", escape_like(query.trim()))], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?;
+        for topic in topic_iter {
+            let (meeting_id, topic) = topic?;
+            if !hits.iter().any(|(id, _)| *id == meeting_id) {
+                let rank = if topic.eq_ignore_ascii_case(query.trim()) { -1.0 } else { -0.5 };
+                hits.push((meeting_id, SearchHit {
+                    segment_id: None,
+                    start_time: None,
+                    end_time: None,
+                    snippet: format!("Topic: <mark>{}</mark>", topic),
+                    rank,
+                }));
+            }
+        }
+
+        // Lower is a better match; the sort is stable, so ties keep segments first
+        hits.sort_by(|a, b| a.1.rank.total_cmp(&b.1.rank));
+
+        let mut meetings: std::collections::HashMap<String, Meeting> = std::collections::HashMap::new();
+        let mut results = Vec::new();
+        for (meeting_id, hit) in hits {
+            if !meetings.contains_key(&meeting_id) {
+                match self.get_meeting(&meeting_id)? {
+                    Some(meeting) => { meetings.insert(meeting_id.clone(), meeting); }
+                    None => continue,
+                }
+            }
+
+            results.push(SearchResult {
+                meeting: meetings[&meeting_id].clone(),
+                segment_id: hit.segment_id,
+                start_time: hit.start_time,
+                end_time: hit.end_time,
+                snippet: hit.snippet,
+                rank: hit.rank,
+            });
+        }
+
+        Ok(results)
+    }
+
+    /// Replace the extracted action items of a meeting. Items that were already
+    /// marked done keep their status when they are extracted again.
+    pub fn replace_action_items(&self, meeting_id: &str, items: &[ActionItem]) -> Result<Vec<ActionItem>> {
+        let previous = self.get_action_items(meeting_id)?;
+
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute("DELETE FROM action_items WHERE meeting_id = ?1", [meeting_id])?;
+
+        for item in items {
+            let done = previous.iter().find(|p| {
+                p.status == "done" && p.description.trim().eq_ignore_ascii_case(item.description.trim())
+            });
+
+            tx.execute(
+                "INSERT INTO action_items (id, meeting_id, description, owner, due_date, status, source_time, created_at, completed_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                params![
+                    item.id,
+                    meeting_id,
+                    item.description,
+                    item.owner,
+                    item.due_date,
+                    done.map(|p| p.status.as_str()).unwrap_or(&item.status),
+                    item.source_time,
+                    item.created_at.to_rfc3339(),
+                    done.and_then(|p| p.completed_at).or(item.completed_at).map(|dt| dt.to_rfc3339())
+                ],
+            )?;
+        }
+
+        tx.commit()?;
+        self.get_action_items(meeting_id)
+    }
+
+    pub fn get_action_items(&self, meeting_id: &str) -> Result<Vec<ActionItem>> {
+        let mut stmt = self.conn.prepare(
+            &format!("SELECT {} FROM action_items a JOIN meetings m ON m.id = a.meeting_id
+             WHERE a.meeting_id = ?1
+             ORDER BY a.source_time IS NULL, a.source_time, a.created_at", ACTION_ITEM_COLUMNS)
+        )?;
+
+        let item_iter = stmt.query_map([meeting_id], action_item_from_row)?;
+
+        let mut items = Vec::new();
+        for item in item_iter {
+            items.push(item?);
+        }
+
+        Ok(items)
+    }
+
+    /// Open action items across all meetings, soonest due date first.
+    pub fn get_open_action_items(&self) -> Result<Vec<ActionItem>> {
+        let mut stmt = self.conn.prepare(
+            &format!("SELECT {} FROM action_items a JOIN meetings m ON m.id = a.meeting_id
+             WHERE a.status = 'open'
+             ORDER BY a.due_date IS NULL, a.due_date, m.created_at, a.source_time", ACTION_ITEM_COLUMNS)
+        )?;
+
+        let item_iter = stmt.query_map([], action_item_from_row)?;
+
+        let mut items = Vec::new();
+        for item in item_iter {
+            items.push(item?);
+        }
+
+        Ok(items)
+    }
+
+    pub fn set_action_item_status(&self, id: &str, status: &str) -> Result<Option<ActionItem>> {
+        let completed_at = (status == "done").then(|| Local::now().to_rfc3339());
+
+        let rows_affected = self.conn.execute(
+            "UPDATE action_items SET status = ?1, completed_at = ?2 WHERE id = ?3",
+            params![status, completed_at, id],
+        )?;
+
+        if rows_affected == 0 {
+            return Ok(None);
+        }
+
+        let mut stmt = self.conn.prepare(
+            &format!("SELECT {} FROM action_items a JOIN meetings m ON m.id = a.meeting_id WHERE a.id = ?1", ACTION_ITEM_COLUMNS)
+        )?;
+        let mut item_iter = stmt.query_map([id], action_item_from_row)?;
+
+        item_iter.next().transpose()
+    }
+
+    pub fn add_recording_pause(&self, pause: &RecordingPause) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO recording_pauses (meeting_id, audio_offset, paused_at, resumed_at)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![
+                pause.meeting_id,
+                pause.audio_offset,
+                pause.paused_at.to_rfc3339(),
+                pause.resumed_at.map(|dt| dt.to_rfc3339())
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Record when the open pause of a meeting ended.
+    pub fn end_recording_pause(&self, meeting_id: &str, resumed_at: DateTime<Local>) -> Result<usize> {
+        self.conn.execute(
+            "UPDATE recording_pauses SET resumed_at = ?1 WHERE meeting_id = ?2 AND resumed_at IS NULL",
+            params![resumed_at.to_rfc3339(), meeting_id],
+        )
+    }
+
+    pub fn get_recording_pauses(&self, meeting_id: &str) -> Result<Vec<RecordingPause>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT meeting_id, audio_offset, paused_at, resumed_at
+             FROM recording_pauses WHERE meeting_id = ?1 ORDER BY audio_offset, paused_at"
+        )?;
+
+        let pause_iter = stmt.query_map([meeting_id], |row| {
+            let paused_at: String = row.get(2)?;
+            let resumed_at: Option<String> = row.get(3)?;
+            Ok(RecordingPause {
+                meeting_id: row.get(0)?,
+                audio_offset: row.get(1)?,
+                paused_at: parse_timestamp(&paused_at, 2, "paused_at")?,
+                resumed_at: resumed_at.map(|value| parse_timestamp(&value, 3, "resumed_at")).transpose()?,
+            })
+        })?;
+
+        let mut pauses = Vec::new();
+        for pause in pause_iter {
+            pauses.push(pause?);
+        }
+
+        Ok(pauses)
+    }
+
+    pub fn get_setting(&self, key: &str) -> Result<Option<String>> {
+        let mut stmt = self.conn.prepare("SELECT value FROM settings WHERE key = ?1")?;
+        let mut rows = stmt.query_map([key], |row| row.get(0))?;
+        rows.next().transpose()
+    }
+
+    pub fn set_setting(&self, key: &str, value: &str) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO settings (key, value) VALUES (?1, ?2)
+             ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+            params![key, value],
+        )?;
+        Ok(())
+    }
+
+    pub fn delete_setting(&self, key: &str) -> Result<()> {
+        self.conn.execute("DELETE FROM settings WHERE key = ?1", [key])?;
+        Ok(())
+    }
+
+    pub fn add_glossary_term(&self, term: &GlossaryTerm) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO glossary_terms (id, meeting_id, term, variants, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                term.id,
+                term.meeting_id,
+                term.term,
+                term.variants.join("\u{1f}"),
+                term.created_at.to_rfc3339()
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Delete a glossary term. Corrections it made stay, without the link to the term.
+    pub fn delete_glossary_term(&self, id: &str) -> Result<usize> {
+        self.conn.execute("UPDATE transcript_corrections SET term_id = NULL WHERE term_id = ?1", [id])?;
+        self.conn.execute("DELETE FROM glossary_terms WHERE id = ?1", [id])
+    }
+
+    /// Terms for every meeting, plus those of `meeting_id` if given; shared terms first.
+    pub fn get_glossary_terms(&self, meeting_id: Option<&str>) -> Result<Vec<GlossaryTerm>> {
+        let mut stmt = self.conn.prepare(
+            &format!("SELECT {} FROM glossary_terms
+             WHERE meeting_id IS NULL OR meeting_id = ?1
+             ORDER BY meeting_id IS NOT NULL, term COLLATE NOCASE", GLOSSARY_TERM_COLUMNS)
+        )?;
+
+        let term_iter = stmt.query_map([meeting_id], glossary_term_from_row)?;
+
+        let mut terms = Vec::new();
+        for term in term_iter {
+            terms.push(term?);
+        }
+
+        Ok(terms)
+    }
+
+    pub fn add_transcript_correction(&self, correction: &TranscriptCorrection) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO transcript_corrections (id, meeting_id, segment_id, term_id, term, original_text, corrected_text, created_at, reverted_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                correction.id,
+                correction.meeting_id,
+                correction.segment_id,
+                correction.term_id,
+                correction.term,
+                correction.original_text,
+                correction.corrected_text,
+                correction.created_at.to_rfc3339(),
+                correction.reverted_at.map(|dt| dt.to_rfc3339())
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Corrections made to a meeting's transcript, oldest first, including reverted ones.
+    pub fn get_transcript_corrections(&self, meeting_id: &str) -> Result<Vec<TranscriptCorrection>> {
+        let mut stmt = self.conn.prepare(
+            &format!("SELECT {} FROM transcript_corrections WHERE meeting_id = ?1 ORDER BY created_at", CORRECTION_COLUMNS)
+        )?;
+
+        let correction_iter = stmt.query_map([meeting_id], correction_from_row)?;
+
+        let mut corrections = Vec::new();
+        for correction in correction_iter {
+            corrections.push(correction?);
+        }
+
+        Ok(corrections)
+    }
+
+    pub fn get_transcript_correction(&self, id: &str) -> Result<Option<TranscriptCorrection>> {
+        let mut stmt = self.conn.prepare(
+            &format!("SELECT {} FROM transcript_corrections WHERE id = ?1", CORRECTION_COLUMNS)
+        )?;
+        let mut correction_iter = stmt.query_map([id], correction_from_row)?;
+
+        correction_iter.next().transpose()
+    }
+
+    pub fn set_correction_reverted(&self, id: &str) -> Result<usize> {
+        self.conn.execute(
+            "UPDATE transcript_corrections SET reverted_at = ?1 WHERE id = ?2",
+            params![Local::now().to_rfc3339(), id],
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(meeting_id: &str, start_time: f64, text: &str) -> MeetingSegment {
+        MeetingSegment {
+            id: Uuid::new_v4().to_string(),
+            meeting_id: meeting_id.to_string(),
+            start_time,
+            end_time: start_time + 2.0,
+            text: text.to_string(),
+            confidence: None,
+            speaker: None,
+            source: None,
+        }
+    }
+
+    fn meeting_with_topics(db: &Database, title: &str, topics: &[&str]) -> Meeting {
+        let mut meeting = db.create_meeting(title.to_string(), None).unwrap();
+        meeting.key_topics = topics.iter().map(|topic| topic.to_string()).collect();
+        db.update_meeting(&meeting).unwrap();
+        meeting
+    }
+
+    #[test]
+    fn search_returns_segment_and_meeting_hits() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::new(dir.path().join("meetings.db")).unwrap();
+        let roadmap = meeting_with_topics(&db, "Roadmap planning", &[]);
+        let standup = meeting_with_topics(&db, "Daily standup", &[]);
+        let first = segment(&standup.id, 0.0, "The roadmap slipped by a week");
+        db.add_meeting_segment(&first).unwrap();
+        db.add_meeting_segment(&segment(&standup.id, 2.0, "Nothing else to report")).unwrap();
+
+        let results = db.search_meetings("roadmap").unwrap();
+
+        assert_eq!(results.len(), 2);
+        let segment_hit = results.iter().find(|r| r.meeting.id == standup.id).unwrap();
+        assert_eq!(segment_hit.segment_id.as_deref(), Some(first.id.as_str()));
+        assert_eq!(segment_hit.start_time, Some(0.0));
+        assert!(segment_hit.snippet.contains("<mark>roadmap</mark>"), "{}", segment_hit.snippet);
+        let title_hit = results.iter().find(|r| r.meeting.id == roadmap.id).unwrap();
+        assert_eq!(title_hit.segment_id, None);
+
+        // The best hit of each source is -1; none is weighted by another table's scores
+        assert_eq!(segment_hit.rank, -1.0);
+        assert_eq!(title_hit.rank, -1.0);
+    }
+
+    #[test]
+    fn search_ranks_are_normalized_per_source() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::new(dir.path().join("meetings.db")).unwrap();
+        let meeting = meeting_with_topics(&db, "Weekly sync", &[]);
+        db.add_meeting_segment(&segment(&meeting.id, 0.0, "Budget budget budget")).unwrap();
+        db.add_meeting_segment(&segment(&meeting.id, 2.0, "We talked about many things, one was the budget for the offsite")).unwrap();
+
+        let results = db.search_meetings("budget").unwrap();
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].rank, -1.0);
+        assert!(results[1].rank > -1.0 && results[1].rank < 0.0, "{}", results[1].rank);
+    }
+
+    #[test]
+    fn search_matches_topics_literally() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::new(dir.path().join("meetings.db")).unwrap();
+        let exact = meeting_with_topics(&db, "Planning", &["Q1_plan"]);
+        let partial = meeting_with_topics(&db, "Review", &["Q1_plan review"]);
+        meeting_with_topics(&db, "Other", &["Q1-plan"]);
+
+        let results = db.search_meetings("Q1_plan").unwrap();
+
+        let ids: Vec<&str> = results.iter().map(|r| r.meeting.id.as_str()).collect();
+        assert_eq!(ids, [exact.id.as_str(), partial.id.as_str()]);
+        assert_eq!(results[0].rank, -1.0);
+        assert_eq!(results[1].rank, -0.5);
+        assert_eq!(results[0].snippet, "Topic: <mark>Q1_plan</mark>");
+    }
+
+    #[test]
+    fn pauses_are_saved_open_and_closed_on_resume() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::new(dir.path().join("meetings.db")).unwrap();
+        let meeting = db.create_meeting("Standup".to_string(), None).unwrap();
+        let paused_at = Local::now();
+        db.add_recording_pause(&RecordingPause {
+            meeting_id: meeting.id.clone(),
+            audio_offset: 12.5,
+            paused_at,
+            resumed_at: None,
+        }).unwrap();
+
+        let pauses = db.get_recording_pauses(&meeting.id).unwrap();
+        assert_eq!(pauses.len(), 1);
+        assert_eq!(pauses[0].resumed_at, None);
+
+        let resumed_at = paused_at + chrono::Duration::seconds(30);
+        assert_eq!(db.end_recording_pause(&meeting.id, resumed_at).unwrap(), 1);
+        // Nothing left to close
+        assert_eq!(db.end_recording_pause(&meeting.id, resumed_at).unwrap(), 0);
+
+        let pauses = db.get_recording_pauses(&meeting.id).unwrap();
+        assert_eq!(pauses[0].audio_offset, 12.5);
+        assert_eq!(pauses[0].duration_seconds(), 30.0);
+    }
+
+    #[test]
+    fn set_segment_words_replaces_only_that_segment() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::new(dir.path().join("meetings.db")).unwrap();
+        let meeting = db.create_meeting("Standup".to_string(), None).unwrap();
+        let first = segment(&meeting.id, 0.0, "Cube Control");
+        let second = segment(&meeting.id, 2.0, "apply");
+        db.add_meeting_segment(&first).unwrap();
+        db.add_meeting_segment(&second).unwrap();
+        let word = |segment: &MeetingSegment, start_time: f64, text: &str| SegmentWord {
+            segment_id: segment.id.clone(),
+            meeting_id: meeting.id.clone(),
+            start_time,
+            end_time: start_time + 0.5,
+            text: text.to_string(),
+            probability: 0.5,
+        };
+        db.add_segment_words(&[word(&first, 0.0, "Cube"), word(&first, 0.5, "Control")]).unwrap();
+        db.add_segment_words(&[word(&second, 2.0, "apply")]).unwrap();
+
+        db.set_segment_words(&first.id, &[word(&first, 0.0, "kubectl")]).unwrap();
+
+        let texts: Vec<String> = db.get_meeting_words(&meeting.id, None).unwrap()
+            .into_iter()
+            .map(|word| word.text)
+            .collect();
+        assert_eq!(texts, vec!["kubectl", "apply"]);
+    }
+
+    #[test]
+    fn delete_meeting_removes_its_segments_words_and_search_hits() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::new(dir.path().join("meetings.db")).unwrap();
+        let meeting = db.create_meeting("Standup".to_string(), None).unwrap();
+        let kept = db.create_meeting("Retro".to_string(), None).unwrap();
+        let deleted = segment(&meeting.id, 0.0, "The roadmap slipped");
+        db.add_meeting_segment(&deleted).unwrap();
+        db.add_meeting_segment(&segment(&kept.id, 0.0, "The roadmap is fine")).unwrap();
+        db.add_segment_words(&[SegmentWord {
+            segment_id: deleted.id.clone(),
+            meeting_id: meeting.id.clone(),
+            start_time: 0.0,
+            end_time: 0.5,
+            text: "roadmap".to_string(),
+            probability: 0.5,
+        }]).unwrap();
+        db.replace_action_items(&meeting.id, &[ActionItem::new(meeting.id.clone(), "Fix the roadmap".to_string(), None, None, None)]).unwrap();
+
+        db.delete_meeting(&meeting.id).unwrap();
+
+        assert!(db.get_meeting_segments(&meeting.id).unwrap().is_empty());
+        assert!(db.get_meeting_words(&meeting.id, None).unwrap().is_empty());
+        assert!(db.get_action_items(&meeting.id).unwrap().is_empty());
+        let results = db.search_meetings("roadmap").unwrap();
+        let ids: Vec<&str> = results.iter().map(|r| r.meeting.id.as_str()).collect();
+        assert_eq!(ids, [kept.id.as_str()]);
+        assert_eq!(db.get_meeting_segments(&kept.id).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn escape_like_escapes_wildcards_and_the_escape_character() {
+        assert_eq!(escape_like("50%_off\\"), "50\\%\\_off\\\\");
+        assert_eq!(escape_like("plain"), "plain");
+    }
+}
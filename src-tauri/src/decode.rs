@@ -0,0 +1,289 @@
+// Audio file decoding for recordings and uploads.
+//
+// symphonia recognizes the container from the file contents (the extension is
+// only a hint), so WAV, MP3, M4A/AAC, OGG/Vorbis, FLAC and WebM/Matroska all
+// decode the same way. The first audio track is decoded to interleaved f32.
+//
+// symphonia has no Opus decoder yet, so Opus tracks (common in WebM and .opus
+// files) are rejected with an error that says so.
+
+use std::fs::File;
+use std::path::Path;
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::{Decoder, DecoderOptions, CODEC_TYPE_NULL, CODEC_TYPE_OPUS};
+use symphonia::core::errors::Error;
+use symphonia::core::formats::{FormatOptions, FormatReader};
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+
+/// What a file holds, read from its headers.
+#[derive(Debug, Clone)]
+pub struct AudioInfo {
+    pub codec: String, // symphonia's short codec name, e.g. "mp3" or "pcm_s16le"
+    pub sample_rate: u32,
+    pub channels: u16,
+    pub bits_per_sample: Option<u16>, // None for lossy codecs
+    pub duration_seconds: Option<f64>, // None when the headers don't say
+}
+
+/// Decoded samples, interleaved and in [-1, 1].
+pub struct DecodedAudio {
+    pub samples: Vec<f32>,
+    pub info: AudioInfo,
+}
+
+impl DecodedAudio {
+    pub fn duration_seconds(&self) -> f64 {
+        let frames = self.samples.len() / self.info.channels.max(1) as usize;
+        frames as f64 / self.info.sample_rate.max(1) as f64
+    }
+}
+
+struct OpenedAudio {
+    format: Box<dyn FormatReader>,
+    decoder: Box<dyn Decoder>,
+    track_id: u32,
+    info: AudioInfo,
+}
+
+fn open(path: &Path) -> Result<OpenedAudio, String> {
+    let file = File::open(path)
+        .map_err(|e| format!("Failed to open audio file: {}", e))?;
+    let stream = MediaSourceStream::new(Box::new(file), Default::default());
+
+    let mut hint = Hint::new();
+    if let Some(extension) = path.extension().and_then(|ext| ext.to_str()) {
+        hint.with_extension(extension);
+    }
+
+    let probed = symphonia::default::get_probe()
+        .format(&hint, stream, &FormatOptions::default(), &MetadataOptions::default())
+        .map_err(|e| format!("Unrecognized audio format: {}", e))?;
+    let format = probed.format;
+
+    let track = format.tracks().iter()
+        .find(|track| track.codec_params.codec != CODEC_TYPE_NULL)
+        .ok_or("The file has no audio track")?;
+    let params = &track.codec_params;
+
+    if params.codec == CODEC_TYPE_OPUS {
+        return Err("Opus audio is not supported yet. Please convert the file to MP3, M4A or WAV.".to_string());
+    }
+
+    let decoder = symphonia::default::get_codecs()
+        .make(params, &DecoderOptions::default())
+        .map_err(|e| format!("Unsupported audio codec: {}", e))?;
+
+    let sample_rate = params.sample_rate.unwrap_or(0);
+    let duration_seconds = params.n_frames.and_then(|frames| match params.time_base {
+        Some(time_base) => {
+            let time = time_base.calc_time(frames);
+            Some(time.seconds as f64 + time.frac)
+        }
+        None if sample_rate > 0 => Some(frames as f64 / sample_rate as f64),
+        None => None,
+    });
+
+    let info = AudioInfo {
+        codec: symphonia::default::get_codecs()
+            .get_codec(params.codec)
+            .map(|codec| codec.short_name.to_string())
+            .unwrap_or_else(|| "unknown".to_string()),
+        sample_rate,
+        channels: params.channels.map(|channels| channels.count() as u16).unwrap_or(0),
+        bits_per_sample: params.bits_per_sample.map(|bits| bits as u16),
+        duration_seconds,
+    };
+    let track_id = track.id;
+
+    Ok(OpenedAudio { format, decoder, track_id, info })
+}
+
+/// Read a file's format from its headers. Sample rate and channels that the
+/// headers leave out are taken from the first decoded packet.
+pub fn probe(path: &Path) -> Result<AudioInfo, String> {
+    let mut audio = open(path)?;
+    if audio.info.sample_rate == 0 || audio.info.channels == 0 {
+        decode_packets(&mut audio, true)?;
+    }
+    Ok(audio.info)
+}
+
+/// Decode the whole file.
+pub fn decode(path: &Path) -> Result<DecodedAudio, String> {
+    let mut audio = open(path)?;
+    let samples = decode_packets(&mut audio, false)?;
+
+    if audio.info.sample_rate == 0 || audio.info.channels == 0 {
+        return Err("The file contains no audio".to_string());
+    }
+
+    Ok(DecodedAudio { samples, info: audio.info })
+}
+
+/// Length of a file in seconds, decoding it when the headers don't say.
+pub fn duration_seconds(path: &Path) -> Result<f64, String> {
+    match probe(path)?.duration_seconds {
+        Some(duration) => Ok(duration),
+        None => Ok(decode(path)?.duration_seconds()),
+    }
+}
+
+/// Decode packets of the audio track, filling in the spec from the decoder output.
+/// A damaged packet is skipped rather than failing the whole file.
+fn decode_packets(audio: &mut OpenedAudio, first_only: bool) -> Result<Vec<f32>, String> {
+    let mut samples = Vec::new();
+    let mut skipped = 0;
+
+    loop {
+        let packet = match audio.format.next_packet() {
+            Ok(packet) => packet,
+            Err(Error::IoError(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => break,
+            // A chained stream starts; the audio so far is all we use
+            Err(Error::ResetRequired) => break,
+            Err(e) => return Err(format!("Failed to read audio: {}", e)),
+        };
+        if packet.track_id() != audio.track_id {
+            continue;
+        }
+
+        let decoded = match audio.decoder.decode(&packet) {
+            Ok(decoded) => decoded,
+            Err(Error::DecodeError(_)) => {
+                skipped += 1;
+                continue;
+            }
+            Err(e) => return Err(format!("Failed to decode audio: {}", e)),
+        };
+
+        let spec = *decoded.spec();
+        audio.info.sample_rate = spec.rate;
+        audio.info.channels = spec.channels.count() as u16;
+
+        let mut buffer = SampleBuffer::<f32>::new(decoded.capacity() as u64, spec);
+        buffer.copy_interleaved_ref(decoded);
+        samples.extend_from_slice(buffer.samples());
+
+        if first_only {
+            break;
+        }
+    }
+
+    if skipped > 0 {
+        eprintln!("⚠️ Skipped {} damaged audio packets", skipped);
+    }
+
+    Ok(samples)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    // Written by tests/fixtures/audio/generate.py: WAV and FLAC hold a 440 Hz
+    // sine at half scale, the lossy files hold silent frames.
+    fn fixture(name: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/audio").join(name)
+    }
+
+    /// Sample `frame` of `channel` as generate.py wrote it at 16 bits.
+    fn sine(frame: usize, channel: usize, rate: f64) -> f32 {
+        let phase = 2.0 * std::f64::consts::PI * 440.0 * frame as f64 / rate + channel as f64;
+        ((0.5 * 32767.0 * phase.sin()).round() / 32768.0) as f32
+    }
+
+    fn assert_info(info: &AudioInfo, codec: &str, sample_rate: u32, channels: u16) {
+        assert_eq!(info.codec, codec);
+        assert_eq!(info.sample_rate, sample_rate);
+        assert_eq!(info.channels, channels);
+    }
+
+    #[test]
+    fn wav_decodes_to_its_samples() {
+        let path = fixture("sine-16k-mono.wav");
+
+        let info = probe(&path).unwrap();
+        assert_info(&info, "pcm_s16le", 16000, 1);
+        assert_eq!(info.bits_per_sample, Some(16));
+        assert_eq!(info.duration_seconds, Some(0.25));
+
+        let audio = decode(&path).unwrap();
+        assert_eq!(audio.samples.len(), 4000);
+        for (frame, &sample) in audio.samples.iter().enumerate() {
+            assert_eq!(sample, sine(frame, 0, 16000.0), "frame {}", frame);
+        }
+        assert_eq!(duration_seconds(&path).unwrap(), 0.25);
+    }
+
+    #[test]
+    fn flac_decodes_both_channels_across_blocks() {
+        let path = fixture("sine-22k-stereo.flac");
+
+        let info = probe(&path).unwrap();
+        assert_info(&info, "flac", 22050, 2);
+        assert_eq!(info.bits_per_sample, Some(16));
+        assert_eq!(info.duration_seconds, Some(0.2));
+
+        // A 4096-frame block and a 314-frame one
+        let audio = decode(&path).unwrap();
+        assert_eq!(audio.samples.len(), 2 * 4410);
+        for (i, &sample) in audio.samples.iter().enumerate() {
+            assert_eq!(sample, sine(i / 2, i % 2, 22050.0), "sample {}", i);
+        }
+    }
+
+    #[test]
+    fn mp3_length_comes_from_decoding() {
+        let path = fixture("silence-44k-stereo.mp3");
+
+        // Without a Xing header the length isn't known up front
+        let info = probe(&path).unwrap();
+        assert_info(&info, "mp3", 44100, 2);
+        assert_eq!(info.duration_seconds, None);
+
+        let audio = decode(&path).unwrap();
+        assert_eq!(audio.samples.len(), 2 * 10 * 1152);
+        assert!(audio.samples.iter().all(|&sample| sample == 0.0));
+        assert_eq!(duration_seconds(&path).unwrap(), 10.0 * 1152.0 / 44100.0);
+    }
+
+    #[test]
+    fn ogg_vorbis_decodes_to_its_granule_length() {
+        let path = fixture("silence-48k-mono.ogg");
+
+        let info = probe(&path).unwrap();
+        assert_info(&info, "vorbis", 48000, 1);
+        assert_eq!(info.bits_per_sample, None);
+
+        // 41 short blocks overlap into 40 halves of 128 samples
+        let audio = decode(&path).unwrap();
+        assert_eq!(audio.samples.len(), 40 * 128);
+        assert!(audio.samples.iter().all(|&sample| sample == 0.0));
+        assert!((duration_seconds(&path).unwrap() - 40.0 * 128.0 / 48000.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn m4a_aac_decodes_every_frame() {
+        let path = fixture("silence-44k-mono.m4a");
+
+        let info = probe(&path).unwrap();
+        assert_info(&info, "aac", 44100, 1);
+        assert!((info.duration_seconds.unwrap() - 10.0 * 1024.0 / 44100.0).abs() < 1e-9);
+
+        let audio = decode(&path).unwrap();
+        assert_eq!(audio.samples.len(), 10 * 1024);
+        assert!(audio.samples.iter().all(|&sample| sample == 0.0));
+    }
+
+    #[test]
+    fn opus_is_rejected_with_its_own_error() {
+        let path = fixture("silence-48k-stereo.opus");
+
+        for error in [probe(&path).err(), decode(&path).err(), duration_seconds(&path).err()] {
+            let error = error.expect("Opus should not decode");
+            assert!(error.starts_with("Opus audio is not supported"), "{}", error);
+        }
+    }
+}
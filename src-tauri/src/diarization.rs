@@ -0,0 +1,555 @@
+// Speaker diarization: groups transcription segments into speaker turns and
+// clusters the turns by voice characteristics (MFCC statistics).
+//
+// This runs without a separate speaker model. When the Whisper model supports
+// tinydiarize (`*-tdrz.bin`), its speaker-turn markers decide where turns end;
+// otherwise every segment is treated as its own turn.
+
+const SAMPLE_RATE: usize = 16000;
+const FRAME_SIZE: usize = 512; // 32ms
+const HOP_SIZE: usize = 160; // 10ms
+const MEL_BANDS: usize = 40;
+const MFCC_COEFFS: usize = 20; // c0 (loudness) is dropped, so 19 are used
+const MIN_TURN_SECONDS: f32 = 0.8; // Shorter turns inherit a neighbour's speaker
+const MAX_TURNS: usize = 1500; // Keeps the distance matrix small for long meetings
+
+pub struct DiarizationOptions {
+    /// Cut the clustering at exactly this many speakers when known
+    pub num_speakers: Option<usize>,
+    pub max_speakers: usize,
+    /// Cosine distance above which turns are considered different speakers
+    pub threshold: f32,
+}
+
+impl Default for DiarizationOptions {
+    fn default() -> Self {
+        Self {
+            num_speakers: None,
+            max_speakers: 8,
+            threshold: 0.75,
+        }
+    }
+}
+
+/// A segment to label: time range in seconds, and whether tinydiarize
+/// detected a speaker change right after it.
+pub struct SegmentSpan {
+    pub start: f32,
+    pub end: f32,
+    pub speaker_turn_next: bool,
+}
+
+/// Label used for the n-th speaker (0-based) in order of first appearance.
+pub fn speaker_label(index: usize) -> String {
+    format!("Speaker {}", index + 1)
+}
+
+/// Assign a speaker index to every segment, numbered in order of first appearance.
+/// Returns no labels when no turn has enough voiced audio to tell voices apart.
+pub fn assign_speakers(audio: &[f32], segments: &[SegmentSpan], use_turn_markers: bool, options: &DiarizationOptions) -> Vec<usize> {
+    if segments.is_empty() {
+        return Vec::new();
+    }
+
+    let turns = group_turns(segments, use_turn_markers);
+
+    // Embed the turns that are long enough to say something about the voice
+    let mut embedded_turns = Vec::new();
+    let mut embeddings = Vec::new();
+    for (i, turn) in turns.iter().enumerate() {
+        if turn.end - turn.start < MIN_TURN_SECONDS {
+            continue;
+        }
+        if let Some(embedding) = turn_embedding(audio, turn.start, turn.end) {
+            embedded_turns.push(i);
+            embeddings.push(embedding);
+        }
+    }
+
+    if embeddings.is_empty() {
+        return Vec::new();
+    }
+
+    let mut turn_speakers: Vec<Option<usize>> = vec![None; turns.len()];
+    normalize_embeddings(&mut embeddings);
+    let clusters = cluster(&embeddings, options);
+    for (turn_index, cluster_id) in embedded_turns.iter().zip(clusters) {
+        turn_speakers[*turn_index] = Some(cluster_id);
+    }
+
+    // Short or silent turns take the speaker of the previous turn (or the next one)
+    let mut previous = None;
+    for speaker in turn_speakers.iter_mut() {
+        match speaker {
+            Some(s) => previous = Some(*s),
+            None => *speaker = previous,
+        }
+    }
+    let mut next = None;
+    for speaker in turn_speakers.iter_mut().rev() {
+        match speaker {
+            Some(s) => next = Some(*s),
+            None => *speaker = next,
+        }
+    }
+
+    // Renumber speakers by first appearance
+    let mut order: Vec<usize> = Vec::new();
+    let mut labels = vec![0; segments.len()];
+    for (turn, speaker) in turns.iter().zip(turn_speakers) {
+        let cluster_id = speaker.unwrap_or(0);
+        let index = match order.iter().position(|&c| c == cluster_id) {
+            Some(index) => index,
+            None => {
+                order.push(cluster_id);
+                order.len() - 1
+            }
+        };
+        for label in &mut labels[turn.first_segment..=turn.last_segment] {
+            *label = index;
+        }
+    }
+
+    labels
+}
+
+struct Turn {
+    first_segment: usize,
+    last_segment: usize,
+    start: f32,
+    end: f32,
+}
+
+fn group_turns(segments: &[SegmentSpan], use_turn_markers: bool) -> Vec<Turn> {
+    let mut turns: Vec<Turn> = Vec::new();
+    let mut turn_open = false;
+
+    for (i, segment) in segments.iter().enumerate() {
+        match turns.last_mut() {
+            Some(turn) if turn_open => {
+                turn.last_segment = i;
+                turn.end = segment.end;
+            }
+            _ => turns.push(Turn {
+                first_segment: i,
+                last_segment: i,
+                start: segment.start,
+                end: segment.end,
+            }),
+        }
+        turn_open = use_turn_markers && !segment.speaker_turn_next;
+    }
+
+    // Very long meetings: merge neighbouring turns pairwise until the count is manageable
+    while turns.len() > MAX_TURNS {
+        turns = turns.chunks(2)
+            .map(|pair| Turn {
+                first_segment: pair[0].first_segment,
+                last_segment: pair[pair.len() - 1].last_segment,
+                start: pair[0].start,
+                end: pair[pair.len() - 1].end,
+            })
+            .collect();
+    }
+
+    turns
+}
+
+/// Mean and standard deviation of the MFCCs over the voiced frames of a turn.
+fn turn_embedding(audio: &[f32], start: f32, end: f32) -> Option<Vec<f32>> {
+    let start_sample = ((start.max(0.0) * SAMPLE_RATE as f32) as usize).min(audio.len());
+    let end_sample = ((end.max(0.0) * SAMPLE_RATE as f32) as usize).min(audio.len());
+    let samples = &audio[start_sample..end_sample];
+
+    if samples.len() < FRAME_SIZE {
+        return None;
+    }
+
+    let window: Vec<f32> = (0..FRAME_SIZE)
+        .map(|i| 0.5 - 0.5 * (2.0 * std::f32::consts::PI * i as f32 / (FRAME_SIZE - 1) as f32).cos())
+        .collect();
+    let filters = mel_filterbank();
+
+    let mut frames: Vec<(f32, Vec<f32>)> = Vec::new();
+    let mut offset = 0;
+    while offset + FRAME_SIZE <= samples.len() {
+        let frame = &samples[offset..offset + FRAME_SIZE];
+        let energy = frame.iter().map(|s| s * s).sum::<f32>() / FRAME_SIZE as f32;
+
+        let mut re: Vec<f32> = frame.iter().zip(&window).map(|(s, w)| s * w).collect();
+        let mut im = vec![0.0; FRAME_SIZE];
+        fft(&mut re, &mut im);
+        let power: Vec<f32> = (0..=FRAME_SIZE / 2).map(|k| re[k] * re[k] + im[k] * im[k]).collect();
+
+        let log_mel: Vec<f32> = filters.iter()
+            .map(|filter| {
+                let band: f32 = filter.iter().map(|&(bin, weight)| power[bin] * weight).sum();
+                (band + 1e-10).ln()
+            })
+            .collect();
+
+        frames.push((energy, dct(&log_mel)));
+        offset += HOP_SIZE;
+    }
+
+    // Ignore pauses inside the turn: keep frames louder than a third of the average energy
+    let mean_energy = frames.iter().map(|(e, _)| e).sum::<f32>() / frames.len().max(1) as f32;
+    let voiced: Vec<&Vec<f32>> = frames.iter()
+        .filter(|(energy, _)| *energy > mean_energy / 3.0 && *energy > 1e-8)
+        .map(|(_, mfcc)| mfcc)
+        .collect();
+
+    if voiced.len() < 10 {
+        return None;
+    }
+
+    let dims = MFCC_COEFFS - 1;
+    let mut mean = vec![0.0; dims];
+    for mfcc in &voiced {
+        for d in 0..dims {
+            mean[d] += mfcc[d + 1];
+        }
+    }
+    for value in &mut mean {
+        *value /= voiced.len() as f32;
+    }
+
+    let mut std_dev = vec![0.0; dims];
+    for mfcc in &voiced {
+        for d in 0..dims {
+            std_dev[d] += (mfcc[d + 1] - mean[d]).powi(2);
+        }
+    }
+    for value in &mut std_dev {
+        *value = (*value / voiced.len() as f32).sqrt();
+    }
+
+    mean.extend(std_dev);
+    Some(mean)
+}
+
+/// Standardize each dimension across the meeting, then scale to unit length
+/// so cosine distance is a plain dot product.
+fn normalize_embeddings(embeddings: &mut [Vec<f32>]) {
+    let dims = embeddings[0].len();
+    let count = embeddings.len() as f32;
+
+    for d in 0..dims {
+        let mean = embeddings.iter().map(|e| e[d]).sum::<f32>() / count;
+        let variance = embeddings.iter().map(|e| (e[d] - mean).powi(2)).sum::<f32>() / count;
+        let std_dev = variance.sqrt().max(1e-6);
+        for embedding in embeddings.iter_mut() {
+            embedding[d] = (embedding[d] - mean) / std_dev;
+        }
+    }
+
+    for embedding in embeddings.iter_mut() {
+        let norm = embedding.iter().map(|v| v * v).sum::<f32>().sqrt().max(1e-6);
+        for value in embedding.iter_mut() {
+            *value /= norm;
+        }
+    }
+}
+
+/// Average-linkage agglomerative clustering (nearest-neighbour chain), cut by
+/// `num_speakers` when given, otherwise by the distance threshold.
+fn cluster(embeddings: &[Vec<f32>], options: &DiarizationOptions) -> Vec<usize> {
+    let n = embeddings.len();
+    let mut distances = vec![0.0f32; n * n];
+    for i in 0..n {
+        for j in (i + 1)..n {
+            let similarity: f32 = embeddings[i].iter().zip(&embeddings[j]).map(|(a, b)| a * b).sum();
+            distances[i * n + j] = 1.0 - similarity;
+            distances[j * n + i] = 1.0 - similarity;
+        }
+    }
+
+    // Build the full dendrogram as a list of (distance, a, b) merges
+    let mut sizes = vec![1usize; n];
+    let mut active = vec![true; n];
+    let mut merges: Vec<(f32, usize, usize)> = Vec::with_capacity(n.saturating_sub(1));
+    let mut chain: Vec<usize> = Vec::new();
+    let mut remaining = n;
+
+    while remaining > 1 {
+        if chain.is_empty() {
+            chain.push(active.iter().position(|&a| a).unwrap_or(0));
+        }
+        let a = chain[chain.len() - 1];
+        let previous = if chain.len() >= 2 { Some(chain[chain.len() - 2]) } else { None };
+
+        let mut nearest = previous.unwrap_or(usize::MAX);
+        let mut nearest_distance = previous.map(|p| distances[a * n + p]).unwrap_or(f32::INFINITY);
+        for k in 0..n {
+            if active[k] && k != a && distances[a * n + k] < nearest_distance {
+                nearest = k;
+                nearest_distance = distances[a * n + k];
+            }
+        }
+
+        if Some(nearest) == previous {
+            chain.truncate(chain.len() - 2);
+            let b = nearest;
+            merges.push((nearest_distance, a, b));
+
+            // Merge b into a (Lance-Williams update for average linkage)
+            for k in 0..n {
+                if active[k] && k != a && k != b {
+                    let merged = (sizes[a] as f32 * distances[a * n + k] + sizes[b] as f32 * distances[b * n + k])
+                        / (sizes[a] + sizes[b]) as f32;
+                    distances[a * n + k] = merged;
+                    distances[k * n + a] = merged;
+                }
+            }
+            sizes[a] += sizes[b];
+            active[b] = false;
+            remaining -= 1;
+        } else {
+            chain.push(nearest);
+        }
+    }
+
+    // Cut the dendrogram by applying the cheapest merges first
+    merges.sort_by(|x, y| x.0.total_cmp(&y.0));
+    let target = options.num_speakers.unwrap_or(1).clamp(1, n);
+    let mut parent: Vec<usize> = (0..n).collect();
+    let mut clusters = n;
+
+    for (distance, a, b) in merges {
+        let over_limit = clusters > options.max_speakers.max(1);
+        let done = match options.num_speakers {
+            Some(_) => clusters <= target,
+            None => distance > options.threshold && !over_limit,
+        };
+        if done {
+            break;
+        }
+
+        let root_a = find(&mut parent, a);
+        let root_b = find(&mut parent, b);
+        if root_a != root_b {
+            parent[root_b] = root_a;
+            clusters -= 1;
+        }
+    }
+
+    (0..n).map(|i| find(&mut parent, i)).collect()
+}
+
+fn find(parent: &mut [usize], i: usize) -> usize {
+    let mut root = i;
+    while parent[root] != root {
+        root = parent[root];
+    }
+    let mut node = i;
+    while parent[node] != root {
+        let next = parent[node];
+        parent[node] = root;
+        node = next;
+    }
+    root
+}
+
+/// Triangular mel filters as (FFT bin, weight) pairs.
+fn mel_filterbank() -> Vec<Vec<(usize, f32)>> {
+    let hz_to_mel = |hz: f32| 2595.0 * (1.0 + hz / 700.0).log10();
+    let mel_to_hz = |mel: f32| 700.0 * (10f32.powf(mel / 2595.0) - 1.0);
+
+    let max_mel = hz_to_mel(SAMPLE_RATE as f32 / 2.0);
+    let min_mel = hz_to_mel(60.0);
+    let bin_of = |hz: f32| hz * FRAME_SIZE as f32 / SAMPLE_RATE as f32;
+
+    let points: Vec<f32> = (0..MEL_BANDS + 2)
+        .map(|i| bin_of(mel_to_hz(min_mel + (max_mel - min_mel) * i as f32 / (MEL_BANDS + 1) as f32)))
+        .collect();
+
+    (0..MEL_BANDS)
+        .map(|m| {
+            let (left, center, right) = (points[m], points[m + 1], points[m + 2]);
+            (left.floor() as usize..=(right.ceil() as usize).min(FRAME_SIZE / 2))
+                .filter_map(|bin| {
+                    let f = bin as f32;
+                    let weight = if f < center {
+                        (f - left) / (center - left).max(1e-6)
+                    } else {
+                        (right - f) / (right - center).max(1e-6)
+                    };
+                    (weight > 0.0).then_some((bin, weight))
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn dct(input: &[f32]) -> Vec<f32> {
+    let n = input.len() as f32;
+    (0..MFCC_COEFFS)
+        .map(|k| {
+            input.iter().enumerate()
+                .map(|(i, &x)| x * (std::f32::consts::PI * k as f32 * (i as f32 + 0.5) / n).cos())
+                .sum()
+        })
+        .collect()
+}
+
+/// In-place iterative radix-2 FFT; `re.len()` must be a power of two.
+fn fft(re: &mut [f32], im: &mut [f32]) {
+    let n = re.len();
+
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            re.swap(i, j);
+            im.swap(i, j);
+        }
+    }
+
+    let mut len = 2;
+    while len <= n {
+        let angle = -2.0 * std::f32::consts::PI / len as f32;
+        for start in (0..n).step_by(len) {
+            for k in 0..len / 2 {
+                let (sin, cos) = (angle * k as f32).sin_cos();
+                let a = start + k;
+                let b = a + len / 2;
+                let tr = re[b] * cos - im[b] * sin;
+                let ti = re[b] * sin + im[b] * cos;
+                re[b] = re[a] - tr;
+                im[b] = im[a] - ti;
+                re[a] += tr;
+                im[a] += ti;
+            }
+        }
+        len <<= 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TURN_SECONDS: f32 = 2.0;
+
+    /// A vowel-like voice: harmonics of `pitch` shaped by two formants, with a
+    /// syllable rhythm and a little noise so no two turns are identical.
+    fn voice(pitch: f32, formants: [f32; 2], seconds: f32, seed: u32) -> Vec<f32> {
+        let mut state = seed.wrapping_mul(2_654_435_761).max(1);
+        let mut noise = move || {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            state as f32 / u32::MAX as f32 - 0.5
+        };
+
+        let harmonics: Vec<(f32, f32)> = (1..)
+            .map(|k| k as f32 * pitch)
+            .take_while(|&frequency| frequency < 7000.0)
+            .map(|frequency| {
+                let gain = formants.iter()
+                    .map(|formant| (-((frequency - formant) / 200.0).powi(2)).exp())
+                    .sum::<f32>();
+                (frequency, gain + 0.01)
+            })
+            .collect();
+
+        (0..(seconds * SAMPLE_RATE as f32) as usize)
+            .map(|i| {
+                let t = i as f32 / SAMPLE_RATE as f32;
+                let syllables = 0.6 + 0.4 * (2.0 * std::f32::consts::PI * 4.0 * t).sin();
+                let tone: f32 = harmonics.iter()
+                    .map(|(frequency, gain)| gain * (2.0 * std::f32::consts::PI * frequency * t).sin())
+                    .sum();
+                0.05 * syllables * tone + 0.002 * noise()
+            })
+            .collect()
+    }
+
+    fn spans(turns: usize) -> Vec<SegmentSpan> {
+        (0..turns)
+            .map(|i| SegmentSpan {
+                start: i as f32 * TURN_SECONDS,
+                end: (i + 1) as f32 * TURN_SECONDS,
+                speaker_turn_next: false,
+            })
+            .collect()
+    }
+
+    fn low_voice(seed: u32) -> Vec<f32> {
+        voice(110.0, [500.0, 1000.0], TURN_SECONDS, seed)
+    }
+
+    fn high_voice(seed: u32) -> Vec<f32> {
+        voice(220.0, [900.0, 2600.0], TURN_SECONDS, seed)
+    }
+
+    #[test]
+    fn alternating_voices_become_two_speakers() {
+        let mut audio = Vec::new();
+        for turn in 0..6 {
+            audio.extend(if turn % 2 == 0 { low_voice(turn) } else { high_voice(turn) });
+        }
+
+        let speakers = assign_speakers(&audio, &spans(6), false, &DiarizationOptions::default());
+
+        assert_eq!(speakers, [0, 1, 0, 1, 0, 1]);
+    }
+
+    #[test]
+    fn known_speaker_count_cuts_the_clustering() {
+        let mut audio = Vec::new();
+        for turn in 0..6 {
+            audio.extend(if turn < 2 { high_voice(turn) } else { low_voice(turn) });
+        }
+        let options = DiarizationOptions { num_speakers: Some(2), ..DiarizationOptions::default() };
+
+        let speakers = assign_speakers(&audio, &spans(6), false, &options);
+
+        assert_eq!(speakers, [0, 0, 1, 1, 1, 1]);
+    }
+
+    #[test]
+    fn silence_has_no_speakers() {
+        let audio = vec![0.0; 4 * TURN_SECONDS as usize * SAMPLE_RATE];
+
+        let speakers = assign_speakers(&audio, &spans(4), false, &DiarizationOptions::default());
+
+        assert!(speakers.is_empty());
+    }
+
+    #[test]
+    fn short_turns_inherit_a_neighbours_speaker() {
+        let mut audio = low_voice(1);
+        audio.extend(high_voice(2));
+        audio.extend(low_voice(3));
+        let mut segments = spans(3);
+        // A 0.3s interjection at the start of the high voice's turn
+        segments.insert(1, SegmentSpan { start: 2.0, end: 2.3, speaker_turn_next: false });
+        segments[2].start = 2.3;
+
+        let speakers = assign_speakers(&audio, &segments, false, &DiarizationOptions::default());
+
+        assert_eq!(speakers, [0, 0, 1, 0]);
+    }
+
+    #[test]
+    fn turn_markers_group_segments() {
+        let segments = [
+            SegmentSpan { start: 0.0, end: 1.0, speaker_turn_next: false },
+            SegmentSpan { start: 1.0, end: 2.0, speaker_turn_next: true },
+            SegmentSpan { start: 2.0, end: 3.0, speaker_turn_next: false },
+        ];
+
+        let turns = group_turns(&segments, true);
+        assert_eq!(turns.len(), 2);
+        assert_eq!((turns[0].first_segment, turns[0].last_segment, turns[0].end), (0, 1, 2.0));
+        assert_eq!((turns[1].first_segment, turns[1].start), (2, 2.0));
+
+        assert_eq!(group_turns(&segments, false).len(), 3);
+    }
+}
@@ -0,0 +1,575 @@
+// FLAC encoding for archived recordings.
+//
+// A small encoder for integer PCM. Each block of each channel is coded with
+// whichever fixed polynomial predictor (order 0 to 4) leaves the smallest
+// residual, and the residual is Rice coded in partitions with their own
+// parameters. Stereo blocks also try left/side, side/right and mid/side. Quiet
+// stretches of a recording become constant or near-constant blocks that take
+// almost no space.
+//
+// No MD5 signature is written (the format allows it to be zero); `archive`
+// checks a file by decoding it again instead.
+
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+const BLOCK_SIZE: usize = 4096;
+const MAX_FIXED_ORDER: usize = 4;
+const MAX_PARTITION_ORDER: u32 = 8;
+const MAX_RICE_PARAMETER: u32 = 14; // 15 marks an escaped partition
+const ESCAPE_PARAMETER: u64 = 15;
+
+/// Write interleaved integer samples of `bits_per_sample` bits as a FLAC file.
+pub fn write(path: &Path, samples: &[i32], channels: u16, sample_rate: u32, bits_per_sample: u16) -> Result<(), String> {
+    if !(1..=8).contains(&channels) {
+        return Err(format!("FLAC supports 1 to 8 channels, not {}", channels));
+    }
+    if !(4..=24).contains(&bits_per_sample) {
+        return Err(format!("Cannot encode {}-bit audio as FLAC", bits_per_sample));
+    }
+    if sample_rate == 0 || sample_rate > 655_350 {
+        return Err(format!("Cannot encode a sample rate of {} Hz as FLAC", sample_rate));
+    }
+
+    let io_error = |e: std::io::Error| format!("Failed to write {}: {}", path.display(), e);
+    let mut file = BufWriter::new(File::create(path).map_err(io_error)?);
+
+    let channels = channels as usize;
+    let frames = samples.len() / channels;
+
+    file.write_all(b"fLaC").map_err(io_error)?;
+    file.write_all(&stream_info(frames as u64, channels, sample_rate, bits_per_sample)).map_err(io_error)?;
+
+    let mut block = vec![Vec::with_capacity(BLOCK_SIZE); channels];
+    for (frame_number, chunk) in samples[..frames * channels].chunks(BLOCK_SIZE * channels).enumerate() {
+        for (channel, samples) in block.iter_mut().enumerate() {
+            samples.clear();
+            samples.extend(chunk.iter().skip(channel).step_by(channels).map(|&s| s as i64));
+        }
+        let frame = encode_frame(&block, frame_number as u64, sample_rate, bits_per_sample as u32);
+        file.write_all(&frame).map_err(io_error)?;
+    }
+
+    file.flush().map_err(io_error)
+}
+
+/// The STREAMINFO metadata block, the only one written.
+fn stream_info(frames: u64, channels: usize, sample_rate: u32, bits_per_sample: u16) -> Vec<u8> {
+    let mut bits = BitWriter::new();
+    bits.write(1, 1); // Last metadata block
+    bits.write(0, 7); // STREAMINFO
+    bits.write(34, 24);
+    bits.write(BLOCK_SIZE as u64, 16); // Minimum block size, not counting the last block
+    bits.write(BLOCK_SIZE as u64, 16);
+    bits.write(0, 24); // Frame sizes unknown
+    bits.write(0, 24);
+    bits.write(sample_rate as u64, 20);
+    bits.write(channels as u64 - 1, 3);
+    bits.write(bits_per_sample as u64 - 1, 5);
+    bits.write(frames >> 32, 4);
+    bits.write(frames & 0xFFFF_FFFF, 32);
+    for _ in 0..4 {
+        bits.write(0, 32); // No MD5 signature
+    }
+    bits.into_bytes()
+}
+
+#[derive(Clone, Copy)]
+enum ChannelAssignment {
+    Independent,
+    LeftSide,
+    SideRight,
+    MidSide,
+}
+
+fn encode_frame(block: &[Vec<i64>], frame_number: u64, sample_rate: u32, bits_per_sample: u32) -> Vec<u8> {
+    let len = block[0].len();
+
+    // Pick the stereo decorrelation that codes smallest
+    let (assignment, subframes) = if block.len() == 2 {
+        let (left, right) = (&block[0], &block[1]);
+        let side: Vec<i64> = left.iter().zip(right).map(|(l, r)| l - r).collect();
+        let mid: Vec<i64> = left.iter().zip(right).map(|(l, r)| (l + r) >> 1).collect();
+
+        let left_plan = plan_subframe(left, bits_per_sample);
+        let right_plan = plan_subframe(right, bits_per_sample);
+        let side_plan = plan_subframe(&side, bits_per_sample + 1);
+        let mid_plan = plan_subframe(&mid, bits_per_sample);
+
+        let options = [
+            (ChannelAssignment::Independent, left_plan.bits + right_plan.bits),
+            (ChannelAssignment::LeftSide, left_plan.bits + side_plan.bits),
+            (ChannelAssignment::SideRight, side_plan.bits + right_plan.bits),
+            (ChannelAssignment::MidSide, mid_plan.bits + side_plan.bits),
+        ];
+        let best = options.iter().min_by_key(|(_, bits)| *bits).map(|(assignment, _)| *assignment)
+            .unwrap_or(ChannelAssignment::Independent);
+
+        let subframes = match best {
+            ChannelAssignment::Independent => vec![(left.clone(), left_plan, bits_per_sample), (right.clone(), right_plan, bits_per_sample)],
+            ChannelAssignment::LeftSide => vec![(left.clone(), left_plan, bits_per_sample), (side, side_plan, bits_per_sample + 1)],
+            ChannelAssignment::SideRight => vec![(side, side_plan, bits_per_sample + 1), (right.clone(), right_plan, bits_per_sample)],
+            ChannelAssignment::MidSide => vec![(mid, mid_plan, bits_per_sample), (side, side_plan, bits_per_sample + 1)],
+        };
+        (best, subframes)
+    } else {
+        let subframes = block.iter()
+            .map(|samples| (samples.clone(), plan_subframe(samples, bits_per_sample), bits_per_sample))
+            .collect();
+        (ChannelAssignment::Independent, subframes)
+    };
+
+    let mut bits = BitWriter::new();
+
+    // Frame header
+    bits.write(0b11_1111_1111_1110, 14); // Sync code
+    bits.write(0, 1);
+    bits.write(0, 1); // Fixed block size, so the header carries the frame number
+    let block_size_code = if len == BLOCK_SIZE { 12 } else { 7 };
+    bits.write(block_size_code, 4);
+    let (rate_code, rate_value) = sample_rate_code(sample_rate);
+    bits.write(rate_code, 4);
+    let channel_code = match assignment {
+        ChannelAssignment::Independent => block.len() as u64 - 1,
+        ChannelAssignment::LeftSide => 0b1000,
+        ChannelAssignment::SideRight => 0b1001,
+        ChannelAssignment::MidSide => 0b1010,
+    };
+    bits.write(channel_code, 4);
+    bits.write(sample_size_code(bits_per_sample), 3);
+    bits.write(0, 1);
+    bits.write_utf8(frame_number);
+    if block_size_code == 7 {
+        bits.write(len as u64 - 1, 16);
+    }
+    if let Some((value, width)) = rate_value {
+        bits.write(value, width);
+    }
+    let crc = crc8(&bits.bytes);
+    bits.write(crc as u64, 8);
+
+    for (samples, plan, sample_bits) in &subframes {
+        write_subframe(&mut bits, samples, plan, *sample_bits);
+    }
+
+    bits.align();
+    let crc = crc16(&bits.bytes);
+    bits.write(crc as u64, 16);
+    bits.into_bytes()
+}
+
+/// Frame header code for a sample rate, and the value that follows the header if any.
+fn sample_rate_code(sample_rate: u32) -> (u64, Option<(u64, u32)>) {
+    match sample_rate {
+        88200 => (1, None),
+        176400 => (2, None),
+        192000 => (3, None),
+        8000 => (4, None),
+        16000 => (5, None),
+        22050 => (6, None),
+        24000 => (7, None),
+        32000 => (8, None),
+        44100 => (9, None),
+        48000 => (10, None),
+        96000 => (11, None),
+        // Code 12 (kHz) is skipped, as symphonia reads its value as Hz
+        rate if rate <= 65535 => (13, Some((rate as u64, 16))),
+        rate if rate % 10 == 0 => (14, Some((rate as u64 / 10, 16))),
+        _ => (0, None), // Taken from STREAMINFO
+    }
+}
+
+fn sample_size_code(bits_per_sample: u32) -> u64 {
+    match bits_per_sample {
+        8 => 1,
+        12 => 2,
+        16 => 4,
+        20 => 5,
+        24 => 6,
+        _ => 0, // Taken from STREAMINFO
+    }
+}
+
+enum Subframe {
+    Constant,
+    Verbatim,
+    Fixed {
+        order: usize,
+        residual: Vec<i64>,
+        partition_order: u32,
+        parameters: Vec<Parameter>,
+    },
+}
+
+#[derive(Clone, Copy)]
+enum Parameter {
+    Rice(u32),
+    Escaped(u32), // Residuals stored as plain signed numbers of this many bits
+}
+
+struct SubframePlan {
+    bits: u64,
+    subframe: Subframe,
+}
+
+/// The cheapest way to code one channel of a block, with its size in bits.
+fn plan_subframe(samples: &[i64], sample_bits: u32) -> SubframePlan {
+    const HEADER_BITS: u64 = 8;
+
+    if samples.iter().all(|&s| s == samples[0]) {
+        return SubframePlan { bits: HEADER_BITS + sample_bits as u64, subframe: Subframe::Constant };
+    }
+
+    let mut best = SubframePlan {
+        bits: HEADER_BITS + samples.len() as u64 * sample_bits as u64,
+        subframe: Subframe::Verbatim,
+    };
+
+    let mut residual = samples.to_vec();
+    for order in 0..=MAX_FIXED_ORDER.min(samples.len() - 1) {
+        if order > 0 {
+            // Each order's residual is the difference of the previous one
+            for i in (order..samples.len()).rev() {
+                residual[i] -= residual[i - 1];
+            }
+        }
+        let (partition_bits, partition_order, parameters) = plan_partitions(&residual[order..], samples.len(), order);
+        let bits = HEADER_BITS + order as u64 * sample_bits as u64 + 6 + partition_bits;
+        if bits < best.bits {
+            best = SubframePlan {
+                bits,
+                subframe: Subframe::Fixed {
+                    order,
+                    residual: residual[order..].to_vec(),
+                    partition_order,
+                    parameters,
+                },
+            };
+        }
+    }
+
+    best
+}
+
+/// Choose the partition order and per-partition Rice parameters for a residual.
+/// Sizes are estimated from each partition's sum, which is close enough to pick by.
+fn plan_partitions(residual: &[i64], block_len: usize, order: usize) -> (u64, u32, Vec<Parameter>) {
+    let mut max_order = 0;
+    while max_order < MAX_PARTITION_ORDER
+        && block_len.is_multiple_of(1 << (max_order + 1))
+        && block_len >> (max_order + 1) > order
+    {
+        max_order += 1;
+    }
+
+    // (count, sum of zigzagged values, bits to store any value plainly) per finest partition
+    let partition_len = block_len >> max_order;
+    let mut partitions: Vec<(u64, u64, u32)> = (0..1usize << max_order)
+        .map(|i| {
+            let start = (i * partition_len).saturating_sub(order);
+            let end = (i + 1) * partition_len - order;
+            let values = &residual[start..end];
+            let sum = values.iter().map(|&r| zigzag(r)).sum();
+            let raw_bits = values.iter().map(|&r| signed_bits(r)).max().unwrap_or(0);
+            (values.len() as u64, sum, raw_bits)
+        })
+        .collect();
+
+    let mut best = (u64::MAX, 0, Vec::new());
+    let mut partition_order = max_order;
+    loop {
+        let parameters: Vec<(u64, Parameter)> = partitions.iter().map(|&p| choose_parameter(p)).collect();
+        let bits = parameters.iter().map(|(bits, _)| bits).sum::<u64>();
+        if bits < best.0 {
+            best = (bits, partition_order, parameters.into_iter().map(|(_, p)| p).collect());
+        }
+
+        if partition_order == 0 {
+            return best;
+        }
+        // Each coarser partition joins two neighbouring ones
+        partition_order -= 1;
+        partitions = partitions.chunks(2)
+            .map(|pair| (pair[0].0 + pair[1].0, pair[0].1 + pair[1].1, pair[0].2.max(pair[1].2)))
+            .collect();
+    }
+}
+
+/// Estimated size of a partition with its best parameter, including the parameter itself.
+fn choose_parameter((count, sum, raw_bits): (u64, u64, u32)) -> (u64, Parameter) {
+    let mut best = (4 + 5 + count * raw_bits as u64, Parameter::Escaped(raw_bits));
+    for k in 0..=MAX_RICE_PARAMETER {
+        // Flooring each quotient drops about half a bit per value compared to `sum >> k`
+        let quotients = if k == 0 { sum } else { (sum >> k).saturating_sub(count / 2) };
+        let bits = 4 + count * (k as u64 + 1) + quotients;
+        if bits < best.0 {
+            best = (bits, Parameter::Rice(k));
+        }
+    }
+    best
+}
+
+fn write_subframe(bits: &mut BitWriter, samples: &[i64], plan: &SubframePlan, sample_bits: u32) {
+    bits.write(0, 1);
+    match &plan.subframe {
+        Subframe::Constant => {
+            bits.write(0b000000, 6);
+            bits.write(0, 1); // No wasted bits
+            bits.write_signed(samples[0], sample_bits);
+        }
+        Subframe::Verbatim => {
+            bits.write(0b000001, 6);
+            bits.write(0, 1);
+            for &sample in samples {
+                bits.write_signed(sample, sample_bits);
+            }
+        }
+        Subframe::Fixed { order, residual, partition_order, parameters } => {
+            bits.write(0b001000 | *order as u64, 6);
+            bits.write(0, 1);
+            for &sample in &samples[..*order] {
+                bits.write_signed(sample, sample_bits);
+            }
+
+            bits.write(0b00, 2); // Rice coding with 4-bit parameters
+            bits.write(*partition_order as u64, 4);
+            let partition_len = samples.len() >> partition_order;
+            let mut start = 0;
+            for (i, parameter) in parameters.iter().enumerate() {
+                let end = (i + 1) * partition_len - order;
+                match *parameter {
+                    Parameter::Rice(k) => {
+                        bits.write(k as u64, 4);
+                        for &r in &residual[start..end] {
+                            bits.write_rice(r, k);
+                        }
+                    }
+                    Parameter::Escaped(raw_bits) => {
+                        bits.write(ESCAPE_PARAMETER, 4);
+                        bits.write(raw_bits as u64, 5);
+                        for &r in &residual[start..end] {
+                            bits.write_signed(r, raw_bits);
+                        }
+                    }
+                }
+                start = end;
+            }
+        }
+    }
+}
+
+fn zigzag(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+/// Bits needed to store `value` as a two's complement number.
+fn signed_bits(value: i64) -> u32 {
+    let magnitude = if value < 0 { !value } else { value };
+    if value == 0 { 0 } else { 65 - magnitude.leading_zeros() }
+}
+
+/// Big-endian bit packing, as FLAC lays out its fields.
+struct BitWriter {
+    bytes: Vec<u8>,
+    buffer: u64,
+    pending: u32, // Bits in `buffer` not yet in `bytes`, fewer than 8 between calls
+}
+
+impl BitWriter {
+    fn new() -> Self {
+        Self { bytes: Vec::new(), buffer: 0, pending: 0 }
+    }
+
+    /// Write the low `count` bits of `value`, at most 32.
+    fn write(&mut self, value: u64, count: u32) {
+        if count == 0 {
+            return;
+        }
+        self.buffer = (self.buffer << count) | (value & ((1u64 << count) - 1));
+        self.pending += count;
+        while self.pending >= 8 {
+            self.pending -= 8;
+            self.bytes.push((self.buffer >> self.pending) as u8);
+        }
+    }
+
+    fn write_signed(&mut self, value: i64, count: u32) {
+        self.write(value as u64, count);
+    }
+
+    fn write_rice(&mut self, value: i64, parameter: u32) {
+        let value = zigzag(value);
+        let mut quotient = value >> parameter;
+        while quotient >= 32 {
+            self.write(0, 32);
+            quotient -= 32;
+        }
+        self.write(1, quotient as u32 + 1); // `quotient` zeros, then a one
+        self.write(value, parameter);
+    }
+
+    /// The frame number, in the extended UTF-8 coding FLAC uses.
+    fn write_utf8(&mut self, value: u64) {
+        if value < 0x80 {
+            self.write(value, 8);
+            return;
+        }
+        let continuation_bytes = match value {
+            0..=0x7FF => 1,
+            0x800..=0xFFFF => 2,
+            0x1_0000..=0x1F_FFFF => 3,
+            0x20_0000..=0x3FF_FFFF => 4,
+            0x400_0000..=0x7FFF_FFFF => 5,
+            _ => 6,
+        };
+        let lead_marker = (0xFF00u64 >> (continuation_bytes + 1)) & 0xFF;
+        self.write(lead_marker | (value >> (6 * continuation_bytes)), 8);
+        for i in (0..continuation_bytes).rev() {
+            self.write(0x80 | ((value >> (6 * i)) & 0x3F), 8);
+        }
+    }
+
+    fn align(&mut self) {
+        if self.pending > 0 {
+            self.write(0, 8 - self.pending);
+        }
+    }
+
+    fn into_bytes(mut self) -> Vec<u8> {
+        self.align();
+        self.bytes
+    }
+}
+
+fn crc8(bytes: &[u8]) -> u8 {
+    bytes.iter().fold(0u8, |mut crc, &byte| {
+        crc ^= byte;
+        for _ in 0..8 {
+            crc = if crc & 0x80 != 0 { (crc << 1) ^ 0x07 } else { crc << 1 };
+        }
+        crc
+    })
+}
+
+fn crc16(bytes: &[u8]) -> u16 {
+    bytes.iter().fold(0u16, |mut crc, &byte| {
+        crc ^= (byte as u16) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 { (crc << 1) ^ 0x8005 } else { crc << 1 };
+        }
+        crc
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decode;
+
+    /// Write `samples` as FLAC, decode the file and check it matches exactly.
+    fn round_trip(samples: &[i32], channels: u16, sample_rate: u32, bits_per_sample: u16) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audio.flac");
+
+        write(&path, samples, channels, sample_rate, bits_per_sample).unwrap();
+        let decoded = decode::decode(&path).unwrap();
+
+        let label = format!("{} channels, {} Hz, {} bits, {} samples", channels, sample_rate, bits_per_sample, samples.len());
+        assert_eq!(decoded.info.codec, "flac", "{}", label);
+        assert_eq!(decoded.info.channels, channels, "{}", label);
+        assert_eq!(decoded.info.sample_rate, sample_rate, "{}", label);
+        assert_eq!(decoded.info.bits_per_sample, Some(bits_per_sample), "{}", label);
+        assert_eq!(decoded.samples.len(), samples.len(), "{}", label);
+
+        let scale = (1i64 << (bits_per_sample - 1)) as f32;
+        for (i, (&sample, &decoded)) in samples.iter().zip(&decoded.samples).enumerate() {
+            assert_eq!(decoded, sample as f32 / scale, "{}: sample {}", label, i);
+        }
+    }
+
+    fn full_scale(bits_per_sample: u16) -> (i32, i32) {
+        let max = (1i32 << (bits_per_sample - 1)) - 1;
+        (-max - 1, max)
+    }
+
+    /// A sine at `amplitude` of full scale plus some noise, per channel.
+    fn signal(frames: usize, channels: u16, bits_per_sample: u16, amplitude: f64) -> Vec<i32> {
+        let (min, max) = full_scale(bits_per_sample);
+        let mut seed = 0x2545F491u32;
+        let mut samples = Vec::with_capacity(frames * channels as usize);
+        for frame in 0..frames {
+            for channel in 0..channels {
+                seed = seed.wrapping_mul(1664525).wrapping_add(1013904223);
+                let noise = (seed >> 16) as f64 / 65536.0 - 0.5;
+                let phase = 2.0 * std::f64::consts::PI * frame as f64 / (50.0 + 13.0 * channel as f64);
+                let value = (amplitude * phase.sin() + 0.01 * noise) * max as f64;
+                samples.push((value.round() as i32).clamp(min, max));
+            }
+        }
+        samples
+    }
+
+    #[test]
+    fn round_trips_each_bit_depth_in_mono_and_stereo() {
+        for bits_per_sample in [8, 12, 16, 20, 24] {
+            for channels in [1, 2] {
+                round_trip(&signal(5000, channels, bits_per_sample, 0.5), channels, 16000, bits_per_sample);
+            }
+        }
+    }
+
+    #[test]
+    fn round_trips_silence() {
+        for bits_per_sample in [8, 16, 24] {
+            round_trip(&vec![0; 2 * 9000], 2, 48000, bits_per_sample);
+            round_trip(&vec![-3; 4096], 1, 44100, bits_per_sample);
+        }
+    }
+
+    #[test]
+    fn round_trips_full_scale() {
+        for bits_per_sample in [8, 16, 24] {
+            let (min, max) = full_scale(bits_per_sample);
+
+            // Square waves swing the residual, and the side channel, across the widest range
+            let square: Vec<i32> = (0..2 * 4500)
+                .map(|i| if (i / 2 / 3 + i % 2) % 2 == 0 { max } else { min })
+                .collect();
+            round_trip(&square, 2, 16000, bits_per_sample);
+
+            let clipped: Vec<i32> = signal(4500, 1, bits_per_sample, 1.5);
+            round_trip(&clipped, 1, 16000, bits_per_sample);
+        }
+    }
+
+    #[test]
+    fn round_trips_odd_block_sizes() {
+        for frames in [1, 2, 5, 17, 4095, 4097, 2 * 4096 + 1] {
+            round_trip(&signal(frames, 2, 16, 0.3), 2, 16000, 16);
+            round_trip(&signal(frames, 1, 24, 0.3), 1, 16000, 24);
+        }
+    }
+
+    #[test]
+    fn round_trips_correlated_stereo_and_uncommon_rates() {
+        // Identical and inverted channels favour side and mid/side coding
+        let mono = signal(6000, 1, 16, 0.4);
+        let same: Vec<i32> = mono.iter().flat_map(|&s| [s, s]).collect();
+        let inverted: Vec<i32> = mono.iter().flat_map(|&s| [s, -s]).collect();
+        for sample_rate in [8000, 11025, 22050, 44100, 48000, 96000, 100_000, 192000] {
+            round_trip(&same, 2, sample_rate, 16);
+            round_trip(&inverted, 2, sample_rate, 16);
+        }
+    }
+
+    #[test]
+    fn rejects_what_flac_cannot_hold() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audio.flac");
+
+        assert!(write(&path, &[0; 9], 9, 16000, 16).is_err());
+        assert!(write(&path, &[0; 4], 1, 16000, 32).is_err());
+        assert!(write(&path, &[0; 4], 1, 0, 16).is_err());
+    }
+}
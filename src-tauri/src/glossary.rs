@@ -0,0 +1,256 @@
+// Custom vocabulary: glossary terms bias Whisper through its initial prompt,
+// and known misrecognitions of a term are replaced in the transcript afterwards.
+//
+// Replacements match whole words, ignoring case, so "Cube Control" in a
+// segment becomes "kubectl" but "cubes" is left alone.
+
+use crate::database::{GlossaryTerm, SegmentWord};
+
+const MAX_PROMPT_CHARS: usize = 400; // Leaves room in Whisper's ~224-token prompt for earlier text
+
+/// Glossary terms as prompt text, e.g. "Glossary: Tokopedia, kubectl, OKR."
+pub fn prompt(terms: &[GlossaryTerm]) -> Option<String> {
+    let mut listed: Vec<&str> = Vec::new();
+    let mut length = 0;
+
+    for term in terms {
+        let text = term.term.trim();
+        if text.is_empty() || listed.iter().any(|t| t.eq_ignore_ascii_case(text)) {
+            continue;
+        }
+        if length + text.len() + 2 > MAX_PROMPT_CHARS {
+            break;
+        }
+        length += text.len() + 2;
+        listed.push(text);
+    }
+
+    (!listed.is_empty()).then(|| format!("Glossary: {}.", listed.join(", ")))
+}
+
+/// `text` with every variant of `term` replaced by the term, or None if nothing matched.
+pub fn correct(text: &str, term: &GlossaryTerm) -> Option<String> {
+    let mut corrected = text.to_string();
+    let mut changed = false;
+
+    for variant in &term.variants {
+        let variant = variant.trim();
+        if variant.is_empty() || variant == term.term {
+            continue;
+        }
+        if let Some(replaced) = replace_word(&corrected, variant, &term.term) {
+            corrected = replaced;
+            changed = true;
+        }
+    }
+
+    (changed && corrected != text).then_some(corrected)
+}
+
+/// `words` of a segment re-derived for its rewritten `text`. Words the text
+/// still has keep their timing and probability; each run of changed words
+/// shares the span of the words it replaced and the lowest of their
+/// probabilities. Segments saved without words stay without.
+pub fn realign_words(words: &[SegmentWord], text: &str) -> Vec<SegmentWord> {
+    let Some(first) = words.first() else { return Vec::new() };
+    let new: Vec<&str> = text.split_whitespace().collect();
+
+    // Longest common subsequence of the old and new words
+    let mut common = vec![vec![0usize; new.len() + 1]; words.len() + 1];
+    for i in (0..words.len()).rev() {
+        for j in (0..new.len()).rev() {
+            common[i][j] = if words[i].text == new[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+
+    let mut realigned = Vec::with_capacity(new.len());
+    let (mut i, mut j) = (0, 0);
+    while i < words.len() || j < new.len() {
+        if i < words.len() && j < new.len() && words[i].text == new[j] {
+            realigned.push(words[i].clone());
+            i += 1;
+            j += 1;
+            continue;
+        }
+
+        // Collect the run of words that differ up to the next common word
+        let (run_start, new_start) = (i, j);
+        while i < words.len() || j < new.len() {
+            if i < words.len() && j < new.len() && words[i].text == new[j] {
+                break;
+            }
+            if j == new.len() || (i < words.len() && common[i + 1][j] >= common[i][j + 1]) {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        let replaced = &words[run_start..i];
+        let inserted = &new[new_start..j];
+        if inserted.is_empty() {
+            continue;
+        }
+
+        // Without replaced words, the new ones sit in the gap between their neighbours
+        let start = replaced.first()
+            .map(|word| word.start_time)
+            .or_else(|| realigned.last().map(|word: &SegmentWord| word.end_time))
+            .unwrap_or(first.start_time);
+        let end = replaced.last()
+            .map(|word| word.end_time)
+            .or_else(|| words.get(i).map(|word| word.start_time))
+            .unwrap_or(start)
+            .max(start);
+        let probability = replaced.iter()
+            .map(|word| word.probability)
+            .reduce(f64::min)
+            .unwrap_or(1.0);
+
+        let step = (end - start) / inserted.len() as f64;
+        for (k, text) in inserted.iter().enumerate() {
+            realigned.push(SegmentWord {
+                segment_id: first.segment_id.clone(),
+                meeting_id: first.meeting_id.clone(),
+                start_time: start + step * k as f64,
+                end_time: start + step * (k + 1) as f64,
+                text: text.to_string(),
+                probability,
+            });
+        }
+    }
+
+    realigned
+}
+
+/// Replace whole-word, case-insensitive occurrences of `from` with `to`.
+fn replace_word(text: &str, from: &str, to: &str) -> Option<String> {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    let mut previous: Option<char> = None;
+    let mut changed = false;
+
+    while !rest.is_empty() {
+        let at_word_start = !previous.is_some_and(char::is_alphanumeric);
+        if at_word_start {
+            if let Some(len) = match_len(rest, from) {
+                let at_word_end = !rest[len..].chars().next().is_some_and(char::is_alphanumeric);
+                if at_word_end {
+                    result.push_str(to);
+                    previous = rest[..len].chars().last();
+                    rest = &rest[len..];
+                    changed = true;
+                    continue;
+                }
+            }
+        }
+
+        let c = rest.chars().next().unwrap_or_default();
+        result.push(c);
+        previous = Some(c);
+        rest = &rest[c.len_utf8()..];
+    }
+
+    changed.then_some(result)
+}
+
+/// Byte length of the prefix of `text` that equals `pattern` ignoring case.
+fn match_len(text: &str, pattern: &str) -> Option<usize> {
+    let mut text_chars = text.char_indices();
+    for p in pattern.chars() {
+        let (_, t) = text_chars.next()?;
+        if !t.to_lowercase().eq(p.to_lowercase()) {
+            return None;
+        }
+    }
+    Some(text_chars.next().map(|(i, _)| i).unwrap_or(text.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(timed: &[(&str, f64, f64, f64)]) -> Vec<SegmentWord> {
+        timed.iter()
+            .map(|&(text, start_time, end_time, probability)| SegmentWord {
+                segment_id: "segment".to_string(),
+                meeting_id: "meeting".to_string(),
+                start_time,
+                end_time,
+                text: text.to_string(),
+                probability,
+            })
+            .collect()
+    }
+
+    // Times to the millisecond, so split spans compare exactly
+    fn summary(words: &[SegmentWord]) -> Vec<(&str, f64, f64, f64)> {
+        let ms = |seconds: f64| (seconds * 1000.0).round() / 1000.0;
+        words.iter()
+            .map(|word| (word.text.as_str(), ms(word.start_time), ms(word.end_time), word.probability))
+            .collect()
+    }
+
+    #[test]
+    fn replaced_words_share_the_span_of_the_words_they_replace() {
+        let original = words(&[
+            ("Run", 0.0, 0.5, 0.9),
+            ("Cube", 0.5, 1.0, 0.4),
+            ("Control", 1.0, 1.6, 0.6),
+            ("apply", 1.6, 2.0, 0.8),
+        ]);
+
+        let corrected = realign_words(&original, "Run kubectl apply");
+
+        assert_eq!(summary(&corrected), vec![
+            ("Run", 0.0, 0.5, 0.9),
+            ("kubectl", 0.5, 1.6, 0.4),
+            ("apply", 1.6, 2.0, 0.8),
+        ]);
+        assert!(corrected.iter().all(|word| word.segment_id == "segment" && word.meeting_id == "meeting"));
+
+        // Undoing splits the span again
+        let reverted = realign_words(&corrected, "Run Cube Control apply");
+        assert_eq!(summary(&reverted), vec![
+            ("Run", 0.0, 0.5, 0.9),
+            ("Cube", 0.5, 1.05, 0.4),
+            ("Control", 1.05, 1.6, 0.4),
+            ("apply", 1.6, 2.0, 0.8),
+        ]);
+    }
+
+    #[test]
+    fn every_occurrence_is_realigned() {
+        let original = words(&[
+            ("toko", 0.0, 0.4, 0.5),
+            ("pedia", 0.4, 0.8, 0.7),
+            ("and", 0.8, 1.0, 0.9),
+            ("toko", 1.0, 1.3, 0.3),
+            ("pedia.", 1.3, 1.8, 0.6),
+        ]);
+
+        let corrected = realign_words(&original, "Tokopedia and Tokopedia.");
+
+        assert_eq!(summary(&corrected), vec![
+            ("Tokopedia", 0.0, 0.8, 0.5),
+            ("and", 0.8, 1.0, 0.9),
+            ("Tokopedia.", 1.0, 1.8, 0.3),
+        ]);
+    }
+
+    #[test]
+    fn segments_without_words_stay_without() {
+        assert!(realign_words(&[], "Run kubectl apply").is_empty());
+    }
+
+    #[test]
+    fn correct_matches_whole_words_ignoring_case() {
+        let term = GlossaryTerm::new(None, "kubectl".to_string(), vec!["cube control".to_string()]);
+
+        assert_eq!(correct("Run Cube Control apply", &term).as_deref(), Some("Run kubectl apply"));
+        assert_eq!(correct("Cube controls", &term), None);
+    }
+}
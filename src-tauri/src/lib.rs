@@ -1,41 +1,125 @@
 use std::sync::{Arc, Mutex};
-use tauri::{AppHandle, Emitter, State};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::collections::HashMap;
+use tauri::{AppHandle, Emitter, Manager, State};
 use std::path::{PathBuf, Path};
 use chrono::{DateTime, Utc, Timelike};
-use whisper_rs::{WhisperContext, WhisperContextParameters};
+use whisper_rs::WhisperContext;
 use std::thread;
 use std::sync::mpsc;
 use std::time::Duration;
 use serde::{Deserialize, Serialize};
 use uuid;
 
+mod ai;
 mod database;
-use database::{Database, Meeting, MeetingSegment};
+mod migrations;
+mod diarization;
+mod recording;
+mod vad;
+mod realtime;
+mod models;
+mod transcription;
+mod glossary;
+mod decode;
+mod resample;
+mod flac;
+mod archive;
+mod loudness;
+use ai::{AiProvider, Cancellation, ChatProvider};
+use archive::RecordingStorage;
+use loudness::AudioProcessing;
+use database::{ActionItem, Database, GlossaryTerm, Meeting, MeetingSegment, RecordingPause, SearchResult, SegmentWord, TranscriptCorrection};
+use recording::{RecordingChannels, RecordingWriter};
+use transcription::{ChannelMix, TranscriptionOptions};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TranscriptionSegment {
     pub start: f32,
     pub end: f32,
     pub text: String,
+    #[serde(default)]
+    pub speaker: Option<String>,
+    // "local" (microphone) or "remote" (system audio), when the channels were kept apart
+    #[serde(default)]
+    pub source: Option<String>,
+    // tinydiarize marker: the speaker changes after this segment
+    #[serde(skip)]
+    pub speaker_turn_next: bool,
+    // Mean probability of the segment's tokens (0-1)
+    #[serde(default)]
+    pub confidence: Option<f32>,
+    #[serde(default)]
+    pub words: Vec<TranscriptionWord>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranscriptionWord {
+    pub start: f32,
+    pub end: f32,
+    pub text: String,
+    // Probability of the word's least likely token (0-1)
+    pub probability: f32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TranscriptionResult {
     pub segments: Vec<TranscriptionSegment>,
     pub full_text: String,
+    // Language Whisper transcribed in, e.g. "en"
+    #[serde(default)]
+    pub language: Option<String>,
+    // How sure Whisper was of `language` when it had to detect it (0-1)
+    #[serde(default)]
+    pub language_probability: Option<f32>,
+}
+
+/// Payload of the "realtime-transcript" event. Times are seconds into the recording.
+#[derive(Debug, Clone, Serialize)]
+pub struct RealtimeTranscript {
+    pub meeting_id: Option<String>,
+    pub chunk_index: usize,
+    pub start: f32,
+    pub end: f32,
+    // Final segments, saved to the meeting; `text` joins them
+    pub segments: Vec<TranscriptionSegment>,
+    pub text: String,
+    // Tentative end of the transcript, replaced by the next event
+    pub provisional: Vec<TranscriptionSegment>,
+    pub language: Option<String>,
+    pub language_probability: Option<f32>,
 }
 
 
+/// The capture thread of one recording and the flag that stops it. Each
+/// recording gets its own flag, so threads of an earlier recording can't
+/// mistake a new one for their own.
+struct CaptureHandle {
+    stop: Arc<AtomicBool>,
+    thread: thread::JoinHandle<()>,
+}
+
 pub struct AudioState {
     is_recording: Arc<Mutex<bool>>,
+    capture: Arc<Mutex<Option<CaptureHandle>>>,
+    // Streams keep running while paused, but their audio is discarded
+    is_paused: Arc<Mutex<bool>>,
+    pauses: Arc<Mutex<Vec<RecordingPause>>>,
     start_time: Arc<Mutex<Option<DateTime<Utc>>>>,
     output_path: Arc<Mutex<Option<PathBuf>>>,
     whisper_context: Arc<Mutex<Option<WhisperContext>>>,
+    whisper_model_path: Arc<Mutex<Option<PathBuf>>>,
+    // Optional smaller model for real-time transcription; the main model is used without it
+    realtime_whisper_context: Arc<Mutex<Option<WhisperContext>>>,
+    realtime_whisper_model_path: Arc<Mutex<Option<PathBuf>>>,
+    // Mixed audio waiting for real-time transcription; the recording itself goes to disk
     recording_data: Arc<Mutex<Vec<f32>>>,
+    recording_writer: Arc<Mutex<Option<RecordingWriter>>>,
+    recording_meeting_id: Arc<Mutex<Option<String>>>,
     // Real-time transcription
     is_realtime_enabled: Arc<Mutex<bool>>,
     transcript_sender: Arc<Mutex<Option<mpsc::Sender<String>>>>,
-    chunk_size: usize, // 30 seconds worth of samples at 16kHz
+    chunk_size: usize, // Longest real-time chunk; VAD usually cuts earlier at a pause
     // New fields for improved audio handling
     mic_data: Arc<Mutex<Vec<f32>>>,
     system_data: Arc<Mutex<Vec<f32>>>,
@@ -45,9 +129,15 @@ pub struct AudioState {
     // Audio gain settings
     mic_gain: Arc<Mutex<f32>>,
     system_gain: Arc<Mutex<f32>>,
+    // Saved in settings; loaded when a recording starts and followed while it runs
+    audio_processing: Arc<Mutex<AudioProcessing>>,
     // Device selection
     selected_mic_device: Arc<Mutex<Option<String>>>,
     selected_system_device: Arc<Mutex<Option<String>>>,
+    // Names of models being downloaded
+    model_downloads: Arc<Mutex<Vec<String>>>,
+    // Recordings whose loudness is being normalized; each receiver turns true when done
+    normalizing: Arc<Mutex<HashMap<PathBuf, tokio::sync::watch::Receiver<bool>>>>,
 }
 
 impl Default for AudioState {
@@ -60,13 +150,21 @@ impl AudioState {
     pub fn new() -> Self {
         Self {
             is_recording: Arc::new(Mutex::new(false)),
+            capture: Arc::new(Mutex::new(None)),
+            is_paused: Arc::new(Mutex::new(false)),
+            pauses: Arc::new(Mutex::new(Vec::new())),
             start_time: Arc::new(Mutex::new(None)),
             output_path: Arc::new(Mutex::new(None)),
             whisper_context: Arc::new(Mutex::new(None)),
+            whisper_model_path: Arc::new(Mutex::new(None)),
+            realtime_whisper_context: Arc::new(Mutex::new(None)),
+            realtime_whisper_model_path: Arc::new(Mutex::new(None)),
             recording_data: Arc::new(Mutex::new(Vec::new())),
+            recording_writer: Arc::new(Mutex::new(None)),
+            recording_meeting_id: Arc::new(Mutex::new(None)),
             is_realtime_enabled: Arc::new(Mutex::new(false)),
             transcript_sender: Arc::new(Mutex::new(None)),
-            chunk_size: 16000 * 10, // 30 seconds at 16kHz
+            chunk_size: 16000 * 10, // 10 seconds at 16kHz
             // Initialize new fields
             mic_data: Arc::new(Mutex::new(Vec::new())),
             system_data: Arc::new(Mutex::new(Vec::new())),
@@ -76,13 +174,35 @@ impl AudioState {
             // Initialize gain settings with improved default values
             mic_gain: Arc::new(Mutex::new(2.5)),
             system_gain: Arc::new(Mutex::new(1.5)),
+            audio_processing: Arc::new(Mutex::new(AudioProcessing::default())),
             // Device selection
             selected_mic_device: Arc::new(Mutex::new(None)),
             selected_system_device: Arc::new(Mutex::new(None)),
+            model_downloads: Arc::new(Mutex::new(Vec::new())),
+            normalizing: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+}
+
+pub struct MinutesState {
+    // Cancellation flags for in-flight streamed generations, keyed by meeting ID
+    cancel_flags: Arc<Mutex<HashMap<String, Arc<Cancellation>>>>,
+}
+
+impl MinutesState {
+    pub fn new() -> Self {
+        Self {
+            cancel_flags: Arc::new(Mutex::new(HashMap::new())),
         }
     }
 }
 
+impl Default for MinutesState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 pub struct DatabaseState {
     db: Arc<Mutex<Option<Database>>>,
 }
@@ -94,7 +214,14 @@ impl DatabaseState {
         }
     }
 
+    /// Open and migrate the database. Both startup recovery and the frontend call
+    /// this; the lock is held throughout so only the first call opens it.
     pub fn initialize(&self) -> Result<(), String> {
+        let mut db_guard = self.db.lock().map_err(|e| e.to_string())?;
+        if db_guard.is_some() {
+            return Ok(());
+        }
+
         let home_dir = dirs::home_dir()
             .ok_or("Could not find home directory")?;
         
@@ -108,7 +235,6 @@ impl DatabaseState {
         let database = Database::new(db_path)
             .map_err(|e| format!("Failed to initialize database: {}", e))?;
         
-        let mut db_guard = self.db.lock().map_err(|e| e.to_string())?;
         *db_guard = Some(database);
         
         println!("✅ Database initialized successfully");
@@ -299,13 +425,17 @@ async fn disable_realtime_transcription(state: State<'_, AudioState>) -> Result<
 async fn get_recording_status(state: State<'_, AudioState>) -> Result<String, String> {
     let is_recording = state.is_recording.lock().map_err(|e| e.to_string())?;
     let start_time = state.start_time.lock().map_err(|e| e.to_string())?;
-    let recording_data = state.recording_data.lock().map_err(|e| e.to_string())?;
+    let recording_writer = state.recording_writer.lock().map_err(|e| e.to_string())?;
+    let is_paused = state.is_paused.lock().map_err(|e| e.to_string())?;
     
     if *is_recording {
-        if let Some(start) = *start_time {
+        if *is_paused {
+            let samples = recording_writer.as_ref().map(|writer| writer.sample_count()).unwrap_or(0);
+            Ok(format!("Paused: {}s recorded", samples / recording::SAMPLE_RATE as usize))
+        } else if let Some(start) = *start_time {
             let duration = chrono::Utc::now().signed_duration_since(start);
             let seconds = duration.num_seconds();
-            let samples = recording_data.len();
+            let samples = recording_writer.as_ref().map(|writer| writer.sample_count()).unwrap_or(0);
             Ok(format!("Recording: {}s, {} samples", seconds, samples))
         } else {
             Ok("Recording: Starting...".to_string())
@@ -316,49 +446,76 @@ async fn get_recording_status(state: State<'_, AudioState>) -> Result<String, St
 }
 
 #[tauri::command]
-async fn initialize_whisper(state: State<'_, AudioState>) -> Result<String, String> {
-    let mut whisper_context = state.whisper_context.lock().map_err(|e| e.to_string())?;
+async fn initialize_whisper(state: State<'_, AudioState>, db_state: State<'_, DatabaseState>) -> Result<String, String> {
+    let selected_model = selected_whisper_model(&db_state, WHISPER_MODEL_SETTING);
+    {
+        let mut whisper_context = state.whisper_context.lock().map_err(|e| e.to_string())?;
+        
+        if whisper_context.is_some() {
+            return Ok("Whisper already initialized".to_string());
+        }
+        
+        let model_path = find_whisper_model(selected_model)?;
+        
+        // Initialize Whisper context
+        let (ctx, info) = models::load(&model_path)?;
+        println!("✅ Loaded {} ({} model) in {:.1}s", info.name, info.model_type, info.load_seconds);
+        
+        *whisper_context = Some(ctx);
+        if let Ok(mut path) = state.whisper_model_path.lock() {
+            *path = Some(model_path);
+        }
+    }
     
-    if whisper_context.is_some() {
-        return Ok("Whisper already initialized".to_string());
+    // A failed real-time model only means the main model does the real-time work too
+    if let Some(name) = selected_whisper_model(&db_state, REALTIME_WHISPER_MODEL_SETTING) {
+        let path = models::models_dir()?.join(&name);
+        if let Err(e) = swap_whisper_model(state.realtime_whisper_context.clone(), state.realtime_whisper_model_path.clone(), Some(path)).await {
+            eprintln!("⚠️ Failed to load real-time model {}: {}", name, e);
+        }
     }
     
+    Ok("Whisper initialized successfully".to_string())
+}
+
+/// The model to load: the one chosen in settings, or the best installed one.
+fn find_whisper_model(selected_model: Option<String>) -> Result<PathBuf, String> {
     // Try to find a Whisper model file
-    let home_dir = dirs::home_dir().ok_or("Could not find home directory")?;
-    let models_dir = home_dir.join("Documents").join("MeetingRecorder").join("MeetingRecordings").join("models");
-    std::fs::create_dir_all(&models_dir).map_err(|e| e.to_string())?;
+    let models_dir = models::models_dir()?;
     
     // Auto-setup: Check if models exist, if not try to set them up automatically
     auto_setup_models(&models_dir)?;
     
-    // Try multiple model options in order of preference
-    // Note: Prioritizing multilingual models for better Indonesian support
-    // Turbo model has issues with non-English languages (hallucinations)
-    let model_options = [
-        ("ggml-large-v3-turbo.bin", "Large V3 Turbo (Fast, supports Indonesian with language param)"),
-        ("ggml-large-v3.bin", "Large V3 Multilingual (RECOMMENDED: Best for Indonesian)"),
-        ("ggml-medium.bin", "Medium Multilingual (Good balance for Indonesian)"),
-        ("ggml-small.bin", "Small Multilingual (Faster, good for Indonesian)"),
-        ("ggml-small.en.bin", "Small English (English only)"),
-        ("ggml-base.en.bin", "Base English (English only)"),
-        ("ggml-medium.en.bin", "Medium English (English only)"),
-    ];
-    
     let mut model_path = None;
     let mut model_info = String::new();
     
-    for (filename, description) in &model_options {
-        let path = models_dir.join(filename);
+    // The model chosen in settings wins; otherwise try the known models in order of preference
+    if let Some(name) = selected_model {
+        let path = models_dir.join(&name);
         if path.exists() {
+            model_info = format!("Using selected model {}", name);
             model_path = Some(path);
-            model_info = format!("Using {}: {}", filename, description);
-            break;
+        } else {
+            println!("⚠️ Selected model {} not found, falling back to the default models", name);
+        }
+    }
+    
+    // Note: Prioritizing multilingual models for better Indonesian support
+    // Turbo model has issues with non-English languages (hallucinations)
+    if model_path.is_none() {
+        for (filename, description) in &models::MODEL_PRIORITY {
+            let path = models_dir.join(filename);
+            if path.exists() {
+                model_path = Some(path);
+                model_info = format!("Using {}: {}", filename, description);
+                break;
+            }
         }
     }
     
     let model_path = model_path.ok_or_else(|| {
         format!(
-            "No Whisper model found. Please download one of these models to {}:\n\
+            "No Whisper model found. Download one from the model manager in Settings, or copy one of these models to {}:\n\
             FOR INDONESIAN SUPPORT (RECOMMENDED):\n\
             1. ggml-large-v3.bin (Best accuracy for Indonesian)\n\
             2. ggml-medium.bin (Good balance for Indonesian)\n\
@@ -372,18 +529,367 @@ async fn initialize_whisper(state: State<'_, AudioState>) -> Result<String, Stri
     })?;
     
     println!("🎙️ {}", model_info);
-    
-    // Initialize Whisper context
-    let ctx_params = WhisperContextParameters::default();
-    let ctx = WhisperContext::new_with_params(&model_path.to_string_lossy(), ctx_params)
-        .map_err(|e| format!("Failed to initialize Whisper: {}", e))?;
-    
-    *whisper_context = Some(ctx);
-    Ok("Whisper initialized successfully".to_string())
+    Ok(model_path)
+}
+
+/// Replace the model in `context` with the one at `path`, or unload it if `path` is None.
+/// The new model loads on a blocking thread while transcriptions keep using the old
+/// one, so there is no window without a model; if it fails to load, the old model
+/// simply stays. Locking the context to swap them waits for transcriptions still
+/// using the old model, and transcriptions started meanwhile wait for the new one.
+async fn swap_whisper_model(
+    context: Arc<Mutex<Option<WhisperContext>>>,
+    model_path: Arc<Mutex<Option<PathBuf>>>,
+    path: Option<PathBuf>
+) -> Result<Option<models::LoadedModel>, String> {
+    let loaded = match path {
+        Some(path) => {
+            let load_path = path.clone();
+            let (ctx, info) = tauri::async_runtime::spawn_blocking(move || models::load(&load_path))
+                .await
+                .map_err(|e| format!("Failed to load {}: {}", path.display(), e))??;
+            println!("✅ Loaded {} ({} model) in {:.1}s", info.name, info.model_type, info.load_seconds);
+            Some((ctx, path, info))
+        }
+        None => None,
+    };
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut ctx_guard = context.lock().map_err(|e| e.to_string())?;
+        let mut path_guard = model_path.lock().map_err(|e| e.to_string())?;
+
+        let previous = path_guard.take();
+        match loaded {
+            Some((ctx, path, info)) => {
+                *ctx_guard = Some(ctx);
+                *path_guard = Some(path);
+                Ok(Some(info))
+            }
+            None => {
+                *ctx_guard = None;
+                if let Some(previous) = previous {
+                    println!("🗑️ Unloaded {}", previous.display());
+                }
+                Ok(None)
+            }
+        }
+    })
+    .await
+    .map_err(|e| format!("Failed to swap Whisper model: {}", e))?
+}
+
+const WHISPER_MODEL_SETTING: &str = "whisper_model";
+const REALTIME_WHISPER_MODEL_SETTING: &str = "whisper_realtime_model";
+
+fn selected_whisper_model(db_state: &DatabaseState, setting: &str) -> Option<String> {
+    let db_guard = db_state.get_db().ok()?;
+    db_guard.as_ref()?.get_setting(setting).ok()?
+}
+
+/// Path of an installed model, for a name that came from the frontend.
+fn installed_model_path(name: &str) -> Result<PathBuf, String> {
+    models::validate_model_name(name)?;
+    let path = models::models_dir()?.join(name);
+    if !path.exists() {
+        return Err(format!("Model {} is not installed", name));
+    }
+    Ok(path)
+}
+
+/// Switch the main model without restarting the app: `name`, or the selected
+/// model if none is given. A given model also becomes the selected one.
+#[tauri::command]
+async fn reload_whisper_model(
+    state: State<'_, AudioState>,
+    db_state: State<'_, DatabaseState>,
+    name: Option<String>
+) -> Result<models::LoadedModel, String> {
+    let path = match &name {
+        Some(name) => installed_model_path(name)?,
+        None => find_whisper_model(selected_whisper_model(&db_state, WHISPER_MODEL_SETTING))?,
+    };
+
+    println!("🔄 Reloading Whisper with {}", path.display());
+    let info = swap_whisper_model(state.whisper_context.clone(), state.whisper_model_path.clone(), Some(path)).await?
+        .ok_or("Model was not loaded")?;
+
+    if let Some(name) = name {
+        let db_guard = db_state.get_db()?;
+        let db = db_guard.as_ref().ok_or("Database not initialized")?;
+        db.set_setting(WHISPER_MODEL_SETTING, &name)
+            .map_err(|e| format!("Failed to save selected model: {}", e))?;
+    }
+
+    Ok(info)
+}
+
+/// Load `name` for real-time transcription only, e.g. a small model that keeps
+/// up with speech while the main model does the final transcription.
+/// With no name the real-time model is unloaded and the main model is used.
+#[tauri::command]
+async fn set_realtime_whisper_model(
+    state: State<'_, AudioState>,
+    db_state: State<'_, DatabaseState>,
+    name: Option<String>
+) -> Result<Option<models::LoadedModel>, String> {
+    let path = name.as_deref().map(installed_model_path).transpose()?;
+    let info = swap_whisper_model(state.realtime_whisper_context.clone(), state.realtime_whisper_model_path.clone(), path).await?;
+
+    let db_guard = db_state.get_db()?;
+    let db = db_guard.as_ref().ok_or("Database not initialized")?;
+    match name {
+        Some(name) => db.set_setting(REALTIME_WHISPER_MODEL_SETTING, &name),
+        None => db.delete_setting(REALTIME_WHISPER_MODEL_SETTING),
+    }.map_err(|e| format!("Failed to save real-time model: {}", e))?;
+
+    Ok(info)
+}
+
+#[tauri::command]
+async fn list_whisper_models(
+    state: State<'_, AudioState>,
+    db_state: State<'_, DatabaseState>
+) -> Result<Vec<models::ModelInfo>, String> {
+    let selected = selected_whisper_model(&db_state, WHISPER_MODEL_SETTING);
+    let loaded = state.whisper_model_path.lock().map_err(|e| e.to_string())?.clone();
+    let realtime = state.realtime_whisper_model_path.lock().map_err(|e| e.to_string())?.clone();
+    Ok(models::list_models(&models::models_dir()?, selected.as_deref(), loaded.as_deref(), realtime.as_deref()))
+}
+
+/// Make `name` the model used by `initialize_whisper`.
+#[tauri::command]
+async fn select_whisper_model(
+    state: State<'_, AudioState>,
+    db_state: State<'_, DatabaseState>,
+    name: String
+) -> Result<String, String> {
+    installed_model_path(&name)?;
+
+    {
+        let db_guard = db_state.get_db()?;
+        let db = db_guard.as_ref().ok_or("Database not initialized")?;
+        db.set_setting(WHISPER_MODEL_SETTING, &name)
+            .map_err(|e| format!("Failed to save selected model: {}", e))?;
+    }
+
+    let loaded = state.whisper_model_path.lock().map_err(|e| e.to_string())?.clone();
+    match loaded {
+        Some(path) if path.file_name().and_then(|n| n.to_str()) != Some(name.as_str()) => {
+            Ok(format!("Selected {}; reload Whisper to switch to it", name))
+        }
+        _ => Ok(format!("Selected {}", name)),
+    }
+}
+
+/// Download a model into the models directory, resuming an interrupted download.
+/// Without `url` the model comes from the whisper.cpp repository on Hugging Face,
+/// which also provides the checksum; other sources need `sha256`.
+/// Emits "model-download-progress" while downloading.
+#[tauri::command]
+async fn download_whisper_model(
+    app: AppHandle,
+    state: State<'_, AudioState>,
+    name: String,
+    url: Option<String>,
+    sha256: Option<String>
+) -> Result<models::ModelInfo, String> {
+    models::validate_model_name(&name)?;
+
+    {
+        let mut downloads = state.model_downloads.lock().map_err(|e| e.to_string())?;
+        if downloads.contains(&name) {
+            return Err(format!("{} is already being downloaded", name));
+        }
+        downloads.push(name.clone());
+    }
+
+    let result = download_model_file(&app, &name, url, sha256).await;
+
+    if let Ok(mut downloads) = state.model_downloads.lock() {
+        downloads.retain(|download| download != &name);
+    }
+
+    let path = result?;
+    let size_bytes = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+    println!("✅ Downloaded model {} ({} bytes)", name, size_bytes);
+
+    Ok(models::ModelInfo {
+        multilingual: models::is_multilingual(&name),
+        description: models::description(&name).map(|d| d.to_string()),
+        path: path.to_string_lossy().to_string(),
+        selected: false,
+        loaded: false,
+        realtime: false,
+        size_bytes,
+        name,
+    })
+}
+
+async fn download_model_file(app: &AppHandle, name: &str, url: Option<String>, sha256: Option<String>) -> Result<PathBuf, String> {
+    let client = reqwest::Client::new();
+
+    let (url, sha256) = match (url, sha256) {
+        (url, Some(sha256)) => (url.unwrap_or_else(|| models::default_url(name)), sha256),
+        (None, None) => (models::default_url(name), models::published_sha256(&client, name).await?),
+        (Some(_), None) => return Err("A SHA-256 checksum is required to download from a custom URL".to_string()),
+    };
+
+    let dest = models::models_dir()?.join(name);
+    if dest.exists() {
+        return Err(format!("Model {} is already installed", name));
+    }
+
+    println!("⬇️ Downloading {} from {}", name, url);
+    models::download(&client, &url, &dest, &sha256, |downloaded, total| {
+        let _ = app.emit("model-download-progress", serde_json::json!({
+            "name": name,
+            "downloaded_bytes": downloaded,
+            "total_bytes": total,
+        }));
+    }).await?;
+
+    Ok(dest)
+}
+
+#[tauri::command]
+async fn delete_whisper_model(
+    state: State<'_, AudioState>,
+    db_state: State<'_, DatabaseState>,
+    name: String
+) -> Result<String, String> {
+    models::validate_model_name(&name)?;
+    let path = models::models_dir()?.join(&name);
+
+    let loaded = state.whisper_model_path.lock().map_err(|e| e.to_string())?.clone();
+    let realtime = state.realtime_whisper_model_path.lock().map_err(|e| e.to_string())?.clone();
+    if loaded.as_deref() == Some(path.as_path()) || realtime.as_deref() == Some(path.as_path()) {
+        return Err(format!("{} is in use and can't be deleted", name));
+    }
+
+    // Also drop what is left of an interrupted download
+    let part_path = models::part_path(&path);
+    if part_path.exists() {
+        std::fs::remove_file(&part_path)
+            .map_err(|e| format!("Failed to delete partial download: {}", e))?;
+    }
+    if path.exists() {
+        std::fs::remove_file(&path)
+            .map_err(|e| format!("Failed to delete model: {}", e))?;
+    }
+
+    for setting in [WHISPER_MODEL_SETTING, REALTIME_WHISPER_MODEL_SETTING] {
+        if selected_whisper_model(&db_state, setting).as_deref() == Some(name.as_str()) {
+            let db_guard = db_state.get_db()?;
+            if let Some(db) = db_guard.as_ref() {
+                db.delete_setting(setting)
+                    .map_err(|e| format!("Failed to clear selected model: {}", e))?;
+            }
+        }
+    }
+
+    println!("🗑️ Deleted model {}", name);
+    Ok(format!("Deleted {}", name))
+}
+
+/// Options of the preset chosen in settings, for transcriptions that aren't given any.
+fn active_transcription_options(db_state: &DatabaseState) -> TranscriptionOptions {
+    let Ok(db_guard) = db_state.get_db() else { return TranscriptionOptions::default() };
+    let Some(db) = db_guard.as_ref() else { return TranscriptionOptions::default() };
+
+    let saved = db.get_setting(transcription::PRESETS_SETTING).ok().flatten();
+    let active = db.get_setting(transcription::ACTIVE_PRESET_SETTING).ok().flatten()
+        .unwrap_or_else(|| transcription::DEFAULT_PRESET.to_string());
+
+    transcription::find_preset(saved.as_deref(), &active).unwrap_or_default()
+}
+
+#[tauri::command]
+async fn get_transcription_presets(db_state: State<'_, DatabaseState>) -> Result<transcription::TranscriptionPresets, String> {
+    let db_guard = db_state.get_db()?;
+    let db = db_guard.as_ref().ok_or("Database not initialized")?;
+
+    let saved = db.get_setting(transcription::PRESETS_SETTING)
+        .map_err(|e| format!("Failed to load transcription presets: {}", e))?;
+    let presets = transcription::all_presets(saved.as_deref());
+
+    // A deleted preset can't stay active
+    let active = db.get_setting(transcription::ACTIVE_PRESET_SETTING)
+        .map_err(|e| format!("Failed to load transcription presets: {}", e))?
+        .filter(|name| presets.iter().any(|preset| &preset.name == name))
+        .unwrap_or_else(|| transcription::DEFAULT_PRESET.to_string());
+
+    Ok(transcription::TranscriptionPresets { active, presets })
+}
+
+/// Save `options` as the preset `name`. Saving under a built-in name replaces
+/// the built-in options until the preset is deleted.
+#[tauri::command]
+async fn save_transcription_preset(
+    db_state: State<'_, DatabaseState>,
+    name: String,
+    options: TranscriptionOptions
+) -> Result<(), String> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err("Preset name cannot be empty".to_string());
+    }
+
+    let db_guard = db_state.get_db()?;
+    let db = db_guard.as_ref().ok_or("Database not initialized")?;
+
+    let saved = db.get_setting(transcription::PRESETS_SETTING)
+        .map_err(|e| format!("Failed to load transcription presets: {}", e))?;
+    let mut presets = transcription::parse_saved(saved.as_deref());
+    presets.insert(name, options);
+
+    let json = serde_json::to_string(&presets).map_err(|e| e.to_string())?;
+    db.set_setting(transcription::PRESETS_SETTING, &json)
+        .map_err(|e| format!("Failed to save transcription preset: {}", e))
+}
+
+/// Delete a saved preset. Built-in presets go back to their default options.
+#[tauri::command]
+async fn delete_transcription_preset(db_state: State<'_, DatabaseState>, name: String) -> Result<(), String> {
+    let db_guard = db_state.get_db()?;
+    let db = db_guard.as_ref().ok_or("Database not initialized")?;
+
+    let saved = db.get_setting(transcription::PRESETS_SETTING)
+        .map_err(|e| format!("Failed to load transcription presets: {}", e))?;
+    let mut presets = transcription::parse_saved(saved.as_deref());
+    if presets.remove(&name).is_none() && !transcription::is_builtin(&name) {
+        return Err(format!("Preset {} not found", name));
+    }
+
+    let json = serde_json::to_string(&presets).map_err(|e| e.to_string())?;
+    db.set_setting(transcription::PRESETS_SETTING, &json)
+        .map_err(|e| format!("Failed to delete transcription preset: {}", e))
+}
+
+/// Make `name` the preset used for real-time transcription and by default.
+#[tauri::command]
+async fn set_active_transcription_preset(db_state: State<'_, DatabaseState>, name: String) -> Result<TranscriptionOptions, String> {
+    let db_guard = db_state.get_db()?;
+    let db = db_guard.as_ref().ok_or("Database not initialized")?;
+
+    let saved = db.get_setting(transcription::PRESETS_SETTING)
+        .map_err(|e| format!("Failed to load transcription presets: {}", e))?;
+    let options = transcription::find_preset(saved.as_deref(), &name)
+        .ok_or_else(|| format!("Preset {} not found", name))?;
+
+    db.set_setting(transcription::ACTIVE_PRESET_SETTING, &name)
+        .map_err(|e| format!("Failed to save active preset: {}", e))?;
+
+    Ok(options)
 }
 
 #[tauri::command]
-async fn transcribe_audio(state: State<'_, AudioState>, audio_path: String, language: Option<String>) -> Result<String, String> {
+async fn transcribe_audio(
+    state: State<'_, AudioState>,
+    audio_path: String,
+    language: Option<String>,
+    options: Option<TranscriptionOptions>
+) -> Result<String, String> {
+    wait_for_normalization(&state, Path::new(&audio_path)).await;
     let whisper_context = state.whisper_context.lock().map_err(|e| e.to_string())?;
     
     if whisper_context.is_none() {
@@ -395,15 +901,17 @@ async fn transcribe_audio(state: State<'_, AudioState>, audio_path: String, lang
         return Err(format!("Audio file not found: {}", audio_path));
     }
     
+    let options = options.unwrap_or_default();
+    
     // Load and validate audio file
-    let audio_data = match load_audio_file(&audio_path) {
+    let audio_data = match load_audio_file(&audio_path, options.channel_mix) {
         Ok(data) => data,
         Err(e) => return Err(format!("Failed to process audio file: {}", e))
     };
     
     // Perform actual transcription
     if let Some(ref ctx) = *whisper_context {
-        match transcribe_with_whisper(ctx, &audio_data, language.as_deref()) {
+        match transcribe_with_whisper(ctx, &audio_data, language.as_deref(), &options) {
             Ok(transcript) => {
                 let duration = audio_data.len() as f32 / 16000.0;
                 Ok(format!(
@@ -418,8 +926,23 @@ async fn transcribe_audio(state: State<'_, AudioState>, audio_path: String, lang
     }
 }
 
+/// `meeting_id` adds that meeting's glossary terms to the prompt, after the shared ones.
+/// With `trim_silence`, long silences are cut before Whisper runs; segment times still refer to the file.
 #[tauri::command]
-async fn transcribe_audio_with_segments(state: State<'_, AudioState>, audio_path: String, language: Option<String>) -> Result<TranscriptionResult, String> {
+#[allow(clippy::too_many_arguments)] // Tauri passes command arguments individually
+async fn transcribe_audio_with_segments(
+    state: State<'_, AudioState>,
+    db_state: State<'_, DatabaseState>,
+    audio_path: String,
+    meeting_id: Option<String>,
+    language: Option<String>,
+    diarize: Option<bool>,
+    num_speakers: Option<usize>,
+    trim_silence: Option<bool>,
+    options: Option<TranscriptionOptions>
+) -> Result<TranscriptionResult, String> {
+    wait_for_normalization(&state, Path::new(&audio_path)).await;
+    let vocabulary = glossary_prompt(&db_state, meeting_id.as_deref());
     let whisper_context = state.whisper_context.lock().map_err(|e| e.to_string())?;
     
     if whisper_context.is_none() {
@@ -431,16 +954,53 @@ async fn transcribe_audio_with_segments(state: State<'_, AudioState>, audio_path
         return Err(format!("Audio file not found: {}", audio_path));
     }
     
+    let options = options.unwrap_or_default();
+    
     // Load and validate audio file
-    let audio_data = match load_audio_file(&audio_path) {
+    let audio_data = match load_audio_file(&audio_path, options.channel_mix) {
         Ok(data) => data,
         Err(e) => return Err(format!("Failed to process audio file: {}", e))
     };
     
+    // tinydiarize models mark speaker turns; other models rely on clustering alone
+    let tdrz = state.whisper_model_path.lock()
+        .map(|path| path.as_ref().map(|p| p.to_string_lossy().contains("tdrz")).unwrap_or(false))
+        .unwrap_or(false);
+
+    // Whisper only sees speech; segment times are mapped back to the recording
+    let trimmed = trim_silence.unwrap_or(false).then(|| vad::trim_silence(&audio_data));
+    let speech_audio = trimmed.as_ref().map(|(audio, _)| audio.as_slice()).unwrap_or(&audio_data);
+    if trimmed.is_some() {
+        println!("🔇 Trimmed {:.1}s of silence before transcription",
+                 (audio_data.len() - speech_audio.len()) as f32 / 16000.0);
+    }
+
     // Perform actual transcription with segments
     if let Some(ref ctx) = *whisper_context {
-        match transcribe_with_whisper_segments(ctx, &audio_data, language.as_deref()) {
-            Ok(result) => Ok(result),
+        match transcribe_with_whisper_segments(ctx, speech_audio, language.as_deref(), tdrz, &options, vocabulary.as_deref(), None) {
+            Ok(mut result) => {
+                if let Some((_, map)) = &trimmed {
+                    for segment in result.segments.iter_mut() {
+                        segment.start = map.start_to_original(segment.start);
+                        segment.end = map.end_to_original(segment.end);
+                        for word in segment.words.iter_mut() {
+                            word.start = map.start_to_original(word.start);
+                            word.end = map.end_to_original(word.end);
+                        }
+                    }
+                }
+                if diarize.unwrap_or(true) {
+                    let options = diarization::DiarizationOptions {
+                        num_speakers,
+                        ..Default::default()
+                    };
+                    diarize_segments(&audio_data, &mut result.segments, tdrz, &options);
+                }
+                if let Some((mic, system)) = load_channel_tracks(Path::new(&audio_path)) {
+                    label_segment_sources(&mut result.segments, &mic, &system);
+                }
+                Ok(result)
+            }
             Err(e) => Err(format!("Transcription failed: {}", e))
         }
     } else {
@@ -448,91 +1008,261 @@ async fn transcribe_audio_with_segments(state: State<'_, AudioState>, audio_path
     }
 }
 
-// Audio processing helper functions
-fn resample_audio(input: &[f32], input_rate: u32, output_rate: u32) -> Vec<f32> {
-    if input_rate == output_rate {
-        return input.to_vec();
-    }
-    
-    let ratio = input_rate as f64 / output_rate as f64;
-    let output_len = (input.len() as f64 / ratio) as usize;
-    let mut output = Vec::with_capacity(output_len);
-    
-    for i in 0..output_len {
-        let src_index = (i as f64 * ratio) as usize;
-        if src_index < input.len() {
-            // Linear interpolation for better quality
-            let next_index = (src_index + 1).min(input.len() - 1);
-            let fraction = (i as f64 * ratio) - src_index as f64;
-            let sample = input[src_index] * (1.0 - fraction as f32) + input[next_index] * fraction as f32;
-            output.push(sample);
-        }
+/// Label every segment with a speaker ("Speaker 1", "Speaker 2", ...).
+fn diarize_segments(audio_data: &[f32], segments: &mut [TranscriptionSegment], tdrz: bool, options: &diarization::DiarizationOptions) {
+    let spans: Vec<diarization::SegmentSpan> = segments.iter()
+        .map(|segment| diarization::SegmentSpan {
+            start: segment.start,
+            end: segment.end,
+            speaker_turn_next: segment.speaker_turn_next,
+        })
+        .collect();
+
+    let speakers = diarization::assign_speakers(audio_data, &spans, tdrz, options);
+    for (segment, speaker) in segments.iter_mut().zip(&speakers) {
+        segment.speaker = Some(diarization::speaker_label(*speaker));
     }
-    
-    output
-}
 
-fn convert_i16_to_f32(input: &[i16]) -> Vec<f32> {
-    input.iter().map(|&sample| sample as f32 / 32768.0).collect()
+    let speaker_count = speakers.iter().max().map(|max| max + 1).unwrap_or(0);
+    println!("🗣️ Diarization found {} speakers in {} segments", speaker_count, segments.len());
 }
 
-fn convert_to_mono(input: &[f32], channels: u16) -> Vec<f32> {
-    if channels == 1 {
-        return input.to_vec();
-    }
-    
-    let mut mono = Vec::with_capacity(input.len() / channels as usize);
-    for chunk in input.chunks(channels as usize) {
-        let sum: f32 = chunk.iter().sum();
-        mono.push(sum / channels as f32);
-    }
-    mono
-}
+/// Mark each segment as local or remote by which track carries more of its energy.
+/// The microphone also picks up speaker bleed, so "local" needs a clear margin.
+fn label_segment_sources(segments: &mut [TranscriptionSegment], mic: &[f32], system: &[f32]) {
+    const LOCAL_MARGIN: f32 = 2.0; // mic RMS must be ~6 dB above system RMS
+    const SILENCE_RMS: f32 = 1e-4;
 
-fn mix_audio_streams(mic_data: &[f32], system_data: &[f32], mic_gain: f32, system_gain: f32) -> Vec<f32> {
-    let max_len = mic_data.len().max(system_data.len());
-    let mut mixed = Vec::with_capacity(max_len);
-    
-    for i in 0..max_len {
-        let mic_sample = mic_data.get(i).copied().unwrap_or(0.0) * mic_gain;
-        let system_sample = system_data.get(i).copied().unwrap_or(0.0) * system_gain;
-        
-        // Mix with soft clipping to prevent distortion
-        let mixed_sample = mic_sample + system_sample;
-        let clipped = if mixed_sample > 1.0 {
-            1.0 - (1.0 / (1.0 + (mixed_sample - 1.0)))
-        } else if mixed_sample < -1.0 {
-            -1.0 + (1.0 / (1.0 + (-mixed_sample - 1.0)))
+    let rms = |track: &[f32], start: usize, end: usize| -> f32 {
+        let window = &track[start.min(track.len())..end.min(track.len())];
+        if window.is_empty() {
+            return 0.0;
+        }
+        (window.iter().map(|s| s * s).sum::<f32>() / window.len() as f32).sqrt()
+    };
+
+    let mut local_count = 0;
+    for segment in segments.iter_mut() {
+        let start = (segment.start.max(0.0) * recording::SAMPLE_RATE as f32) as usize;
+        let end = (segment.end.max(0.0) * recording::SAMPLE_RATE as f32) as usize;
+        let mic_rms = rms(mic, start, end);
+        let system_rms = rms(system, start, end);
+
+        segment.source = if mic_rms < SILENCE_RMS && system_rms < SILENCE_RMS {
+            None
+        } else if mic_rms > system_rms * LOCAL_MARGIN {
+            local_count += 1;
+            Some("local".to_string())
         } else {
-            mixed_sample
+            Some("remote".to_string())
         };
-        
-        mixed.push(clipped);
     }
-    
-    mixed
+
+    println!("🎚️ Labelled {} of {} segments as local speaker", local_count, segments.len());
 }
 
-fn transcribe_with_whisper(ctx: &WhisperContext, audio_data: &[f32], language: Option<&str>) -> Result<String, String> {
-    use whisper_rs::{FullParams, SamplingStrategy};
-    
-    let _duration = audio_data.len() as f32 / 16000.0;
-    
-    // Check if audio is too short
-    if audio_data.len() < 1600 { // Less than 0.1 seconds at 16kHz
-        return Ok("(Audio too short for transcription)".to_string());
+/// Turn the segments saved during real-time transcription into the meeting's
+/// transcript, so a recording doesn't have to be transcribed again after it stops.
+/// Speakers and sources are labelled from the recording as in a full transcription.
+#[tauri::command]
+async fn finalize_realtime_transcript(
+    app: AppHandle,
+    state: State<'_, AudioState>,
+    db_state: State<'_, DatabaseState>,
+    meeting_id: String,
+    diarize: Option<bool>,
+    num_speakers: Option<usize>
+) -> Result<TranscriptionResult, String> {
+    let (mut meeting, saved_segments, saved_words) = {
+        let db_guard = db_state.get_db()?;
+        let db = db_guard.as_ref().ok_or("Database not initialized")?;
+        let meeting = db.get_meeting(&meeting_id)
+            .map_err(|e| format!("Failed to get meeting: {}", e))?
+            .ok_or("Meeting not found")?;
+        let segments = db.get_meeting_segments(&meeting_id)
+            .map_err(|e| format!("Failed to get segments: {}", e))?;
+        let words = db.get_meeting_words(&meeting_id, None)
+            .map_err(|e| format!("Failed to get words: {}", e))?;
+        (meeting, segments, words)
+    };
+
+    if saved_segments.is_empty() {
+        return Err("No real-time transcript was saved for this meeting".to_string());
     }
-    
-    // Create a new state for this transcription
-    let mut state = ctx.create_state()
-        .map_err(|e| format!("Failed to create Whisper state: {}", e))?;
-    
-    // Set up parameters for transcription
-    let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
-    
-    // Configure parameters for better transcription
-    params.set_n_threads(4); // Use 4 threads for faster processing
-    params.set_translate(false); // Don't translate, keep original language
+
+    let mut segments: Vec<TranscriptionSegment> = saved_segments.into_iter()
+        .map(|segment| TranscriptionSegment {
+            start: segment.start_time as f32,
+            end: segment.end_time as f32,
+            text: segment.text,
+            speaker: segment.speaker,
+            source: segment.source,
+            speaker_turn_next: false,
+            confidence: segment.confidence.map(|confidence| confidence as f32),
+            words: saved_words.iter()
+                .filter(|word| word.segment_id == segment.id)
+                .map(|word| TranscriptionWord {
+                    start: word.start_time as f32,
+                    end: word.end_time as f32,
+                    text: word.text.clone(),
+                    probability: word.probability as f32,
+                })
+                .collect(),
+        })
+        .collect();
+
+    if let Some(ref audio_path) = meeting.audio_file_path {
+        wait_for_normalization(&state, Path::new(audio_path)).await;
+        if diarize.unwrap_or(true) {
+            match load_audio_file(audio_path, ChannelMix::Average) {
+                Ok(audio_data) => {
+                    let options = diarization::DiarizationOptions {
+                        num_speakers,
+                        ..Default::default()
+                    };
+                    diarize_segments(&audio_data, &mut segments, false, &options);
+                }
+                Err(e) => eprintln!("⚠️ Skipping diarization of real-time transcript: {}", e),
+            }
+        }
+        if let Some((mic, system)) = load_channel_tracks(Path::new(audio_path)) {
+            label_segment_sources(&mut segments, &mic, &system);
+        }
+    }
+
+    let mut full_text = segments.iter().map(|segment| segment.text.as_str()).collect::<Vec<_>>().join(" ");
+
+    let db_guard = db_state.get_db()?;
+    let db = db_guard.as_ref().ok_or("Database not initialized")?;
+
+    meeting.transcript = Some(full_text.clone());
+    db.update_meeting(&meeting)
+        .map_err(|e| format!("Failed to update meeting with transcript: {}", e))?;
+
+    db.delete_meeting_segments(&meeting_id)
+        .map_err(|e| format!("Failed to delete existing segments: {}", e))?;
+    let segment_ids = segments.iter()
+        .map(|segment| save_segment(db, &meeting_id, segment))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    println!("✅ Saved real-time transcript of meeting {} ({} segments)", meeting_id, segments.len());
+
+    let corrections = apply_glossary(db, &mut meeting)?;
+    for correction in &corrections {
+        if let Some(index) = segment_ids.iter().position(|id| *id == correction.segment_id) {
+            segments[index].text = correction.corrected_text.clone();
+        }
+    }
+    if !corrections.is_empty() {
+        full_text = meeting.transcript.clone().unwrap_or_default();
+    }
+
+    archive_after_transcription(&app, &meeting_id);
+
+    Ok(TranscriptionResult {
+        segments,
+        full_text,
+        language: meeting.language,
+        language_probability: None,
+    })
+}
+
+// Audio processing helper functions
+fn convert_i16_to_f32(input: &[i16]) -> Vec<f32> {
+    input.iter().map(|&sample| sample as f32 / 32768.0).collect()
+}
+
+fn convert_to_mono(input: &[f32], channels: u16) -> Vec<f32> {
+    if channels == 1 {
+        return input.to_vec();
+    }
+    
+    let mut mono = Vec::with_capacity(input.len() / channels as usize);
+    for chunk in input.chunks(channels as usize) {
+        let sum: f32 = chunk.iter().sum();
+        mono.push(sum / channels as f32);
+    }
+    mono
+}
+
+/// Mono audio from interleaved `input` as chosen by `mix`.
+fn downmix(input: &[f32], channels: u16, sample_rate: u32, mix: ChannelMix) -> Result<Vec<f32>, String> {
+    const FRAME_SECONDS: f32 = 0.02; // Loudest channel is picked per 20ms
+    const CROSSFADE_FRACTION: usize = 4; // Fade over the first quarter of a frame when switching
+
+    let channels = channels.max(1) as usize;
+    if channels == 1 {
+        return Ok(input.to_vec());
+    }
+
+    match mix {
+        ChannelMix::Average => Ok(convert_to_mono(input, channels as u16)),
+        ChannelMix::Channel(channel) => {
+            let channel = channel as usize;
+            if channel >= channels {
+                return Err(format!("Channel {} was chosen, but the file has only {} channels", channel + 1, channels));
+            }
+            Ok(input.chunks_exact(channels).map(|frame| frame[channel]).collect())
+        }
+        ChannelMix::Loudest => {
+            let frame_len = ((sample_rate as f32 * FRAME_SECONDS) as usize).max(1);
+            let fade_len = (frame_len / CROSSFADE_FRACTION).max(1);
+            let mut mono = Vec::with_capacity(input.len() / channels);
+            let mut previous: Option<usize> = None;
+
+            for block in input.chunks(frame_len * channels) {
+                let energy = |channel: usize| block.iter().skip(channel).step_by(channels).map(|s| s * s).sum::<f32>();
+                let loudest = (0..channels)
+                    .max_by(|&a, &b| energy(a).total_cmp(&energy(b)))
+                    .unwrap_or(0);
+                let from = previous.unwrap_or(loudest);
+
+                for (i, frame) in block.chunks_exact(channels).enumerate() {
+                    // Crossfade instead of jumping between channels, which would click
+                    let sample = if from != loudest && i < fade_len {
+                        let t = i as f32 / fade_len as f32;
+                        frame[from] * (1.0 - t) + frame[loudest] * t
+                    } else {
+                        frame[loudest]
+                    };
+                    mono.push(sample);
+                }
+                previous = Some(loudest);
+            }
+
+            Ok(mono)
+        }
+    }
+}
+
+fn mix_audio_streams(mic_data: &[f32], system_data: &[f32], limiter: &mut loudness::Limiter) -> Vec<f32> {
+    let max_len = mic_data.len().max(system_data.len());
+    let mut mixed = Vec::with_capacity(max_len);
+    
+    for i in 0..max_len {
+        let mic_sample = mic_data.get(i).copied().unwrap_or(0.0);
+        let system_sample = system_data.get(i).copied().unwrap_or(0.0);
+        mixed.push(mic_sample + system_sample);
+    }
+    
+    // Limit peaks to prevent distortion
+    limiter.process(&mixed)
+}
+
+fn transcribe_with_whisper(ctx: &WhisperContext, audio_data: &[f32], language: Option<&str>, options: &TranscriptionOptions) -> Result<String, String> {
+    let _duration = audio_data.len() as f32 / 16000.0;
+    
+    // Check if audio is too short
+    if audio_data.len() < 1600 { // Less than 0.1 seconds at 16kHz
+        return Ok("(Audio too short for transcription)".to_string());
+    }
+    
+    // Create a new state for this transcription
+    let mut state = ctx.create_state()
+        .map_err(|e| format!("Failed to create Whisper state: {}", e))?;
+    
+    // Set up parameters for transcription
+    let mut params = options.full_params(None);
     
     // Set language parameter - use provided language or auto-detect
     params.set_language(language);
@@ -580,9 +1310,17 @@ fn transcribe_with_whisper(ctx: &WhisperContext, audio_data: &[f32], language: O
     }
 }
 
-fn transcribe_with_whisper_segments(ctx: &WhisperContext, audio_data: &[f32], language: Option<&str>) -> Result<TranscriptionResult, String> {
-    use whisper_rs::{FullParams, SamplingStrategy};
-    
+/// The prompt is the one in `options`, then the glossary terms in `vocabulary`,
+/// then `initial_prompt`, which is text carried over from earlier audio.
+fn transcribe_with_whisper_segments(
+    ctx: &WhisperContext,
+    audio_data: &[f32],
+    language: Option<&str>,
+    tdrz: bool,
+    options: &TranscriptionOptions,
+    vocabulary: Option<&str>,
+    initial_prompt: Option<&str>
+) -> Result<TranscriptionResult, String> {
     let _duration = audio_data.len() as f32 / 16000.0;
     
     // Check if audio is too short
@@ -590,6 +1328,8 @@ fn transcribe_with_whisper_segments(ctx: &WhisperContext, audio_data: &[f32], la
         return Ok(TranscriptionResult {
             segments: vec![],
             full_text: "(Audio too short for transcription)".to_string(),
+            language: None,
+            language_probability: None,
         });
     }
     
@@ -598,23 +1338,42 @@ fn transcribe_with_whisper_segments(ctx: &WhisperContext, audio_data: &[f32], la
         .map_err(|e| format!("Failed to create Whisper state: {}", e))?;
     
     // Set up parameters for transcription
-    let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
-    
-    // Configure parameters for better transcription
-    params.set_n_threads(4); // Use 4 threads for faster processing
-    params.set_translate(false); // Don't translate, keep original language
-    
-    // Set language parameter - use provided language or auto-detect
+    let prompt = [vocabulary, initial_prompt].into_iter().flatten().collect::<Vec<_>>().join(" ");
+    let mut params = options.full_params((!prompt.is_empty()).then_some(prompt.as_str()));
+    
+    // Without a language, detect it first so we can report how sure Whisper is.
+    // English-only models have nothing to detect.
+    let mut language_probability = None;
+    let language = match language {
+        None if ctx.is_multilingual() => match detect_language(&mut state, audio_data) {
+            Ok((detected, probability)) => {
+                println!("🌐 Detected language: {} ({:.0}%)", detected, probability * 100.0);
+                language_probability = Some(probability);
+                Some(detected)
+            }
+            Err(e) => {
+                eprintln!("Warning: Language detection failed, letting Whisper decide: {}", e);
+                None
+            }
+        },
+        language => language,
+    };
     params.set_language(language);
     
     params.set_print_progress(false); // Don't print progress to console
     params.set_print_realtime(false); // Don't print realtime output
     params.set_print_timestamps(false); // Don't print timestamps to console
+    params.set_tdrz_enable(tdrz); // Speaker turn markers (tinydiarize models only)
+    params.set_token_timestamps(true); // Times of the individual words
     
     // Run the transcription
     state.full(params, audio_data)
         .map_err(|e| format!("Whisper transcription failed: {}", e))?;
     
+    let language = state.full_lang_id_from_state().ok()
+        .and_then(whisper_rs::get_lang_str)
+        .map(|lang| lang.to_string());
+    
     // Get the number of segments
     let num_segments = state.full_n_segments()
         .map_err(|e| format!("Failed to get segment count: {}", e))?;
@@ -623,6 +1382,8 @@ fn transcribe_with_whisper_segments(ctx: &WhisperContext, audio_data: &[f32], la
         return Ok(TranscriptionResult {
             segments: vec![],
             full_text: "(No speech detected)".to_string(),
+            language,
+            language_probability,
         });
     }
     
@@ -662,11 +1423,18 @@ fn transcribe_with_whisper_segments(ctx: &WhisperContext, audio_data: &[f32], la
             }
         };
         
+        let (words, confidence) = segment_words(ctx, &state, i, start_time, end_time);
+        
         // Add to segments
         segments.push(TranscriptionSegment {
             start: start_time,
             end: end_time,
             text: text.clone(),
+            speaker: None,
+            source: None,
+            speaker_turn_next: tdrz && state.full_get_segment_speaker_turn_next(i),
+            confidence,
+            words,
         });
         
         // Build full text
@@ -687,81 +1455,107 @@ fn transcribe_with_whisper_segments(ctx: &WhisperContext, audio_data: &[f32], la
     Ok(TranscriptionResult {
         segments,
         full_text: final_text,
+        language,
+        language_probability,
     })
 }
 
-fn calculate_audio_duration(path: &str) -> Result<i64, String> {
-    let reader = hound::WavReader::open(path)
-        .map_err(|e| format!("Failed to open audio file: {}", e))?;
-    
-    let spec = reader.spec();
-    let duration_seconds = reader.duration() as f64 / spec.sample_rate as f64;
-    
-    Ok(duration_seconds.round() as i64)
-}
+/// Words of segment `segment` and the mean probability of its tokens.
+/// Tokens are pieces of words; one starting with a space begins a new word,
+/// anything else (including punctuation) belongs to the word before it.
+fn segment_words(
+    ctx: &WhisperContext,
+    state: &whisper_rs::WhisperState,
+    segment: i32,
+    segment_start: f32,
+    segment_end: f32
+) -> (Vec<TranscriptionWord>, Option<f32>) {
+    // Special tokens (timestamps, speaker turns, ...) come after end-of-text
+    let eot = ctx.token_eot();
+    let token_count = state.full_n_tokens(segment).unwrap_or(0);
 
-fn load_audio_file(path: &str) -> Result<Vec<f32>, String> {
-    let mut reader = hound::WavReader::open(path)
-        .map_err(|e| format!("Failed to open audio file: {}", e))?;
-    
-    let spec = reader.spec();
-    
-    // Convert to f32 samples normalized to [-1, 1]
-    let samples: Result<Vec<f32>, _> = match spec.sample_format {
-        hound::SampleFormat::Float => {
-            reader.samples::<f32>().collect()
+    let mut words = Vec::new();
+    // Tokens can split a UTF-8 character, so words collect bytes
+    let mut current: Option<(Vec<u8>, TranscriptionWord)> = None;
+    let mut probability_sum = 0.0;
+    let mut text_tokens = 0;
+
+    let finish = |(bytes, mut word): (Vec<u8>, TranscriptionWord)| {
+        word.text = String::from_utf8_lossy(&bytes).trim().to_string();
+        (!word.text.is_empty()).then_some(word)
+    };
+
+    for token in 0..token_count {
+        let Ok(data) = state.full_get_token_data(segment, token) else { continue };
+        if data.id >= eot {
+            continue;
         }
-        hound::SampleFormat::Int => {
-            match spec.bits_per_sample {
-                16 => {
-                    reader.samples::<i16>()
-                        .map(|s| s.map(|sample| sample as f32 / i16::MAX as f32))
-                        .collect()
-                }
-                32 => {
-                    reader.samples::<i32>()
-                        .map(|s| s.map(|sample| sample as f32 / i32::MAX as f32))
-                        .collect()
-                }
-                _ => return Err("Unsupported bit depth".to_string()),
+        let Ok(bytes) = ctx.token_to_cstr(data.id).map(|text| text.to_bytes()) else { continue };
+
+        probability_sum += data.p;
+        text_tokens += 1;
+
+        // Token times are in centiseconds, -1 when whisper.cpp has none
+        let start = (data.t0 as f32 / 100.0).clamp(segment_start, segment_end);
+        let end = (data.t1 as f32 / 100.0).clamp(start, segment_end);
+
+        match current.as_mut() {
+            Some((text, word)) if !bytes.starts_with(b" ") => {
+                text.extend_from_slice(bytes);
+                word.end = word.end.max(end);
+                word.probability = word.probability.min(data.p);
             }
-        }
-    };
-    
-    let mut audio_data = samples.map_err(|e| format!("Failed to read samples: {}", e))?;
-    
-    // Convert to mono if stereo
-    if spec.channels == 2 {
-        audio_data = audio_data
-            .chunks(2)
-            .map(|chunk| (chunk[0] + chunk[1]) / 2.0)
-            .collect();
-    }
-    
-    // Resample to 16kHz if needed (Whisper expects 16kHz)
-    if spec.sample_rate != 16000 {
-        // Simple resampling (not ideal but works for basic cases)
-        let ratio = spec.sample_rate as f32 / 16000.0;
-        let new_len = (audio_data.len() as f32 / ratio) as usize;
-        let mut resampled = Vec::with_capacity(new_len);
-        
-        for i in 0..new_len {
-            let src_idx = (i as f32 * ratio) as usize;
-            if src_idx < audio_data.len() {
-                resampled.push(audio_data[src_idx]);
+            _ => {
+                words.extend(current.take().and_then(finish));
+                current = Some((bytes.to_vec(), TranscriptionWord {
+                    start,
+                    end,
+                    text: String::new(),
+                    probability: data.p,
+                }));
             }
         }
-        audio_data = resampled;
     }
-    
-    Ok(audio_data)
+    words.extend(current.and_then(finish));
+
+    let confidence = (text_tokens > 0).then(|| probability_sum / text_tokens as f32);
+    (words, confidence)
+}
+
+/// Most likely language of the first 30 seconds of `audio_data` and its probability.
+fn detect_language(state: &mut whisper_rs::WhisperState, audio_data: &[f32]) -> Result<(&'static str, f32), String> {
+    state.pcm_to_mel(audio_data, 4)
+        .map_err(|e| format!("Failed to compute spectrogram: {}", e))?;
+    let (lang_id, probabilities) = state.lang_detect(0, 4)
+        .map_err(|e| format!("Failed to detect language: {}", e))?;
+    let language = whisper_rs::get_lang_str(lang_id)
+        .ok_or_else(|| format!("Unknown language id {}", lang_id))?;
+
+    Ok((language, probabilities.get(lang_id as usize).copied().unwrap_or(0.0)))
+}
+
+fn calculate_audio_duration(path: &str) -> Result<i64, String> {
+    let duration_seconds = decode::duration_seconds(&archive::resolve(Path::new(path)))?;
+    Ok(duration_seconds.round() as i64)
+}
+
+/// Decode any supported audio file to 16 kHz mono, as Whisper expects.
+/// `mix` decides how files with more than one channel become mono.
+fn load_audio_file(path: &str, mix: ChannelMix) -> Result<Vec<f32>, String> {
+    let decoded = decode::decode(&archive::resolve(Path::new(path)))?;
+    let spec = decoded.info;
+    let audio_data = downmix(&decoded.samples, spec.channels, spec.sample_rate, mix)?;
+    
+    // Whisper expects 16kHz
+    Ok(resample::resample(&audio_data, spec.sample_rate, 16000))
 }
 
 #[tauri::command]
 async fn start_recording(
     state: State<'_, AudioState>, 
     db_state: State<'_, DatabaseState>,
-    app_handle: AppHandle
+    app_handle: AppHandle,
+    language: Option<String>
 ) -> Result<serde_json::Value, String> {
     let mut is_recording = state.is_recording.lock().map_err(|e| e.to_string())?;
     let mut start_time = state.start_time.lock().map_err(|e| e.to_string())?;
@@ -774,13 +1568,17 @@ async fn start_recording(
     
     // Create a meeting first to get the ID
     db_state.initialize().ok();
+    // Read before the database is locked below; the lock isn't reentrant
+    let channels = recording_channels(&db_state);
+    let processing = audio_processing(&db_state);
     let db_guard = db_state.get_db()?;
     let db = db_guard.as_ref().ok_or("Database not initialized")?;
     
     let current_date = chrono::Local::now();
     let meeting_title = format!("Meeting {}", current_date.format("%Y-%m-%d %H:%M:%S"));
     
-    let meeting = db.create_meeting(meeting_title, None)
+    // None (auto) is replaced by the detected language once transcription knows it
+    let meeting = db.create_meeting(meeting_title, language)
         .map_err(|e| format!("Failed to create meeting: {}", e))?;
     
     println!("✅ Created meeting with ID: {}", meeting.id);
@@ -792,10 +1590,18 @@ async fn start_recording(
     
     let file_path = recordings_dir.join(format!("recording_{}.wav", meeting.id));
     
+    // Audio is written as it is captured, so open the files before starting
+    let writer = RecordingWriter::create(&file_path, channels)?;
+    *state.recording_writer.lock().map_err(|e| e.to_string())? = Some(writer);
+    *state.recording_meeting_id.lock().map_err(|e| e.to_string())? = Some(meeting.id.clone());
+    *state.audio_processing.lock().map_err(|e| e.to_string())? = processing;
+    
     *output_path = Some(file_path.clone());
     *start_time = Some(chrono::Utc::now());
     *is_recording = true;
     recording_data.clear();
+    *state.is_paused.lock().map_err(|e| e.to_string())? = false;
+    state.pauses.lock().map_err(|e| e.to_string())?.clear();
     
     // Store app handle for event emission
     {
@@ -804,8 +1610,10 @@ async fn start_recording(
     }
     
     // Start actual audio recording in a separate thread
+    let stop = Arc::new(AtomicBool::new(false));
     let recording_data_clone = state.recording_data.clone();
-    let is_recording_clone = state.is_recording.clone();
+    let stop_clone = stop.clone();
+    let is_paused_clone = state.is_paused.clone();
     let whisper_context_clone = state.whisper_context.clone();
     let is_realtime_clone = state.is_realtime_enabled.clone();
     let app_handle_clone = state.app_handle.clone();
@@ -813,25 +1621,31 @@ async fn start_recording(
     
     let mic_gain_clone = state.mic_gain.clone();
     let system_gain_clone = state.system_gain.clone();
+    let audio_processing_clone = state.audio_processing.clone();
     let selected_mic_clone = state.selected_mic_device.clone();
     let selected_system_clone = state.selected_system_device.clone();
+    let recording_writer_clone = state.recording_writer.clone();
     
-    thread::spawn(move || {
+    let capture_thread = thread::spawn(move || {
         if let Err(e) = start_audio_capture_with_realtime(
             recording_data_clone, 
-            is_recording_clone,
+            recording_writer_clone,
+            stop_clone,
+            is_paused_clone,
             whisper_context_clone,
             is_realtime_clone,
             app_handle_clone,
             chunk_size,
             mic_gain_clone,
             system_gain_clone,
+            audio_processing_clone,
             selected_mic_clone,
             selected_system_clone,
         ) {
             eprintln!("Audio capture error: {}", e);
         }
     });
+    *state.capture.lock().map_err(|e| e.to_string())? = Some(CaptureHandle { stop, thread: capture_thread });
     
     // Return both the message and meeting info
     let result = serde_json::json!({
@@ -843,15 +1657,20 @@ async fn start_recording(
     Ok(result)
 }
 
+/// Capture, mix and queue audio until `stop` is set. Returns once the mixer has
+/// written the last of it and the real-time queue has been handed to the worker.
 fn start_audio_capture_with_realtime(
     recording_data: Arc<Mutex<Vec<f32>>>,
-    is_recording: Arc<Mutex<bool>>,
+    recording_writer: Arc<Mutex<Option<RecordingWriter>>>,
+    stop: Arc<AtomicBool>,
+    is_paused: Arc<Mutex<bool>>,
     whisper_context: Arc<Mutex<Option<WhisperContext>>>,
     is_realtime_enabled: Arc<Mutex<bool>>,
     app_handle: Arc<Mutex<Option<AppHandle>>>,
     chunk_size: usize,
     mic_gain: Arc<Mutex<f32>>,
     system_gain: Arc<Mutex<f32>>,
+    audio_processing: Arc<Mutex<AudioProcessing>>,
     selected_mic_device: Arc<Mutex<Option<String>>>,
     selected_system_device: Arc<Mutex<Option<String>>>,
 ) -> Result<(), String> {
@@ -985,35 +1804,32 @@ fn start_audio_capture_with_realtime(
     // Clone references for closures
     let mic_buffer_clone = mic_buffer.clone();
     let system_buffer_clone = system_buffer.clone();
-    let is_recording_mic = is_recording.clone();
-    let is_recording_system = is_recording.clone();
+    let stop_mic = stop.clone();
+    let stop_system = stop.clone();
     let mic_name_clone = mic_name.clone();
     
+    // Resamplers keep state between callbacks, so buffer edges don't click
+    let mut mic_resampler = resample::Resampler::new(mic_sample_rate, target_sample_rate);
+    
     // Start microphone capture
     let mic_stream = match mic_config.sample_format() {
         cpal::SampleFormat::F32 => {
             mic_device.build_input_stream(
                 &mic_config.into(),
                 move |data: &[f32], _: &cpal::InputCallbackInfo| {
-                    if let Ok(is_rec) = is_recording_mic.lock() {
-                        if *is_rec {
-                            if let Ok(mut buffer) = mic_buffer_clone.lock() {
-                                // Convert to mono and resample if needed
-                                let mono_data = convert_to_mono(data, mic_channels);
-                                
-                                // Calculate audio level for debugging
-                                let max_level = mono_data.iter().map(|x| x.abs()).fold(0.0f32, f32::max);
-                                if max_level > 0.01 { // Only log if there's significant audio
-                                    println!("🎤 Mic audio level: {:.3} (samples: {})", max_level, mono_data.len());
-                                }
-                                
-                                let resampled = if mic_sample_rate != target_sample_rate {
-                                    resample_audio(&mono_data, mic_sample_rate, target_sample_rate)
-                                } else {
-                                    mono_data
-                                };
-                                buffer.extend_from_slice(&resampled);
+                    if !stop_mic.load(Ordering::SeqCst) {
+                        if let Ok(mut buffer) = mic_buffer_clone.lock() {
+                            // Convert to mono and resample
+                            let mono_data = convert_to_mono(data, mic_channels);
+                            
+                            // Calculate audio level for debugging
+                            let max_level = mono_data.iter().map(|x| x.abs()).fold(0.0f32, f32::max);
+                            if max_level > 0.01 { // Only log if there's significant audio
+                                println!("🎤 Mic audio level: {:.3} (samples: {})", max_level, mono_data.len());
                             }
+                            
+                            let resampled = mic_resampler.process(&mono_data);
+                            buffer.extend_from_slice(&resampled);
                         }
                     }
                 },
@@ -1025,19 +1841,13 @@ fn start_audio_capture_with_realtime(
             mic_device.build_input_stream(
                 &mic_config.into(),
                 move |data: &[i16], _: &cpal::InputCallbackInfo| {
-                    if let Ok(is_rec) = is_recording_mic.lock() {
-                        if *is_rec {
-                            if let Ok(mut buffer) = mic_buffer_clone.lock() {
-                                // Convert I16 to F32, then to mono and resample
-                                let f32_data = convert_i16_to_f32(data);
-                                let mono_data = convert_to_mono(&f32_data, mic_channels);
-                                let resampled = if mic_sample_rate != target_sample_rate {
-                                    resample_audio(&mono_data, mic_sample_rate, target_sample_rate)
-                                } else {
-                                    mono_data
-                                };
-                                buffer.extend_from_slice(&resampled);
-                            }
+                    if !stop_mic.load(Ordering::SeqCst) {
+                        if let Ok(mut buffer) = mic_buffer_clone.lock() {
+                            // Convert I16 to F32, then to mono and resample
+                            let f32_data = convert_i16_to_f32(data);
+                            let mono_data = convert_to_mono(&f32_data, mic_channels);
+                            let resampled = mic_resampler.process(&mono_data);
+                            buffer.extend_from_slice(&resampled);
                         }
                     }
                 },
@@ -1061,24 +1871,19 @@ fn start_audio_capture_with_realtime(
         let sys_sample_rate = sys_config.sample_rate().0;
         let sys_channels = sys_config.channels();
         let sys_name_clone = sys_name.clone();
+        let mut sys_resampler = resample::Resampler::new(sys_sample_rate, target_sample_rate);
         
         let stream = match sys_config.sample_format() {
             cpal::SampleFormat::F32 => {
                 sys_device.build_input_stream(
                     &sys_config.into(),
                     move |data: &[f32], _: &cpal::InputCallbackInfo| {
-                        if let Ok(is_rec) = is_recording_system.lock() {
-                            if *is_rec {
-                                if let Ok(mut buffer) = system_buffer_clone.lock() {
-                                    // Convert to mono and resample if needed
-                                    let mono_data = convert_to_mono(data, sys_channels);
-                                    let resampled = if sys_sample_rate != target_sample_rate {
-                                        resample_audio(&mono_data, sys_sample_rate, target_sample_rate)
-                                    } else {
-                                        mono_data
-                                    };
-                                    buffer.extend_from_slice(&resampled);
-                                }
+                        if !stop_system.load(Ordering::SeqCst) {
+                            if let Ok(mut buffer) = system_buffer_clone.lock() {
+                                // Convert to mono and resample
+                                let mono_data = convert_to_mono(data, sys_channels);
+                                let resampled = sys_resampler.process(&mono_data);
+                                buffer.extend_from_slice(&resampled);
                             }
                         }
                     },
@@ -1090,19 +1895,13 @@ fn start_audio_capture_with_realtime(
                 sys_device.build_input_stream(
                     &sys_config.into(),
                     move |data: &[i16], _: &cpal::InputCallbackInfo| {
-                        if let Ok(is_rec) = is_recording_system.lock() {
-                            if *is_rec {
-                                if let Ok(mut buffer) = system_buffer_clone.lock() {
-                                    // Convert I16 to F32, then to mono and resample
-                                    let f32_data = convert_i16_to_f32(data);
-                                    let mono_data = convert_to_mono(&f32_data, sys_channels);
-                                    let resampled = if sys_sample_rate != target_sample_rate {
-                                        resample_audio(&mono_data, sys_sample_rate, target_sample_rate)
-                                    } else {
-                                        mono_data
-                                    };
-                                    buffer.extend_from_slice(&resampled);
-                                }
+                        if !stop_system.load(Ordering::SeqCst) {
+                            if let Ok(mut buffer) = system_buffer_clone.lock() {
+                                // Convert I16 to F32, then to mono and resample
+                                let f32_data = convert_i16_to_f32(data);
+                                let mono_data = convert_to_mono(&f32_data, sys_channels);
+                                let resampled = sys_resampler.process(&mono_data);
+                                buffer.extend_from_slice(&resampled);
                             }
                         }
                     },
@@ -1128,49 +1927,44 @@ fn start_audio_capture_with_realtime(
     
     // Audio mixing and processing thread
     let recording_data_clone = recording_data.clone();
-    let is_recording_mixer = is_recording.clone();
+    let stop_mixer = stop.clone();
+    // Set once the mixer has queued its last audio for real-time transcription
+    let mixer_done = Arc::new(AtomicBool::new(false));
+    let mixer_done_clone = mixer_done.clone();
+    let is_paused_mixer = is_paused.clone();
     let mic_buffer_mixer = mic_buffer.clone();
     let system_buffer_mixer = system_buffer.clone();
     let mic_gain_mixer = mic_gain.clone();
     let system_gain_mixer = system_gain.clone();
+    let audio_processing_mixer = audio_processing.clone();
     
-    thread::spawn(move || {
-        let mut last_mic_len = 0;
-        let mut last_system_len = 0;
+    let mixer = thread::spawn(move || {
+        let mut write_failed = false;
         
-        loop {
+        // Automatic gain starts from the configured gains and adapts from there
+        let mut mic_auto_gain = loudness::AutoGain::new(target_sample_rate, *mic_gain_mixer.lock().unwrap());
+        let mut system_auto_gain = loudness::AutoGain::new(target_sample_rate, *system_gain_mixer.lock().unwrap());
+        let mut limiter = loudness::Limiter::new(target_sample_rate);
+        
+        let mut stopping = false;
+        while !stopping {
             thread::sleep(Duration::from_millis(100)); // Mix every 100ms
             
-            // Check if still recording
-            if let Ok(is_rec) = is_recording_mixer.lock() {
-                if !*is_rec {
-                    break;
-                }
-            }
+            // After a stop, mix what the streams delivered until then and finish
+            stopping = stop_mixer.load(Ordering::SeqCst);
             
-            // Get current audio data
+            // Take the audio captured since the last tick
             let (mic_data, system_data) = {
-                let mic_guard = mic_buffer_mixer.lock().unwrap();
-                let system_guard = system_buffer_mixer.lock().unwrap();
-                
-                let new_mic_data = if mic_guard.len() > last_mic_len {
-                    mic_guard[last_mic_len..].to_vec()
-                } else {
-                    Vec::new()
-                };
-                
-                let new_system_data = if system_guard.len() > last_system_len {
-                    system_guard[last_system_len..].to_vec()
-                } else {
-                    Vec::new()
-                };
-                
-                last_mic_len = mic_guard.len();
-                last_system_len = system_guard.len();
-                
-                (new_mic_data, new_system_data)
+                let mut mic_guard = mic_buffer_mixer.lock().unwrap();
+                let mut system_guard = system_buffer_mixer.lock().unwrap();
+                (std::mem::take(&mut *mic_guard), std::mem::take(&mut *system_guard))
             };
             
+            // Drop audio captured during a pause
+            if is_paused_mixer.lock().map(|paused| *paused).unwrap_or(false) {
+                continue;
+            }
+            
             // Mix audio streams if we have new data
             if !mic_data.is_empty() || !system_data.is_empty() {
                 // Get current gain settings
@@ -1183,114 +1977,279 @@ fn start_audio_capture_with_realtime(
                     1.5
                 });
                 
-                // Mix with configurable gains for better volume control
-                let mixed = mix_audio_streams(&mic_data, &system_data, mic_gain_val, system_gain_val);
+                let auto_gain = audio_processing_mixer.lock().map(|p| p.auto_gain).unwrap_or(true);
+                
+                // Level each source, automatically or with the configured gains
+                let (mic_track, system_track): (Vec<f32>, Vec<f32>) = if auto_gain {
+                    (mic_auto_gain.process(&mic_data), system_auto_gain.process(&system_data))
+                } else {
+                    (
+                        mic_data.iter().map(|s| s * mic_gain_val).collect(),
+                        system_data.iter().map(|s| s * system_gain_val).collect(),
+                    )
+                };
+                let mixed = mix_audio_streams(&mic_track, &system_track, &mut limiter);
                 
-                // Add to main recording buffer
+                // Write to disk; the writer is gone once stop_recording has finalized the files
+                if let Ok(mut writer_guard) = recording_writer.lock() {
+                    if let Some(writer) = writer_guard.as_mut() {
+                        if let Err(e) = writer.write(&mixed, &mic_track, &system_track) {
+                            if !write_failed {
+                                eprintln!("❌ Failed to write recording to disk: {}", e);
+                                write_failed = true;
+                            }
+                        }
+                    }
+                }
+
+                // Queue for real-time transcription
                 if let Ok(mut recording) = recording_data_clone.lock() {
                     recording.extend_from_slice(&mixed);
                 }
             }
         }
+        mixer_done_clone.store(true, Ordering::SeqCst);
     });
     
-    // Real-time transcription processing thread
+    // Real-time transcription: this thread cuts the queue into chunks at pauses,
+    // a worker transcribes them in order so segments are saved in sequence
+    const MIN_REALTIME_CHUNK: usize = 16000 * 3; // Don't cut at a pause before 3 seconds
+    const MIN_LANGUAGE_PROBABILITY: f32 = 0.8;
     let recording_data_rt = recording_data.clone();
-    let is_recording_rt = is_recording.clone();
+    let mixer_done_rt = mixer_done.clone();
     let whisper_context_rt = whisper_context.clone();
     let is_realtime_rt = is_realtime_enabled.clone();
     let app_handle_rt = app_handle.clone();
     
-    thread::spawn(move || {
-        let mut last_processed = 0;
-        
+    let chunker = thread::spawn(move || {
+        let app = app_handle_rt.lock().ok().and_then(|app| app.clone());
+        let meeting_id = app.as_ref().and_then(|app| {
+            app.state::<AudioState>().recording_meeting_id.lock().ok().and_then(|id| id.clone())
+        });
+        // The language chosen for the meeting; None means auto-detect
+        let meeting_language = app.as_ref().zip(meeting_id.as_deref()).and_then(|(app, meeting_id)| {
+            let db_state = app.state::<DatabaseState>();
+            let db_guard = db_state.get_db().ok()?;
+            db_guard.as_ref()?.get_meeting(meeting_id).ok()??.language
+        });
+
+        let (chunk_sender, chunk_receiver) = mpsc::channel::<(usize, Vec<f32>)>();
+        let worker_app = app.clone();
+        let worker_meeting_id = meeting_id.clone();
+        let realtime_context = app.as_ref().map(|app| app.state::<AudioState>().realtime_whisper_context.clone());
+        let options = app.as_ref()
+            .map(|app| active_transcription_options(&app.state::<DatabaseState>()))
+            .unwrap_or_default();
+        let vocabulary = app.as_ref()
+            .and_then(|app| glossary_prompt(&app.state::<DatabaseState>(), meeting_id.as_deref()));
+        // Whether every sample of the recording went through the real-time path
+        let queue_complete = Arc::new(AtomicBool::new(true));
+        let worker_queue_complete = queue_complete.clone();
+        // Transcription can lag behind the recording, so the worker is not joined
+        // on stop; it finishes the chunks of its own recording and reports back
+        thread::spawn(move || {
+            let mut window = realtime::SlidingWindow::default();
+            let mut language = meeting_language;
+            let mut chunks = 0;
+            let mut complete = true;
+            for (offset, chunk) in chunk_receiver.iter() {
+                let update = window.push(offset, &chunk, |audio, prompt| {
+                    // Prefer the real-time model; models can be swapped between chunks
+                    if let Some(realtime_context) = &realtime_context {
+                        let ctx_guard = realtime_context.lock().map_err(|e| e.to_string())?;
+                        if let Some(ctx) = ctx_guard.as_ref() {
+                            return transcribe_with_whisper_segments(ctx, audio, language.as_deref(), false, &options, vocabulary.as_deref(), prompt);
+                        }
+                    }
+                    let ctx_guard = whisper_context_rt.lock().map_err(|e| e.to_string())?;
+                    let ctx = ctx_guard.as_ref().ok_or("Whisper not initialized")?;
+                    transcribe_with_whisper_segments(ctx, audio, language.as_deref(), false, &options, vocabulary.as_deref(), prompt)
+                });
+                match update.and_then(|update| save_realtime_update(worker_app.as_ref(), worker_meeting_id.as_deref(), chunks, update)) {
+                    Ok(transcript) => {
+                        println!("Real-time transcript #{}: {}", chunks, transcript.text);
+                        chunks += 1;
+
+                        // With auto-detect, keep the first confident detection for the rest of the meeting
+                        if let (None, Some(detected), Some(probability)) = (&language, &transcript.language, transcript.language_probability) {
+                            if probability >= MIN_LANGUAGE_PROBABILITY {
+                                language = Some(detected.clone());
+                                if let (Some(app), Some(meeting_id)) = (worker_app.as_ref(), worker_meeting_id.as_deref()) {
+                                    if let Err(e) = save_detected_language(&app.state::<DatabaseState>(), meeting_id, detected) {
+                                        eprintln!("Failed to save detected language: {}", e);
+                                    }
+                                }
+                            }
+                        }
+                    }
+                    Err(e) => {
+                        eprintln!("Real-time transcription error: {}", e);
+                        complete = false;
+                    }
+                }
+            }
+            // Nothing left to revise the last segment with
+            let last = window.finish();
+            if !last.committed.is_empty() {
+                if let Err(e) = save_realtime_update(worker_app.as_ref(), worker_meeting_id.as_deref(), chunks, last) {
+                    eprintln!("Real-time transcription error: {}", e);
+                    complete = false;
+                }
+            }
+
+            // Tell the frontend whether the saved segments cover the whole recording
+            if let Some(app) = worker_app {
+                let _ = app.emit("realtime-transcript-finished", serde_json::json!({
+                    "meeting_id": worker_meeting_id,
+                    "chunks": chunks,
+                    "complete": complete && worker_queue_complete.load(Ordering::SeqCst) && chunks > 0,
+                }));
+            }
+        });
+
+        // Samples taken off the queue so far, i.e. the position in the recording
+        let mut consumed = 0;
+
         loop {
-            thread::sleep(Duration::from_secs(5)); // Check every 5 seconds
-            
-            // Check if still recording
-            if let Ok(is_rec) = is_recording_rt.lock() {
-                if !*is_rec {
+            // Check every second so chunks end close to a pause, sooner once the mixer is done
+            for _ in 0..10 {
+                if mixer_done_rt.load(Ordering::SeqCst) {
                     break;
                 }
+                thread::sleep(Duration::from_millis(100));
             }
             
-            // Check if real-time is enabled
-            let realtime_enabled = if let Ok(rt) = is_realtime_rt.lock() {
-                *rt
-            } else {
-                continue;
+            // Read before taking the queue: once the mixer is done, the queue holds all that is left
+            let still_recording = !mixer_done_rt.load(Ordering::SeqCst);
+            let realtime_enabled = is_realtime_rt.lock().map(|rt| *rt).unwrap_or(false);
+            
+            let Ok(mut recording) = recording_data_rt.lock() else {
+                queue_complete.store(false, Ordering::SeqCst);
+                if still_recording {
+                    continue;
+                }
+                break;
             };
             
             if !realtime_enabled {
-                continue;
-            }
-            
-            // Process new audio chunks
-            if let Ok(recording) = recording_data_rt.lock() {
-                let current_len = recording.len();
+                // Nothing will read the queued audio, so don't let it pile up
+                if !recording.is_empty() {
+                    queue_complete.store(false, Ordering::SeqCst);
+                    consumed += recording.len();
+                    recording.clear();
+                }
+            } else {
+                // Cut at a pause once there is enough audio, or at chunk_size if nobody stops talking.
+                // Once recording stops, whatever is left becomes the last chunk.
+                let cut = if still_recording {
+                    vad::next_chunk_end(&recording, MIN_REALTIME_CHUNK.min(chunk_size), chunk_size)
+                } else {
+                    Some(recording.len())
+                };
                 
-                // If we have enough new data for a chunk
-                if current_len >= last_processed + chunk_size {
-                    let chunk_end = last_processed + chunk_size;
-                    let chunk: Vec<f32> = recording[last_processed..chunk_end].to_vec();
+                if let Some(cut) = cut.filter(|&cut| cut > 0) {
+                    let chunk: Vec<f32> = recording.drain(..cut).collect();
+                    let offset = consumed;
+                    consumed += chunk.len();
                     
-                    // Transcribe chunk in background
-                    let whisper_ctx = whisper_context_rt.clone();
-                    let app_handle_chunk = app_handle_rt.clone();
-                    thread::spawn(move || {
-                        if let Ok(ctx_guard) = whisper_ctx.lock() {
-                            if let Some(ref ctx) = *ctx_guard {
-                                match transcribe_with_whisper(ctx, &chunk, None) {
-                                    Ok(transcript) => {
-                                        println!("Real-time transcript: {}", transcript);
-                                        // Send to frontend via event
-                                        if let Ok(app_guard) = app_handle_chunk.lock() {
-                                            if let Some(ref app) = *app_guard {
-                                                let _ = app.emit("realtime-transcript", &transcript);
-                                            }
-                                        }
-                                    }
-                                    Err(e) => {
-                                        eprintln!("Real-time transcription error: {}", e);
-                                    }
-                                }
-                            }
+                    if vad::has_speech(&chunk) {
+                        if chunk_sender.send((offset, chunk)).is_err() {
+                            queue_complete.store(false, Ordering::SeqCst);
                         }
-                    });
-                    
-                    last_processed = chunk_end;
+                    } else {
+                        println!("🔇 Skipping silent {:.1}s real-time chunk", chunk.len() as f32 / 16000.0);
+                    }
                 }
             }
+            
+            if !still_recording {
+                break;
+            }
         }
+
+        // The last chunk took everything the mixer queued; anything still here was
+        // never transcribed, and the queue must be empty for the next recording
+        if let Ok(mut recording) = recording_data_rt.lock() {
+            if !recording.is_empty() {
+                queue_complete.store(false, Ordering::SeqCst);
+                recording.clear();
+            }
+        }
+
+        // Dropping the sender lets the worker finish once it has the last chunk
+        drop(chunk_sender);
     });
     
     // Keep the streams alive while recording
-    loop {
+    while !stop.load(Ordering::SeqCst) {
         thread::sleep(Duration::from_millis(100));
-        if let Ok(is_rec) = is_recording.lock() {
-            if !*is_rec {
-                break;
-            }
-        }
+    }
+    drop(mic_stream);
+    drop(system_stream);
+    
+    if mixer.join().is_err() {
+        eprintln!("❌ Audio mixer thread panicked");
+    }
+    if chunker.join().is_err() {
+        eprintln!("❌ Real-time chunking thread panicked");
     }
     
     Ok(())
 }
 
+/// Save the committed segments of a real-time update to the meeting and send
+/// the update to the frontend.
+fn save_realtime_update(
+    app: Option<&AppHandle>,
+    meeting_id: Option<&str>,
+    chunk_index: usize,
+    update: realtime::WindowUpdate,
+) -> Result<RealtimeTranscript, String> {
+    let transcript = RealtimeTranscript {
+        meeting_id: meeting_id.map(|id| id.to_string()),
+        chunk_index,
+        start: update.start,
+        end: update.end,
+        text: update.committed.iter().map(|segment| segment.text.as_str()).collect::<Vec<_>>().join(" "),
+        segments: update.committed,
+        provisional: update.provisional,
+        language: update.language,
+        language_probability: update.language_probability,
+    };
+
+    if let (Some(app), Some(meeting_id)) = (app, meeting_id) {
+        let db_state = app.state::<DatabaseState>();
+        let db_guard = db_state.get_db()?;
+        let db = db_guard.as_ref().ok_or("Database not initialized")?;
+        for segment in &transcript.segments {
+            save_segment(db, meeting_id, segment)?;
+        }
+    }
+
+    if let Some(app) = app {
+        let _ = app.emit("realtime-transcript", &transcript);
+    }
+
+    Ok(transcript)
+}
+
 fn start_audio_capture(
     recording_data: Arc<Mutex<Vec<f32>>>,
-    is_recording: Arc<Mutex<bool>>,
+    stop: Arc<AtomicBool>,
 ) -> Result<(), String> {
     // Fallback to simple audio capture without real-time features
     start_audio_capture_with_realtime(
         recording_data,
-        is_recording,
+        Arc::new(Mutex::new(None)), // Nothing written to disk
+        stop,
+        Arc::new(Mutex::new(false)),
         Arc::new(Mutex::new(None)),
         Arc::new(Mutex::new(false)),
         Arc::new(Mutex::new(None)),
         0,
         Arc::new(Mutex::new(2.5)), // Default mic gain
         Arc::new(Mutex::new(1.5)), // Default system gain
+        Arc::new(Mutex::new(AudioProcessing::default())),
         Arc::new(Mutex::new(None)), // No selected mic device
         Arc::new(Mutex::new(None))  // No selected system device
     )
@@ -1303,60 +2262,94 @@ pub struct RecordingResult {
     pub audio_file_path: Option<String>,
     pub duration_seconds: i64,
     pub sample_count: usize,
+    #[serde(default)]
+    pub channel_files: Vec<String>,
 }
 
 #[tauri::command]
-async fn stop_recording(state: State<'_, AudioState>) -> Result<RecordingResult, String> {
-    let mut is_recording = state.is_recording.lock().map_err(|e| e.to_string())?;
-    let mut start_time = state.start_time.lock().map_err(|e| e.to_string())?;
-    let output_path = state.output_path.lock().map_err(|e| e.to_string())?;
-    let recording_data = state.recording_data.lock().map_err(|e| e.to_string())?;
+async fn stop_recording(
+    state: State<'_, AudioState>,
+    db_state: State<'_, DatabaseState>
+) -> Result<RecordingResult, String> {
+    let capture = {
+        let mut is_recording = state.is_recording.lock().map_err(|e| e.to_string())?;
+        if !*is_recording {
+            return Err("Not currently recording".to_string());
+        }
+        *is_recording = false;
+        state.capture.lock().map_err(|e| e.to_string())?.take()
+    };
     
-    if !*is_recording {
-        return Err("Not currently recording".to_string());
+    // Wait for the mixer to write the last audio before the files are finalized
+    if let Some(capture) = capture {
+        capture.stop.store(true, Ordering::SeqCst);
+        let joined = tauri::async_runtime::spawn_blocking(move || capture.thread.join()).await;
+        if !matches!(joined, Ok(Ok(()))) {
+            eprintln!("❌ Audio capture thread panicked; finalizing what was written");
+        }
     }
     
-    // Calculate recording duration
+    let mut start_time = state.start_time.lock().map_err(|e| e.to_string())?;
+    let output_path = state.output_path.lock().map_err(|e| e.to_string())?;
+    
+    // Stopping while paused ends the pause
+    let pauses = {
+        let mut pauses = state.pauses.lock().map_err(|e| e.to_string())?;
+        for pause in pauses.iter_mut().filter(|pause| pause.resumed_at.is_none()) {
+            let resumed_at = chrono::Local::now();
+            pause.resumed_at = Some(resumed_at);
+            if let Err(e) = end_recording_pause(&db_state, &pause.meeting_id, resumed_at) {
+                println!("⚠️ Failed to save end of pause of meeting {}: {}", pause.meeting_id, e);
+            }
+        }
+        std::mem::take(&mut *pauses)
+    };
+    *state.is_paused.lock().map_err(|e| e.to_string())? = false;
+    
+    // Calculate recording duration, leaving out the pauses
     let end_time = chrono::Utc::now();
+    let paused_seconds: f64 = pauses.iter().map(|pause| pause.duration_seconds()).sum();
     let duration_seconds = if let Some(start) = *start_time {
-        end_time.signed_duration_since(start).num_seconds()
+        (end_time.signed_duration_since(start).num_seconds() - paused_seconds.round() as i64).max(0)
     } else {
         0
     };
     
-    *is_recording = false;
     *start_time = None;
     
-    // Save the recorded audio to file
-    if let Some(path) = output_path.as_ref() {
-        let spec = hound::WavSpec {
-            channels: 1,
-            sample_rate: 16000, // Whisper expects 16kHz
-            bits_per_sample: 16,
-            sample_format: hound::SampleFormat::Int,
-        };
-        
-        let mut writer = hound::WavWriter::create(path, spec)
-            .map_err(|e| format!("Failed to create WAV file: {}", e))?;
+    // The mixer is done with the writer; finalize what is on disk
+    let writer = state.recording_writer.lock().map_err(|e| e.to_string())?.take();
+    let meeting_id = state.recording_meeting_id.lock().map_err(|e| e.to_string())?.take();
+    
+    if let (Some(path), Some(writer)) = (output_path.as_ref(), writer) {
+        let sample_count = writer.sample_count();
+        let channel_files = writer.finish()?;
         
-        for &sample in recording_data.iter() {
-            let sample_i16 = (sample * i16::MAX as f32) as i16;
-            writer.write_sample(sample_i16)
-                .map_err(|e| format!("Failed to write sample: {}", e))?;
+        println!("✅ Recording saved: {} (Duration: {}s, Samples: {})", 
+                 path.display(), duration_seconds, sample_count);
+        for file in &channel_files {
+            println!("🎚️ Channel track saved: {}", file.display());
         }
         
-        writer.finalize()
-            .map_err(|e| format!("Failed to finalize WAV file: {}", e))?;
+        let normalize_loudness = state.audio_processing.lock().map(|p| p.normalize_loudness).unwrap_or(false);
+        if normalize_loudness {
+            normalize_in_background(&state, path.clone());
+        }
         
-        println!("✅ Recording saved: {} (Duration: {}s, Samples: {})", 
-                 path.display(), duration_seconds, recording_data.len());
+        // Attach the audio right away so startup recovery doesn't treat it as orphaned
+        if let Some(meeting_id) = meeting_id {
+            if let Err(e) = attach_recording(&db_state, &meeting_id, path, duration_seconds) {
+                println!("⚠️ Failed to attach recording to meeting {}: {}", meeting_id, e);
+            }
+        }
         
         Ok(RecordingResult {
             success: true,
             message: format!("Recording stopped and saved successfully (Duration: {}s)", duration_seconds),
             audio_file_path: Some(path.to_string_lossy().to_string()),
             duration_seconds,
-            sample_count: recording_data.len(),
+            sample_count,
+            channel_files: channel_files.iter().map(|file| file.to_string_lossy().to_string()).collect(),
         })
     } else {
         Ok(RecordingResult {
@@ -1364,11 +2357,487 @@ async fn stop_recording(state: State<'_, AudioState>) -> Result<RecordingResult,
             message: "Recording stopped but no file path available".to_string(),
             audio_file_path: None,
             duration_seconds,
-            sample_count: recording_data.len(),
+            sample_count: 0,
+            channel_files: Vec::new(),
         })
     }
 }
 
+/// Normalize a finished recording's loudness on a blocking thread, so stopping
+/// returns right away. Transcription waits for it in `wait_for_normalization`.
+fn normalize_in_background(state: &AudioState, path: PathBuf) {
+    let (done, pending) = tokio::sync::watch::channel(false);
+    if let Ok(mut normalizing) = state.normalizing.lock() {
+        normalizing.insert(path.clone(), pending);
+    }
+
+    let normalizing = state.normalizing.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        match loudness::normalize_wav(&path) {
+            Ok(Some(measured)) => println!("🔊 Normalized recording from {:.1} to {:.1} LUFS", measured, loudness::TARGET_LOUDNESS_LUFS),
+            Ok(None) => println!("🔇 Recording is silent, skipped loudness normalization"),
+            Err(e) => println!("⚠️ Failed to normalize loudness of {}: {}", path.display(), e),
+        }
+        if let Ok(mut normalizing) = normalizing.lock() {
+            normalizing.remove(&path);
+        }
+        let _ = done.send(true);
+    });
+}
+
+/// Wait until the recording at `path` is normalized, if that is still running.
+async fn wait_for_normalization(state: &AudioState, path: &Path) {
+    let pending = state.normalizing.lock().ok().and_then(|normalizing| normalizing.get(path).cloned());
+    if let Some(mut pending) = pending {
+        println!("⏳ Waiting for loudness normalization of {}", path.display());
+        // An error means the task ended without sending, e.g. it panicked; the file is as good as it gets
+        let _ = pending.wait_for(|done| *done).await;
+    }
+}
+
+fn save_recording_pause(db_state: &DatabaseState, pause: &RecordingPause) -> Result<(), String> {
+    let db_guard = db_state.get_db()?;
+    let db = db_guard.as_ref()
+        .ok_or("Database not initialized")?;
+
+    db.add_recording_pause(pause)
+        .map_err(|e| format!("Failed to save recording pause: {}", e))
+}
+
+fn end_recording_pause(db_state: &DatabaseState, meeting_id: &str, resumed_at: chrono::DateTime<chrono::Local>) -> Result<(), String> {
+    let db_guard = db_state.get_db()?;
+    let db = db_guard.as_ref()
+        .ok_or("Database not initialized")?;
+
+    db.end_recording_pause(meeting_id, resumed_at)
+        .map(|_| ())
+        .map_err(|e| format!("Failed to save end of recording pause: {}", e))
+}
+
+#[tauri::command]
+async fn pause_recording(state: State<'_, AudioState>, db_state: State<'_, DatabaseState>) -> Result<String, String> {
+    let is_recording = state.is_recording.lock().map_err(|e| e.to_string())?;
+    let mut is_paused = state.is_paused.lock().map_err(|e| e.to_string())?;
+
+    if !*is_recording {
+        return Err("Not currently recording".to_string());
+    }
+    if *is_paused {
+        return Err("Recording is already paused".to_string());
+    }
+
+    // Checkpoint the file so a crash during a long break loses nothing
+    let audio_offset = {
+        let mut writer = state.recording_writer.lock().map_err(|e| e.to_string())?;
+        match writer.as_mut() {
+            Some(writer) => {
+                writer.flush()?;
+                writer.sample_count() as f64 / recording::SAMPLE_RATE as f64
+            }
+            None => 0.0,
+        }
+    };
+
+    let meeting_id = state.recording_meeting_id.lock().map_err(|e| e.to_string())?
+        .clone()
+        .unwrap_or_default();
+
+    let pause = RecordingPause {
+        meeting_id,
+        audio_offset,
+        paused_at: chrono::Local::now(),
+        resumed_at: None,
+    };
+    // Saved right away, so the pause is known even if the app doesn't get to stop
+    if let Err(e) = save_recording_pause(&db_state, &pause) {
+        println!("⚠️ Failed to save pause of meeting {}: {}", pause.meeting_id, e);
+    }
+
+    *is_paused = true;
+    state.pauses.lock().map_err(|e| e.to_string())?.push(pause);
+
+    println!("⏸️ Recording paused at {:.1}s", audio_offset);
+    Ok(format!("Recording paused at {}", format_time_readable(audio_offset)))
+}
+
+#[tauri::command]
+async fn resume_recording(state: State<'_, AudioState>, db_state: State<'_, DatabaseState>) -> Result<String, String> {
+    let is_recording = state.is_recording.lock().map_err(|e| e.to_string())?;
+    let mut is_paused = state.is_paused.lock().map_err(|e| e.to_string())?;
+
+    if !*is_recording {
+        return Err("Not currently recording".to_string());
+    }
+    if !*is_paused {
+        return Err("Recording is not paused".to_string());
+    }
+
+    let mut pauses = state.pauses.lock().map_err(|e| e.to_string())?;
+    let paused_seconds = match pauses.last_mut() {
+        Some(pause) => {
+            let resumed_at = chrono::Local::now();
+            pause.resumed_at = Some(resumed_at);
+            if let Err(e) = end_recording_pause(&db_state, &pause.meeting_id, resumed_at) {
+                println!("⚠️ Failed to save end of pause of meeting {}: {}", pause.meeting_id, e);
+            }
+            pause.duration_seconds()
+        }
+        None => 0.0,
+    };
+
+    *is_paused = false;
+
+    println!("▶️ Recording resumed after {:.1}s pause", paused_seconds);
+    Ok(format!("Recording resumed after {:.0}s pause", paused_seconds))
+}
+
+#[tauri::command]
+async fn get_meeting_pauses(
+    db_state: State<'_, DatabaseState>,
+    meeting_id: String
+) -> Result<Vec<RecordingPause>, String> {
+    let db_guard = db_state.get_db()?;
+    let db = db_guard.as_ref()
+        .ok_or("Database not initialized")?;
+
+    db.get_recording_pauses(&meeting_id)
+        .map_err(|e| format!("Failed to get recording pauses: {}", e))
+}
+
+/// Store a recording's path and duration on its meeting.
+fn attach_recording(db_state: &DatabaseState, meeting_id: &str, path: &Path, duration_seconds: i64) -> Result<(), String> {
+    let db_guard = db_state.get_db()?;
+    let db = db_guard.as_ref()
+        .ok_or("Database not initialized")?;
+
+    let mut meeting = db.get_meeting(meeting_id)
+        .map_err(|e| format!("Failed to get meeting: {}", e))?
+        .ok_or_else(|| format!("Meeting {} not found", meeting_id))?;
+
+    meeting.audio_file_path = Some(path.to_string_lossy().to_string());
+    meeting.duration_seconds = Some(duration_seconds);
+    meeting.updated_at = chrono::Local::now();
+
+    db.update_meeting(&meeting)
+        .map_err(|e| format!("Failed to update meeting: {}", e))
+}
+
+/// Record the language Whisper detected for a meeting transcribed with "auto".
+fn save_detected_language(db_state: &DatabaseState, meeting_id: &str, language: &str) -> Result<(), String> {
+    let db_guard = db_state.get_db()?;
+    let db = db_guard.as_ref()
+        .ok_or("Database not initialized")?;
+
+    let mut meeting = db.get_meeting(meeting_id)
+        .map_err(|e| format!("Failed to get meeting: {}", e))?
+        .ok_or_else(|| format!("Meeting {} not found", meeting_id))?;
+
+    meeting.language = Some(language.to_string());
+    meeting.updated_at = chrono::Local::now();
+
+    db.update_meeting(&meeting)
+        .map_err(|e| format!("Failed to update meeting: {}", e))
+}
+
+/// Repair and register recordings left behind by a crash: files whose meeting
+/// never got its audio attached, or whose meeting is missing entirely.
+fn recover_orphaned_recordings(db_state: &DatabaseState, audio_state: &AudioState) -> Result<usize, String> {
+    let home_dir = dirs::home_dir().ok_or("Could not find home directory")?;
+    let recordings_dir = home_dir.join("Documents").join("MeetingRecorder").join("MeetingRecordings");
+
+    let mut recovered = 0;
+    for (path, meeting_id) in recording::find_recordings(&recordings_dir) {
+        // A recording may have been started while we were scanning
+        let active = audio_state.recording_meeting_id.lock().map_err(|e| e.to_string())?;
+        if active.as_deref() == Some(meeting_id.as_str()) {
+            continue;
+        }
+        drop(active);
+
+        let existing = {
+            let db_guard = db_state.get_db()?;
+            let db = db_guard.as_ref()
+                .ok_or("Database not initialized")?;
+            db.get_meeting(&meeting_id)
+                .map_err(|e| format!("Failed to get meeting: {}", e))?
+        };
+
+        if existing.as_ref().is_some_and(|meeting| meeting.audio_file_path.is_some()) {
+            continue;
+        }
+
+        match recording::repair_wav(&path) {
+            Ok(true) => println!("🩹 Repaired WAV header of {}", path.display()),
+            Ok(false) => {}
+            Err(e) => {
+                println!("⚠️ Skipping unreadable recording {}: {}", path.display(), e);
+                continue;
+            }
+        }
+        for suffix in recording::CHANNEL_SUFFIXES {
+            let channel_path = recording::channel_file_path(&path, suffix);
+            if channel_path.exists() {
+                if let Err(e) = recording::repair_wav(&channel_path) {
+                    println!("⚠️ Failed to repair channel track {}: {}", channel_path.display(), e);
+                }
+            }
+        }
+
+        let duration_seconds = match calculate_audio_duration(&path.to_string_lossy()) {
+            Ok(duration) if duration > 0 => duration,
+            Ok(_) => continue,
+            Err(e) => {
+                println!("⚠️ Skipping unreadable recording {}: {}", path.display(), e);
+                continue;
+            }
+        };
+
+        let meeting_id = match existing {
+            Some(meeting) => meeting.id,
+            None => {
+                let db_guard = db_state.get_db()?;
+                let db = db_guard.as_ref()
+                    .ok_or("Database not initialized")?;
+                let modified = std::fs::metadata(&path)
+                    .and_then(|metadata| metadata.modified())
+                    .map(chrono::DateTime::<chrono::Local>::from)
+                    .unwrap_or_else(|_| chrono::Local::now());
+                let title = format!("Recovered recording {}", modified.format("%Y-%m-%d %H:%M"));
+                db.create_meeting(title, None)
+                    .map_err(|e| format!("Failed to create meeting: {}", e))?
+                    .id
+            }
+        };
+
+        attach_recording(db_state, &meeting_id, &path, duration_seconds)?;
+        println!("♻️ Recovered recording {} ({}s)", path.display(), duration_seconds);
+        recovered += 1;
+    }
+
+    Ok(recovered)
+}
+
+/// Microphone and system tracks for a recording, from its stereo file or stems.
+fn load_channel_tracks(audio_path: &Path) -> Option<(Vec<f32>, Vec<f32>)> {
+    let audio_path = &archive::resolve(audio_path);
+    let stereo_path = recording::channel_file_path(audio_path, "stereo");
+    if stereo_path.exists() {
+        let stereo = decode::decode(&stereo_path).ok()?;
+        if stereo.info.channels == 2 {
+            let mic = stereo.samples.iter().step_by(2).copied().collect();
+            let system = stereo.samples.iter().skip(1).step_by(2).copied().collect();
+            return Some((mic, system));
+        }
+    }
+
+    let mic_path = recording::channel_file_path(audio_path, "mic");
+    let system_path = recording::channel_file_path(audio_path, "system");
+    if mic_path.exists() && system_path.exists() {
+        let mic = load_audio_file(&mic_path.to_string_lossy(), ChannelMix::Average).ok()?;
+        let system = load_audio_file(&system_path.to_string_lossy(), ChannelMix::Average).ok()?;
+        return Some((mic, system));
+    }
+
+    None
+}
+
+#[tauri::command]
+async fn get_recording_channels(db_state: State<'_, DatabaseState>) -> Result<RecordingChannels, String> {
+    Ok(recording_channels(&db_state))
+}
+
+#[tauri::command]
+async fn set_recording_channels(db_state: State<'_, DatabaseState>, channels: RecordingChannels) -> Result<(), String> {
+    let db_guard = db_state.get_db()?;
+    let db = db_guard.as_ref().ok_or("Database not initialized")?;
+    let json = serde_json::to_string(&channels).map_err(|e| e.to_string())?;
+    db.set_setting(recording::CHANNELS_SETTING, &json)
+        .map_err(|e| format!("Failed to save recording channels: {}", e))?;
+    println!("🎚️ Recording channels set to {:?}", channels);
+    Ok(())
+}
+
+fn recording_channels(db_state: &DatabaseState) -> RecordingChannels {
+    let Ok(db_guard) = db_state.get_db() else { return RecordingChannels::default() };
+    db_guard.as_ref()
+        .and_then(|db| db.get_setting(recording::CHANNELS_SETTING).ok().flatten())
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+#[tauri::command]
+async fn get_audio_processing(db_state: State<'_, DatabaseState>) -> Result<AudioProcessing, String> {
+    Ok(audio_processing(&db_state))
+}
+
+/// Save how audio is levelled; a recording in progress follows the change.
+#[tauri::command]
+async fn set_audio_processing(
+    state: State<'_, AudioState>,
+    db_state: State<'_, DatabaseState>,
+    processing: AudioProcessing
+) -> Result<(), String> {
+    {
+        let db_guard = db_state.get_db()?;
+        let db = db_guard.as_ref().ok_or("Database not initialized")?;
+        let json = serde_json::to_string(&processing).map_err(|e| e.to_string())?;
+        db.set_setting(loudness::PROCESSING_SETTING, &json)
+            .map_err(|e| format!("Failed to save audio processing: {}", e))?;
+    }
+    *state.audio_processing.lock().map_err(|e| e.to_string())? = processing;
+    println!("🔊 Audio processing set to {:?}", processing);
+    Ok(())
+}
+
+fn audio_processing(db_state: &DatabaseState) -> AudioProcessing {
+    let Ok(db_guard) = db_state.get_db() else { return AudioProcessing::default() };
+    db_guard.as_ref()
+        .and_then(|db| db.get_setting(loudness::PROCESSING_SETTING).ok().flatten())
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+#[tauri::command]
+async fn get_recording_storage(db_state: State<'_, DatabaseState>) -> Result<RecordingStorage, String> {
+    Ok(recording_storage(&db_state))
+}
+
+#[tauri::command]
+async fn set_recording_storage(db_state: State<'_, DatabaseState>, storage: RecordingStorage) -> Result<(), String> {
+    let db_guard = db_state.get_db()?;
+    let db = db_guard.as_ref().ok_or("Database not initialized")?;
+    let json = serde_json::to_string(&storage).map_err(|e| e.to_string())?;
+    db.set_setting(archive::STORAGE_SETTING, &json)
+        .map_err(|e| format!("Failed to save recording storage: {}", e))?;
+    println!("🗜️ Recording storage set to {:?}", storage);
+    Ok(())
+}
+
+fn recording_storage(db_state: &DatabaseState) -> RecordingStorage {
+    let Ok(db_guard) = db_state.get_db() else { return RecordingStorage::default() };
+    db_guard.as_ref()
+        .and_then(|db| db.get_setting(archive::STORAGE_SETTING).ok().flatten())
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+/// The WAV file behind a meeting's audio path, if it is one the app recorded or
+/// stored and can archive. Files elsewhere on disk are left alone.
+fn archivable_recording(audio_file_path: &str) -> Option<PathBuf> {
+    let path = PathBuf::from(audio_file_path);
+    let recordings_dir = dirs::home_dir()?.join("Documents").join("MeetingRecorder").join("MeetingRecordings");
+    (archive::is_wav(&path) && path.exists() && path.starts_with(&recordings_dir)).then_some(path)
+}
+
+/// Convert a meeting's recording to FLAC and point the meeting at the new file.
+/// Returns the bytes saved, or None if there was nothing to convert.
+fn archive_meeting_recording(db_state: &DatabaseState, meeting_id: &str) -> Result<Option<u64>, String> {
+    let audio_path = {
+        let db_guard = db_state.get_db()?;
+        let db = db_guard.as_ref().ok_or("Database not initialized")?;
+        db.get_meeting(meeting_id)
+            .map_err(|e| format!("Failed to get meeting: {}", e))?
+            .and_then(|meeting| meeting.audio_file_path)
+            .and_then(|path| archivable_recording(&path))
+    };
+    let Some(audio_path) = audio_path else { return Ok(None) };
+
+    // Converting takes a while, so the database is not locked meanwhile
+    let (flac_path, saved) = archive::compress_recording(&audio_path)?;
+
+    let db_guard = db_state.get_db()?;
+    let db = db_guard.as_ref().ok_or("Database not initialized")?;
+    if let Some(mut meeting) = db.get_meeting(meeting_id).map_err(|e| format!("Failed to get meeting: {}", e))? {
+        meeting.audio_file_path = Some(flac_path.to_string_lossy().to_string());
+        db.update_meeting(&meeting)
+            .map_err(|e| format!("Failed to update meeting: {}", e))?;
+    }
+
+    println!("🗜️ Archived recording of meeting {} as FLAC ({:.1} MB saved)", meeting_id, saved as f64 / (1024.0 * 1024.0));
+    Ok(Some(saved))
+}
+
+/// Archive a freshly transcribed meeting's recording in the background, if the storage policy says so.
+/// Callers hold the database lock, so the policy is read on the background thread too.
+fn archive_after_transcription(app: &AppHandle, meeting_id: &str) {
+    let app = app.clone();
+    let meeting_id = meeting_id.to_string();
+    thread::spawn(move || {
+        let db_state = app.state::<DatabaseState>();
+        if recording_storage(&db_state) != RecordingStorage::Flac {
+            return;
+        }
+        match archive_meeting_recording(&db_state, &meeting_id) {
+            Ok(Some(_)) => {
+                let _ = app.emit("recording-archived", &meeting_id);
+            }
+            Ok(None) => {}
+            Err(e) => eprintln!("❌ Failed to archive recording of meeting {}: {}", meeting_id, e),
+        }
+    });
+}
+
+#[derive(Clone, Serialize)]
+struct CompressionProgress {
+    meeting_id: String,
+    done: usize,
+    total: usize,
+    saved_bytes: u64,
+    error: Option<String>,
+}
+
+/// Convert every meeting's WAV recording to FLAC in the background. Returns how
+/// many recordings will be converted; progress follows as events.
+#[tauri::command]
+async fn compress_recordings(
+    app: AppHandle,
+    state: State<'_, AudioState>,
+    db_state: State<'_, DatabaseState>,
+) -> Result<usize, String> {
+    let recording_meeting_id = state.recording_meeting_id.lock().map_err(|e| e.to_string())?.clone();
+
+    let meeting_ids: Vec<String> = {
+        let db_guard = db_state.get_db()?;
+        let db = db_guard.as_ref().ok_or("Database not initialized")?;
+        db.get_all_meetings()
+            .map_err(|e| format!("Failed to get meetings: {}", e))?
+            .into_iter()
+            .filter(|meeting| recording_meeting_id.as_ref() != Some(&meeting.id))
+            .filter(|meeting| meeting.audio_file_path.as_deref().and_then(archivable_recording).is_some())
+            .map(|meeting| meeting.id)
+            .collect()
+    };
+    let total = meeting_ids.len();
+    println!("🗜️ Compressing {} recordings", total);
+
+    thread::spawn(move || {
+        let db_state = app.state::<DatabaseState>();
+        let mut saved_bytes = 0;
+        for (index, meeting_id) in meeting_ids.into_iter().enumerate() {
+            let error = match archive_meeting_recording(&db_state, &meeting_id) {
+                Ok(saved) => {
+                    saved_bytes += saved.unwrap_or(0);
+                    None
+                }
+                Err(e) => {
+                    eprintln!("❌ Failed to archive recording of meeting {}: {}", meeting_id, e);
+                    Some(e)
+                }
+            };
+            let _ = app.emit("recording-compression-progress", CompressionProgress {
+                meeting_id,
+                done: index + 1,
+                total,
+                saved_bytes,
+                error,
+            });
+        }
+        println!("✅ Recording compression finished ({:.1} MB saved)", saved_bytes as f64 / (1024.0 * 1024.0));
+    });
+
+    Ok(total)
+}
+
 #[tauri::command]
 async fn save_files(state: State<'_, AudioState>) -> Result<String, String> {
     let output_path = state.output_path.lock().map_err(|e| e.to_string())?;
@@ -1467,42 +2936,6 @@ async fn save_uploaded_audio(file_name: String, file_data: Vec<u8>) -> Result<St
     Ok(file_path.to_string_lossy().to_string())
 }
 
-// OpenAI API structures
-#[derive(Serialize, Deserialize)]
-struct OpenAIMessage {
-    role: String,
-    content: String,
-}
-
-#[derive(Serialize, Deserialize)]
-struct OpenAIRequest {
-    model: String,
-    messages: Vec<OpenAIMessage>,
-    max_tokens: Option<u32>,
-    temperature: Option<f32>,
-}
-
-#[derive(Serialize, Deserialize)]
-struct OpenAIChoice {
-    message: OpenAIMessage,
-    finish_reason: Option<String>,
-}
-
-#[derive(Serialize, Deserialize)]
-struct Usage {
-    prompt_tokens: u32,
-    completion_tokens: u32,
-    total_tokens: u32,
-}
-
-#[derive(Serialize, Deserialize)]
-struct OpenAIResponse {
-    choices: Vec<OpenAIChoice>,
-    usage: Option<Usage>,
-}
-
-
-
 #[tauri::command]
 async fn get_gain_settings(state: State<'_, AudioState>) -> Result<(f32, f32), String> {
     let mic_gain = state.mic_gain.lock().map_err(|e| e.to_string())?;
@@ -1740,130 +3173,290 @@ async fn set_gain_settings(state: State<'_, AudioState>, mic_gain: f32, system_g
     Ok(())
 }
 
+#[derive(Serialize, Deserialize)]
+pub struct MeetingMinutesResult {
+    pub meeting_minutes: String, // Minutes body with the metadata trailer stripped
+    pub ai_provider: String, // "openai" or "ollama"
+    pub model: String,
+    pub key_topics: Vec<String>,
+    pub sentiment: Option<String>,
+    pub energy: Option<String>,
+    pub action_items: Vec<ActionItem>, // Only extracted when the minutes belong to a meeting
+}
+
+impl MeetingMinutesResult {
+    fn new(raw_minutes: &str, provider: &AiProvider) -> Self {
+        let (meeting_minutes, metadata) = ai::split_minutes_trailer(raw_minutes);
+        let metadata = metadata.unwrap_or_else(|| {
+            println!("⚠️ Meeting minutes have no KEY_TOPICS/SENTIMENT/ENERGY trailer");
+            ai::MinutesMetadata::default()
+        });
+
+        Self {
+            meeting_minutes,
+            ai_provider: provider.name().to_string(),
+            model: provider.model().to_string(),
+            key_topics: metadata.key_topics,
+            sentiment: metadata.sentiment,
+            energy: metadata.energy,
+            action_items: Vec::new(),
+        }
+    }
+}
+
+#[derive(Clone, Serialize)]
+struct MeetingMinutesProgress {
+    meeting_id: Option<String>,
+    #[serde(flatten)]
+    progress: ai::MinutesProgress,
+}
+
+/// Split the transcript into chunks that fit the provider's context window,
+/// following the stored segment boundaries when the meeting has segments.
+fn meeting_transcript_chunks(
+    db_state: &DatabaseState,
+    meeting_id: Option<&str>,
+    transcript: &str,
+    budget: usize
+) -> Result<Vec<ai::TranscriptChunk>, String> {
+    let segments = match meeting_id {
+        Some(id) => {
+            let db_guard = db_state.get_db()?;
+            match db_guard.as_ref() {
+                Some(db) => db.get_meeting_segments(id)
+                    .map_err(|e| format!("Failed to get meeting segments: {}", e))?,
+                None => Vec::new(),
+            }
+        }
+        None => Vec::new(),
+    };
+
+    // Speaker-labelled segments carry more than the plain transcript, so prefer them
+    let has_speakers = segments.iter().any(|segment| segment.speaker_label().is_some());
+    if transcript.len() <= budget && !has_speakers {
+        return Ok(vec![ai::TranscriptChunk {
+            start_time: None,
+            end_time: None,
+            text: transcript.to_string(),
+        }]);
+    }
+
+    let chunks = if segments.is_empty() {
+        ai::chunk_text(transcript, budget)
+    } else {
+        ai::chunk_segments(&segments, budget)
+    };
+
+    println!("🧩 Transcript has {} chars (budget {}), split into {} chunks", transcript.len(), budget, chunks.len());
+    Ok(chunks)
+}
+
+/// Extract action items with structured JSON output and replace the meeting's stored items.
+/// Segments are sent with timestamps so each item can point back to where it was said.
+async fn extract_and_store_action_items(
+    db_state: &DatabaseState,
+    provider: &AiProvider,
+    meeting_id: &str,
+    transcript: &str,
+    language: Option<&str>,
+    cancelled: &Cancellation
+) -> Result<Vec<ActionItem>, String> {
+    let budget = ai::chunk_budget_chars(provider);
+    let chunks = {
+        let db_guard = db_state.get_db()?;
+        let db = db_guard.as_ref()
+            .ok_or("Database not initialized")?;
+
+        let segments = db.get_meeting_segments(meeting_id)
+            .map_err(|e| format!("Failed to get meeting segments: {}", e))?;
+
+        if segments.is_empty() {
+            ai::chunk_text(transcript, budget)
+        } else {
+            ai::chunk_segments(&segments, budget)
+        }
+    };
+
+    println!("📋 Extracting action items with {} ({} chunks)", provider.name(), chunks.len());
+    let extracted = ai::extract_action_items(provider, &chunks, language, cancelled).await?;
+
+    let items: Vec<ActionItem> = extracted.iter()
+        .map(|item| ActionItem::new(
+            meeting_id.to_string(),
+            item.description.clone(),
+            item.owner.clone(),
+            item.due_date.clone(),
+            item.source_time(),
+        ))
+        .collect();
+
+    let db_guard = db_state.get_db()?;
+    let db = db_guard.as_ref()
+        .ok_or("Database not initialized")?;
+
+    let stored = db.replace_action_items(meeting_id, &items)
+        .map_err(|e| format!("Failed to save action items: {}", e))?;
+
+    println!("✅ Stored {} action items for meeting {}", stored.len(), meeting_id);
+    Ok(stored)
+}
+
+#[tauri::command]
+async fn generate_meeting_minutes(
+    app_handle: AppHandle,
+    db_state: State<'_, DatabaseState>,
+    transcript: String,
+    language: Option<String>,
+    provider: Option<String>,
+    meeting_id: Option<String>
+) -> Result<MeetingMinutesResult, String> {
+    // Load environment variables
+    dotenv::dotenv().ok();
+
+    if transcript.trim().is_empty() {
+        return Err("No transcript provided for meeting minutes generation".to_string());
+    }
+
+    let provider = AiProvider::from_env(provider.as_deref())?;
+    println!("🤖 Generating meeting minutes with {} ({})", provider.name(), provider.model());
+
+    let chunks = meeting_transcript_chunks(&db_state, meeting_id.as_deref(), &transcript, ai::chunk_budget_chars(&provider))?;
+    let not_cancelled = Cancellation::new();
+    let messages = ai::prepare_minutes_messages(&provider, chunks, &transcript, language.as_deref(), &not_cancelled, |progress| {
+        let _ = app_handle.emit("meeting-minutes-progress", MeetingMinutesProgress {
+            meeting_id: meeting_id.clone(),
+            progress,
+        });
+    }).await?;
+    let meeting_minutes = provider.chat(&messages).await?;
+    let mut minutes = MeetingMinutesResult::new(&meeting_minutes, &provider);
+
+    if let Some(meeting_id) = meeting_id.as_deref() {
+        minutes.action_items = extract_and_store_action_items(&db_state, &provider, meeting_id, &transcript, language.as_deref(), &not_cancelled)
+            .await
+            .unwrap_or_else(|e| {
+                report_action_items_error(&app_handle, meeting_id, &e);
+                Vec::new()
+            });
+    }
+
+    Ok(minutes)
+}
+
+#[derive(Clone, Serialize)]
+struct ActionItemsError {
+    meeting_id: String,
+    error: String,
+}
+
+/// The minutes were generated, but their action items could not be extracted.
+fn report_action_items_error(app_handle: &AppHandle, meeting_id: &str, error: &str) {
+    println!("⚠️ Failed to extract action items: {}", error);
+    let _ = app_handle.emit("action-items-error", ActionItemsError {
+        meeting_id: meeting_id.to_string(),
+        error: error.to_string(),
+    });
+}
+
+
+#[derive(Clone, Serialize)]
+struct MeetingMinutesToken {
+    meeting_id: String,
+    token: String,
+}
+
 #[tauri::command]
-async fn generate_meeting_minutes(transcript: String, language: Option<String>) -> Result<String, String> {
+async fn generate_meeting_minutes_stream(
+    app_handle: AppHandle,
+    db_state: State<'_, DatabaseState>,
+    minutes_state: State<'_, MinutesState>,
+    meeting_id: String,
+    transcript: String,
+    language: Option<String>,
+    provider: Option<String>
+) -> Result<MeetingMinutesResult, String> {
     // Load environment variables
     dotenv::dotenv().ok();
-    
-    let api_key = std::env::var("OPENAI_API_KEY")
-        .map_err(|_| "OPENAI_API_KEY not found in environment variables. Please add it to your .env file.".to_string())?;
-    
-    let model = std::env::var("OPENAI_MODEL").unwrap_or_else(|_| "gpt-4.1".to_string());
-    let max_tokens = std::env::var("OPENAI_MAX_TOKENS")
-        .unwrap_or_else(|_| "2000".to_string())
-        .parse::<u32>()
-        .unwrap_or(2000);
-    let temperature = std::env::var("OPENAI_TEMPERATURE")
-        .unwrap_or_else(|_| "0.3".to_string())
-        .parse::<f32>()
-        .unwrap_or(0.3);
 
     if transcript.trim().is_empty() {
         return Err("No transcript provided for meeting minutes generation".to_string());
     }
 
-    // Create the prompt for meeting minutes with language awareness
-    let language_instruction = match language.as_deref() {
-        Some("id") => "Generate the meeting minutes in Indonesian (Bahasa Indonesia). Use professional Indonesian business language.",
-        Some("es") => "Generate the meeting minutes in Spanish. Use professional Spanish business language.",
-        Some("fr") => "Generate the meeting minutes in French. Use professional French business language.",
-        Some("de") => "Generate the meeting minutes in German. Use professional German business language.",
-        Some("it") => "Generate the meeting minutes in Italian. Use professional Italian business language.",
-        Some("pt") => "Generate the meeting minutes in Portuguese. Use professional Portuguese business language.",
-        Some("nl") => "Generate the meeting minutes in Dutch. Use professional Dutch business language.",
-        Some("ru") => "Generate the meeting minutes in Russian. Use professional Russian business language.",
-        Some("ja") => "Generate the meeting minutes in Japanese. Use professional Japanese business language.",
-        Some("ko") => "Generate the meeting minutes in Korean. Use professional Korean business language.",
-        Some("zh") => "Generate the meeting minutes in Chinese. Use professional Chinese business language.",
-        Some("ar") => "Generate the meeting minutes in Arabic. Use professional Arabic business language.",
-        Some("hi") => "Generate the meeting minutes in Hindi. Use professional Hindi business language.",
-        Some("tr") => "Generate the meeting minutes in Turkish. Use professional Turkish business language.",
-        Some("en") | _ => "Generate the meeting minutes in English. Use professional English business language.",
-    };
-
-    let system_prompt = format!(r#"You are an expert meeting assistant. Transform the following meeting transcript into well-structured meeting minutes. {}
-
-Include the following sections:
-
-1. **Meeting Summary** - Brief overview of the meeting
-2. **Key Discussion Points** - Main topics discussed
-3. **Decisions Made** - Any decisions or conclusions reached
-4. **Action Items** - Tasks assigned with responsible parties (if mentioned)
-5. **Next Steps** - Follow-up actions or future meetings
+    let provider = AiProvider::from_env(provider.as_deref())?;
+    println!("🤖 Streaming meeting minutes with {} ({})", provider.name(), provider.model());
 
-Format the output in clear, professional language with proper headings and bullet points. Use markdown formatting including:
-- **Bold text** for emphasis
-- Bullet points for lists
-- Tables for structured data (use proper markdown table syntax with | separators)
-- Code blocks for technical content
+    let chunks = meeting_transcript_chunks(&db_state, Some(&meeting_id), &transcript, ai::chunk_budget_chars(&provider))?;
 
-When presenting structured information like action items, deadlines, or data comparisons, use markdown tables with this format:
-| Column 1 | Column 2 | Column 3 |
-|----------|----------|----------|
-| Data 1   | Data 2   | Data 3   |
+    // Register a cancellation flag for this meeting
+    let cancelled = Arc::new(Cancellation::new());
+    {
+        let mut flags = minutes_state.cancel_flags.lock().map_err(|e| e.to_string())?;
+        if flags.contains_key(&meeting_id) {
+            return Err("Meeting minutes are already being generated for this meeting".to_string());
+        }
+        flags.insert(meeting_id.clone(), cancelled.clone());
+    }
 
-If specific names or roles aren't mentioned, use generic terms like "Participant A", "Team Member", etc. Maintain the same language throughout the entire document.
+    let result = async {
+        let messages = ai::prepare_minutes_messages(&provider, chunks, &transcript, language.as_deref(), &cancelled, |progress| {
+            let _ = app_handle.emit("meeting-minutes-progress", MeetingMinutesProgress {
+                meeting_id: Some(meeting_id.clone()),
+                progress,
+            });
+        }).await?;
 
-IMPORTANT: End your response with exactly this format:
----
-KEY_TOPICS: [comma-separated list of 3-5 topics]
-SENTIMENT: [Positive/Neutral/Negative]
-ENERGY: [High/Medium/Low]"#, language_instruction);
+        let text = provider.chat_stream(&messages, &cancelled, |token| {
+            let _ = app_handle.emit("meeting-minutes-token", MeetingMinutesToken {
+                meeting_id: meeting_id.clone(),
+                token: token.to_string(),
+            });
+        }).await?;
+        let mut minutes = MeetingMinutesResult::new(&text, &provider);
 
-    let user_prompt = format!("Please generate meeting minutes from this transcript:\n\n{}", transcript);
+        // Save before extracting action items, so the minutes are kept if that fails or is cancelled
+        save_meeting_minutes_to_database(
+            db_state.clone(),
+            meeting_id.clone(),
+            minutes.meeting_minutes.clone(),
+            minutes.ai_provider.clone(),
+            Some(minutes.key_topics.clone()),
+            minutes.sentiment.clone(),
+            minutes.energy.clone()
+        ).await?;
 
-    // Prepare the OpenAI request
-    let request = OpenAIRequest {
-        model,
-        messages: vec![
-            OpenAIMessage {
-                role: "system".to_string(),
-                content: system_prompt.to_string(),
-            },
-            OpenAIMessage {
-                role: "user".to_string(),
-                content: user_prompt,
-            },
-        ],
-        max_tokens: Some(max_tokens),
-        temperature: Some(temperature),
-    };
+        minutes.action_items = extract_and_store_action_items(&db_state, &provider, &meeting_id, &transcript, language.as_deref(), &cancelled)
+            .await
+            .unwrap_or_else(|e| {
+                report_action_items_error(&app_handle, &meeting_id, &e);
+                Vec::new()
+            });
 
-    // Make the API call
-    let client = reqwest::Client::new();
-    let response = client
-        .post("https://api.openai.com/v1/chat/completions")
-        .header("Authorization", format!("Bearer {}", api_key))
-        .header("Content-Type", "application/json")
-        .json(&request)
-        .send()
-        .await
-        .map_err(|e| format!("Failed to send request to OpenAI: {}", e))?;
+        Ok::<_, String>(minutes)
+    }.await;
 
-    if !response.status().is_success() {
-        let status_code = response.status();
-        let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
-        return Err(format!("OpenAI API error ({}): {}", status_code, error_text));
+    if let Ok(mut flags) = minutes_state.cancel_flags.lock() {
+        flags.remove(&meeting_id);
     }
 
-    // Get response text first for debugging
-    let response_text = response.text().await
-        .map_err(|e| format!("Failed to get response text: {}", e))?;
-    
-    // Try to parse the JSON response
-    let openai_response: OpenAIResponse = serde_json::from_str(&response_text)
-        .map_err(|e| format!("Failed to parse OpenAI response: {}. Response was: {}", e, response_text))?;
+    result
+}
 
-    if openai_response.choices.is_empty() {
-        return Err("No response from OpenAI".to_string());
-    }
+#[tauri::command]
+async fn cancel_meeting_minutes(
+    minutes_state: State<'_, MinutesState>,
+    meeting_id: String
+) -> Result<String, String> {
+    let flags = minutes_state.cancel_flags.lock().map_err(|e| e.to_string())?;
 
-    let meeting_minutes = &openai_response.choices[0].message.content;
-    
-    Ok(meeting_minutes.to_string())
+    match flags.get(&meeting_id) {
+        Some(flag) => {
+            flag.cancel();
+            Ok("Meeting minutes generation cancelled".to_string())
+        }
+        None => Err("No meeting minutes generation in progress for this meeting".to_string()),
+    }
 }
 
-
 #[tauri::command]
 async fn save_meeting_minutes(meeting_minutes: String, filename: Option<String>) -> Result<String, String> {
     use std::fs;
@@ -1925,6 +3518,7 @@ async fn create_meeting(
 
 #[tauri::command]
 async fn test_save_audio_path(
+    app: AppHandle,
     db_state: State<'_, DatabaseState>
 ) -> Result<String, String> {
     let test_audio_path = "/Users/test/audio.wav";
@@ -1933,6 +3527,7 @@ async fn test_save_audio_path(
     println!("🧪 Testing save_transcript_to_database with audio path: {}", test_audio_path);
     
     let result = save_transcript_to_database(
+        app,
         db_state,
         test_title.to_string(),
         "Test transcript".to_string(),
@@ -1987,7 +3582,9 @@ async fn update_meeting_title(
 }
 
 #[tauri::command]
+#[allow(clippy::too_many_arguments)] // Tauri passes command arguments individually
 async fn update_meeting_transcript(
+    app: AppHandle,
     db_state: State<'_, DatabaseState>,
     meeting_id: String,
     title: String,
@@ -2031,7 +3628,9 @@ async fn update_meeting_transcript(
     // Update meeting with transcript, audio file path, and duration
     meeting.title = title;
     meeting.transcript = Some(transcript);
-    meeting.audio_file_path = audio_file_path.clone();
+    // The recording may have been archived since the frontend got its path
+    meeting.audio_file_path = audio_file_path.as_deref()
+        .map(|path| archive::resolve(Path::new(path)).to_string_lossy().to_string());
     meeting.duration_seconds = Some(duration_seconds);
     meeting.language = language;
     
@@ -2050,18 +3649,14 @@ async fn update_meeting_transcript(
         .map_err(|e| format!("Failed to delete existing segments: {}", e))?;
     
     // Add new segments to the meeting
-    for segment in segments {
-        let meeting_segment = MeetingSegment {
-            id: uuid::Uuid::new_v4().to_string(),
-            meeting_id: meeting.id.clone(),
-            start_time: segment.start as f64,
-            end_time: segment.end as f64,
-            text: segment.text,
-            confidence: None,
-        };
-        
-        db.add_meeting_segment(&meeting_segment)
-            .map_err(|e| format!("Failed to add meeting segment: {}", e))?;
+    for segment in &segments {
+        save_segment(db, &meeting.id, segment)?;
+    }
+    
+    apply_glossary(db, &mut meeting)?;
+    
+    if !segments.is_empty() {
+        archive_after_transcription(&app, &meeting.id);
     }
     
     Ok(meeting)
@@ -2069,6 +3664,7 @@ async fn update_meeting_transcript(
 
 #[tauri::command]
 async fn save_transcript_to_database(
+    app: AppHandle,
     db_state: State<'_, DatabaseState>,
     title: String,
     transcript: String,
@@ -2122,29 +3718,256 @@ async fn save_transcript_to_database(
     println!("✅ Meeting updated successfully");
     
     // Add segments to the meeting
-    for segment in segments {
-        let meeting_segment = MeetingSegment {
-            id: uuid::Uuid::new_v4().to_string(),
-            meeting_id: meeting.id.clone(),
-            start_time: segment.start as f64,
-            end_time: segment.end as f64,
-            text: segment.text,
-            confidence: None,
-        };
-        
-        db.add_meeting_segment(&meeting_segment)
-            .map_err(|e| format!("Failed to add meeting segment: {}", e))?;
+    for segment in &segments {
+        save_segment(db, &meeting.id, segment)?;
+    }
+    
+    apply_glossary(db, &mut meeting)?;
+    
+    if !segments.is_empty() {
+        archive_after_transcription(&app, &meeting.id);
     }
     
     Ok(meeting)
 }
 
+/// Add a transcribed segment and its words to a meeting; returns the segment id.
+fn save_segment(db: &Database, meeting_id: &str, segment: &TranscriptionSegment) -> Result<String, String> {
+    let id = uuid::Uuid::new_v4().to_string();
+    db.add_meeting_segment(&MeetingSegment {
+        id: id.clone(),
+        meeting_id: meeting_id.to_string(),
+        start_time: segment.start as f64,
+        end_time: segment.end as f64,
+        text: segment.text.clone(),
+        confidence: segment.confidence.map(|confidence| confidence as f64),
+        speaker: segment.speaker.clone(),
+        source: segment.source.clone(),
+    }).map_err(|e| format!("Failed to add meeting segment: {}", e))?;
+
+    let words: Vec<SegmentWord> = segment.words.iter()
+        .map(|word| SegmentWord {
+            segment_id: id.clone(),
+            meeting_id: meeting_id.to_string(),
+            start_time: word.start as f64,
+            end_time: word.end as f64,
+            text: word.text.clone(),
+            probability: word.probability as f64,
+        })
+        .collect();
+    db.add_segment_words(&words)
+        .map_err(|e| format!("Failed to add segment words: {}", e))?;
+
+    Ok(id)
+}
+
+/// Glossary terms for Whisper's prompt: the shared ones and those of `meeting_id`.
+fn glossary_prompt(db_state: &DatabaseState, meeting_id: Option<&str>) -> Option<String> {
+    let db_guard = db_state.get_db().ok()?;
+    let terms = db_guard.as_ref()?.get_glossary_terms(meeting_id).ok()?;
+    glossary::prompt(&terms)
+}
+
+/// Correct the meeting's segments with its glossary and record each change.
+/// Corrections that were reverted are not made again. The meeting transcript
+/// is rebuilt from the segments if anything changed.
+fn apply_glossary(db: &Database, meeting: &mut Meeting) -> Result<Vec<TranscriptCorrection>, String> {
+    let terms = db.get_glossary_terms(Some(&meeting.id))
+        .map_err(|e| format!("Failed to load glossary: {}", e))?;
+    if terms.iter().all(|term| term.variants.is_empty()) {
+        return Ok(Vec::new());
+    }
+
+    let reverted: Vec<(String, Option<String>)> = db.get_transcript_corrections(&meeting.id)
+        .map_err(|e| format!("Failed to load corrections: {}", e))?
+        .into_iter()
+        .filter(|correction| correction.reverted_at.is_some())
+        .map(|correction| (correction.segment_id, correction.term_id))
+        .collect();
+
+    let segments = db.get_meeting_segments(&meeting.id)
+        .map_err(|e| format!("Failed to get segments: {}", e))?;
+    let words = db.get_meeting_words(&meeting.id, None)
+        .map_err(|e| format!("Failed to get words: {}", e))?;
+
+    let mut corrections = Vec::new();
+    for segment in &segments {
+        let mut text = segment.text.clone();
+        for term in &terms {
+            if reverted.iter().any(|(segment_id, term_id)| *segment_id == segment.id && term_id.as_deref() == Some(term.id.as_str())) {
+                continue;
+            }
+            let Some(corrected) = glossary::correct(&text, term) else { continue };
+
+            let correction = TranscriptCorrection {
+                id: uuid::Uuid::new_v4().to_string(),
+                meeting_id: meeting.id.clone(),
+                segment_id: segment.id.clone(),
+                term_id: Some(term.id.clone()),
+                term: term.term.clone(),
+                original_text: text,
+                corrected_text: corrected.clone(),
+                created_at: chrono::Local::now(),
+                reverted_at: None,
+            };
+            db.update_segment_text(&segment.id, &corrected)
+                .map_err(|e| format!("Failed to correct segment: {}", e))?;
+            db.add_transcript_correction(&correction)
+                .map_err(|e| format!("Failed to record correction: {}", e))?;
+
+            corrections.push(correction);
+            text = corrected;
+        }
+
+        if text != segment.text {
+            realign_segment_words(db, &segment.id, &words, &text)?;
+        }
+    }
+
+    if !corrections.is_empty() {
+        rebuild_transcript(db, meeting)?;
+        println!("📖 Applied {} glossary corrections to meeting {}", corrections.len(), meeting.id);
+    }
+
+    Ok(corrections)
+}
+
+/// Re-derive the stored words of a segment whose text became `text`;
+/// `words` may hold other segments' words too.
+fn realign_segment_words(db: &Database, segment_id: &str, words: &[SegmentWord], text: &str) -> Result<(), String> {
+    let segment_words: Vec<SegmentWord> = words.iter()
+        .filter(|word| word.segment_id == segment_id)
+        .cloned()
+        .collect();
+    if segment_words.is_empty() {
+        return Ok(());
+    }
+    db.set_segment_words(segment_id, &glossary::realign_words(&segment_words, text))
+        .map_err(|e| format!("Failed to update segment words: {}", e))
+}
+
+/// Set the meeting transcript to the text of its segments.
+fn rebuild_transcript(db: &Database, meeting: &mut Meeting) -> Result<(), String> {
+    let segments = db.get_meeting_segments(&meeting.id)
+        .map_err(|e| format!("Failed to get segments: {}", e))?;
+    meeting.transcript = Some(segments.iter().map(|segment| segment.text.as_str()).collect::<Vec<_>>().join(" "));
+    db.update_meeting(meeting)
+        .map_err(|e| format!("Failed to update meeting transcript: {}", e))
+}
+
+/// Shared glossary terms, plus the terms of `meeting_id` if given.
+#[tauri::command]
+async fn get_glossary_terms(db_state: State<'_, DatabaseState>, meeting_id: Option<String>) -> Result<Vec<GlossaryTerm>, String> {
+    let db_guard = db_state.get_db()?;
+    let db = db_guard.as_ref().ok_or("Database not initialized")?;
+    db.get_glossary_terms(meeting_id.as_deref())
+        .map_err(|e| format!("Failed to load glossary: {}", e))
+}
+
+/// Add a term for every meeting, or only for `meeting_id`.
+#[tauri::command]
+async fn add_glossary_term(
+    db_state: State<'_, DatabaseState>,
+    meeting_id: Option<String>,
+    term: String,
+    variants: Vec<String>
+) -> Result<GlossaryTerm, String> {
+    let term = term.trim().to_string();
+    if term.is_empty() {
+        return Err("Glossary term cannot be empty".to_string());
+    }
+    let variants: Vec<String> = variants.iter()
+        .map(|variant| variant.trim().to_string())
+        .filter(|variant| !variant.is_empty())
+        .collect();
+
+    let db_guard = db_state.get_db()?;
+    let db = db_guard.as_ref().ok_or("Database not initialized")?;
+
+    let glossary_term = GlossaryTerm::new(meeting_id, term, variants);
+    db.add_glossary_term(&glossary_term)
+        .map_err(|e| format!("Failed to add glossary term: {}", e))?;
+    Ok(glossary_term)
+}
+
+#[tauri::command]
+async fn delete_glossary_term(db_state: State<'_, DatabaseState>, id: String) -> Result<(), String> {
+    let db_guard = db_state.get_db()?;
+    let db = db_guard.as_ref().ok_or("Database not initialized")?;
+    db.delete_glossary_term(&id)
+        .map_err(|e| format!("Failed to delete glossary term: {}", e))?;
+    Ok(())
+}
+
+/// Correct a saved transcript with the current glossary, e.g. after adding terms.
+#[tauri::command]
+async fn apply_glossary_corrections(db_state: State<'_, DatabaseState>, meeting_id: String) -> Result<Vec<TranscriptCorrection>, String> {
+    let db_guard = db_state.get_db()?;
+    let db = db_guard.as_ref().ok_or("Database not initialized")?;
+    let mut meeting = db.get_meeting(&meeting_id)
+        .map_err(|e| format!("Failed to get meeting: {}", e))?
+        .ok_or("Meeting not found")?;
+    apply_glossary(db, &mut meeting)
+}
+
+#[tauri::command]
+async fn get_transcript_corrections(db_state: State<'_, DatabaseState>, meeting_id: String) -> Result<Vec<TranscriptCorrection>, String> {
+    let db_guard = db_state.get_db()?;
+    let db = db_guard.as_ref().ok_or("Database not initialized")?;
+    db.get_transcript_corrections(&meeting_id)
+        .map_err(|e| format!("Failed to load corrections: {}", e))
+}
+
+/// Undo a glossary correction. Fails if the segment has changed since, so
+/// later edits are never lost; corrections have to be undone newest first.
+#[tauri::command]
+async fn revert_transcript_correction(db_state: State<'_, DatabaseState>, id: String) -> Result<TranscriptCorrection, String> {
+    let db_guard = db_state.get_db()?;
+    let db = db_guard.as_ref().ok_or("Database not initialized")?;
+
+    let correction = db.get_transcript_correction(&id)
+        .map_err(|e| format!("Failed to load correction: {}", e))?
+        .ok_or("Correction not found")?;
+    if correction.reverted_at.is_some() {
+        return Err("Correction was already undone".to_string());
+    }
+
+    let segment = db.get_meeting_segments(&correction.meeting_id)
+        .map_err(|e| format!("Failed to get segments: {}", e))?
+        .into_iter()
+        .find(|segment| segment.id == correction.segment_id)
+        .ok_or("The corrected segment no longer exists")?;
+    if segment.text != correction.corrected_text {
+        return Err("The segment has changed since this correction; undo later corrections first".to_string());
+    }
+
+    db.update_segment_text(&segment.id, &correction.original_text)
+        .map_err(|e| format!("Failed to restore segment: {}", e))?;
+    let words = db.get_meeting_words(&correction.meeting_id, None)
+        .map_err(|e| format!("Failed to get words: {}", e))?;
+    realign_segment_words(db, &segment.id, &words, &correction.original_text)?;
+    db.set_correction_reverted(&id)
+        .map_err(|e| format!("Failed to record undo: {}", e))?;
+
+    let mut meeting = db.get_meeting(&correction.meeting_id)
+        .map_err(|e| format!("Failed to get meeting: {}", e))?
+        .ok_or("Meeting not found")?;
+    rebuild_transcript(db, &mut meeting)?;
+
+    db.get_transcript_correction(&id)
+        .map_err(|e| format!("Failed to load correction: {}", e))?
+        .ok_or_else(|| "Correction not found".to_string())
+}
+
 #[tauri::command]
 async fn save_meeting_minutes_to_database(
     db_state: State<'_, DatabaseState>,
     meeting_id: String,
     meeting_minutes: String,
-    ai_provider: String
+    ai_provider: String,
+    key_topics: Option<Vec<String>>,
+    sentiment: Option<String>,
+    energy: Option<String>
 ) -> Result<String, String> {
     let db_guard = db_state.get_db()?;
     let db = db_guard.as_ref()
@@ -2155,9 +3978,20 @@ async fn save_meeting_minutes_to_database(
         .map_err(|e| format!("Failed to get meeting: {}", e))?
         .ok_or("Meeting not found")?;
     
+    // A trailer left in the minutes takes precedence over the passed metadata
+    let (meeting_minutes, metadata) = ai::split_minutes_trailer(&meeting_minutes);
+    let metadata = metadata.unwrap_or_else(|| ai::MinutesMetadata {
+        key_topics: ai::normalize_topics(&key_topics.unwrap_or_default()),
+        sentiment: sentiment.as_deref().and_then(ai::normalize_sentiment),
+        energy: energy.as_deref().and_then(ai::normalize_energy),
+    });
+
     // Update meeting with minutes
     meeting.meeting_minutes = Some(meeting_minutes);
     meeting.ai_provider = Some(ai_provider);
+    meeting.key_topics = metadata.key_topics;
+    meeting.sentiment = metadata.sentiment;
+    meeting.energy = metadata.energy;
     
     db.update_meeting(&meeting)
         .map_err(|e| format!("Failed to update meeting with minutes: {}", e))?;
@@ -2211,7 +4045,7 @@ async fn delete_meeting(
         // Delete the audio file if it exists
         if let Some(audio_file_path) = &meeting.audio_file_path {
             if !audio_file_path.is_empty() {
-                let audio_path = std::path::Path::new(audio_file_path);
+                let audio_path = &archive::resolve(Path::new(audio_file_path));
                 if audio_path.exists() {
                     match std::fs::remove_file(audio_path) {
                         Ok(_) => println!("✅ Deleted audio file: {}", audio_file_path),
@@ -2223,6 +4057,16 @@ async fn delete_meeting(
                 } else {
                     println!("ℹ️ Audio file not found: {}", audio_file_path);
                 }
+
+                // Separate microphone/system tracks, if any were kept
+                for suffix in recording::CHANNEL_SUFFIXES {
+                    let channel_path = recording::channel_file_path(audio_path, suffix);
+                    if channel_path.exists() {
+                        if let Err(e) = std::fs::remove_file(&channel_path) {
+                            println!("⚠️ Failed to delete channel file {}: {}", channel_path.display(), e);
+                        }
+                    }
+                }
             }
         }
     }
@@ -2238,7 +4082,7 @@ async fn delete_meeting(
 async fn search_meetings(
     db_state: State<'_, DatabaseState>,
     query: String
-) -> Result<Vec<Meeting>, String> {
+) -> Result<Vec<SearchResult>, String> {
     let db_guard = db_state.get_db()?;
     let db = db_guard.as_ref()
         .ok_or("Database not initialized")?;
@@ -2249,6 +4093,28 @@ async fn search_meetings(
     Ok(meetings)
 }
 
+#[tauri::command]
+async fn get_meeting_topics(
+    db_state: State<'_, DatabaseState>
+) -> Result<Vec<TopicCount>, String> {
+    let db_guard = db_state.get_db()?;
+    let db = db_guard.as_ref()
+        .ok_or("Database not initialized")?;
+
+    let topics = db.get_all_topics()
+        .map_err(|e| format!("Failed to get meeting topics: {}", e))?;
+
+    Ok(topics.into_iter()
+        .map(|(topic, meeting_count)| TopicCount { topic, meeting_count })
+        .collect())
+}
+
+#[derive(Serialize)]
+pub struct TopicCount {
+    pub topic: String,
+    pub meeting_count: i64,
+}
+
 #[tauri::command]
 async fn add_meeting_segment(
     db_state: State<'_, DatabaseState>,
@@ -2256,7 +4122,8 @@ async fn add_meeting_segment(
     start_time: f64,
     end_time: f64,
     text: String,
-    confidence: Option<f64>
+    confidence: Option<f64>,
+    speaker: Option<String>
 ) -> Result<String, String> {
     let db_guard = db_state.get_db()?;
     let db = db_guard.as_ref()
@@ -2269,6 +4136,8 @@ async fn add_meeting_segment(
         end_time,
         text,
         confidence,
+        speaker,
+        source: None,
     };
     
     db.add_meeting_segment(&segment)
@@ -2292,12 +4161,164 @@ async fn get_meeting_segments(
     Ok(segments)
 }
 
+/// Words of a meeting with their times and probabilities. With
+/// `max_probability` only the words below it, e.g. to review unclear passages.
+#[tauri::command]
+async fn get_meeting_words(
+    db_state: State<'_, DatabaseState>,
+    meeting_id: String,
+    max_probability: Option<f64>
+) -> Result<Vec<SegmentWord>, String> {
+    let db_guard = db_state.get_db()?;
+    let db = db_guard.as_ref()
+        .ok_or("Database not initialized")?;
+
+    db.get_meeting_words(&meeting_id, max_probability)
+        .map_err(|e| format!("Failed to get meeting words: {}", e))
+}
+
+#[derive(Serialize)]
+pub struct SpeakerInfo {
+    pub name: String,
+    pub segment_count: i64,
+}
+
+#[tauri::command]
+async fn get_meeting_speakers(
+    db_state: State<'_, DatabaseState>,
+    meeting_id: String
+) -> Result<Vec<SpeakerInfo>, String> {
+    let db_guard = db_state.get_db()?;
+    let db = db_guard.as_ref()
+        .ok_or("Database not initialized")?;
+
+    let speakers = db.get_meeting_speakers(&meeting_id)
+        .map_err(|e| format!("Failed to get meeting speakers: {}", e))?;
+
+    Ok(speakers.into_iter()
+        .map(|(name, segment_count)| SpeakerInfo { name, segment_count })
+        .collect())
+}
+
+#[tauri::command]
+async fn rename_speaker(
+    db_state: State<'_, DatabaseState>,
+    meeting_id: String,
+    speaker: String,
+    new_name: String
+) -> Result<String, String> {
+    let new_name = new_name.trim();
+    if new_name.is_empty() {
+        return Err("Speaker name cannot be empty".to_string());
+    }
+
+    let db_guard = db_state.get_db()?;
+    let db = db_guard.as_ref()
+        .ok_or("Database not initialized")?;
+
+    let updated = db.rename_speaker(&meeting_id, &speaker, new_name)
+        .map_err(|e| format!("Failed to rename speaker: {}", e))?;
+
+    if updated == 0 {
+        return Err(format!("Speaker '{}' not found in this meeting", speaker));
+    }
+
+    Ok(format!("Renamed {} to {} in {} segments", speaker, new_name, updated))
+}
+
+#[tauri::command]
+async fn extract_action_items(
+    db_state: State<'_, DatabaseState>,
+    meeting_id: String,
+    language: Option<String>,
+    provider: Option<String>
+) -> Result<Vec<ActionItem>, String> {
+    // Load environment variables
+    dotenv::dotenv().ok();
+
+    let transcript = {
+        let db_guard = db_state.get_db()?;
+        let db = db_guard.as_ref()
+            .ok_or("Database not initialized")?;
+
+        db.get_meeting(&meeting_id)
+            .map_err(|e| format!("Failed to get meeting: {}", e))?
+            .ok_or("Meeting not found")?
+            .transcript
+            .filter(|t| !t.trim().is_empty())
+            .ok_or("No transcript available to extract action items from")?
+    };
+
+    let provider = AiProvider::from_env(provider.as_deref())?;
+    let not_cancelled = Cancellation::new();
+
+    extract_and_store_action_items(&db_state, &provider, &meeting_id, &transcript, language.as_deref(), &not_cancelled).await
+}
+
+#[tauri::command]
+async fn get_meeting_action_items(
+    db_state: State<'_, DatabaseState>,
+    meeting_id: String
+) -> Result<Vec<ActionItem>, String> {
+    let db_guard = db_state.get_db()?;
+    let db = db_guard.as_ref()
+        .ok_or("Database not initialized")?;
+
+    db.get_action_items(&meeting_id)
+        .map_err(|e| format!("Failed to get action items: {}", e))
+}
+
+#[tauri::command]
+async fn get_open_action_items(
+    db_state: State<'_, DatabaseState>
+) -> Result<Vec<ActionItem>, String> {
+    let db_guard = db_state.get_db()?;
+    let db = db_guard.as_ref()
+        .ok_or("Database not initialized")?;
+
+    db.get_open_action_items()
+        .map_err(|e| format!("Failed to get open action items: {}", e))
+}
+
+#[tauri::command]
+async fn mark_action_item_done(
+    db_state: State<'_, DatabaseState>,
+    id: String
+) -> Result<ActionItem, String> {
+    set_action_item_status(&db_state, &id, "done")
+}
+
+#[tauri::command]
+async fn reopen_action_item(
+    db_state: State<'_, DatabaseState>,
+    id: String
+) -> Result<ActionItem, String> {
+    set_action_item_status(&db_state, &id, "open")
+}
+
+fn set_action_item_status(db_state: &DatabaseState, id: &str, status: &str) -> Result<ActionItem, String> {
+    let db_guard = db_state.get_db()?;
+    let db = db_guard.as_ref()
+        .ok_or("Database not initialized")?;
+
+    db.set_action_item_status(id, status)
+        .map_err(|e| format!("Failed to update action item: {}", e))?
+        .ok_or_else(|| "Action item not found".to_string())
+}
+
 #[tauri::command]
 async fn get_audio_file_data(file_path: String) -> Result<Vec<u8>, String> {
     use std::fs;
     
+    let path = archive::resolve(Path::new(&file_path));
+    
+    // Archived recordings are played back as WAV, which every webview supports
+    if path.extension().and_then(|ext| ext.to_str()) == Some("flac") {
+        return archive::wav_bytes(&path);
+    }
+    
     // Read the audio file
-    let audio_data = fs::read(&file_path)
+    let audio_data = fs::read(&path)
         .map_err(|e| format!("Failed to read audio file: {}", e))?;
     
     Ok(audio_data)
@@ -2305,30 +4326,34 @@ async fn get_audio_file_data(file_path: String) -> Result<Vec<u8>, String> {
 
 #[derive(Serialize, Deserialize)]
 pub struct AudioQualityInfo {
+    pub codec: String,
     pub sample_rate: u32,
     pub channels: u16,
-    pub bits_per_sample: u16,
+    pub bits_per_sample: Option<u16>, // None for lossy formats like MP3 or AAC
     pub duration_seconds: f64,
     pub file_size_bytes: u64,
     pub bitrate_kbps: u32,
+    pub loudness_lufs: Option<f64>, // EBU R128 integrated loudness; None if too short or silent
+    pub peak_dbfs: Option<f64>,     // None for digital silence
 }
 
 #[tauri::command]
 async fn get_audio_quality_info(file_path: String) -> Result<AudioQualityInfo, String> {
     use std::fs;
     
-    if !std::path::Path::new(&file_path).exists() {
+    let file_path = archive::resolve(Path::new(&file_path));
+    if !file_path.exists() {
         return Err("Audio file not found".to_string());
     }
     
     let metadata = fs::metadata(&file_path)
         .map_err(|e| format!("Failed to read file metadata: {}", e))?;
     
-    let reader = hound::WavReader::open(&file_path)
-        .map_err(|e| format!("Failed to open audio file: {}", e))?;
-    
-    let spec = reader.spec();
-    let duration_seconds = reader.duration() as f64 / spec.sample_rate as f64;
+    let decoded = decode::decode(&file_path)?;
+    let duration_seconds = decoded.info.duration_seconds.unwrap_or_else(|| decoded.duration_seconds());
+    let loudness_lufs = loudness::integrated_loudness(&decoded.samples, decoded.info.channels, decoded.info.sample_rate);
+    let peak_dbfs = loudness::peak_dbfs(&decoded.samples);
+    let info = decoded.info;
     let file_size_bytes = metadata.len();
     
     // Calculate bitrate (bits per second / 1000 for kbps)
@@ -2339,15 +4364,21 @@ async fn get_audio_quality_info(file_path: String) -> Result<AudioQualityInfo, S
     };
     
     Ok(AudioQualityInfo {
-        sample_rate: spec.sample_rate,
-        channels: spec.channels,
-        bits_per_sample: spec.bits_per_sample,
+        codec: info.codec,
+        sample_rate: info.sample_rate,
+        channels: info.channels,
+        bits_per_sample: info.bits_per_sample,
         duration_seconds,
         file_size_bytes,
         bitrate_kbps,
+        loudness_lufs,
+        peak_dbfs,
     })
 }
 
+// Words below this probability are marked in exports and highlighted in the app
+const LOW_CONFIDENCE_PROBABILITY: f64 = 0.5;
+
 #[derive(Serialize, Deserialize, Clone)]
 pub struct ExportOptions {
     pub format: String, // "pdf", "txt", "json", "md"
@@ -2372,11 +4403,14 @@ async fn export_meeting_data(
         .map_err(|e| format!("Failed to get meeting: {}", e))?
         .ok_or("Meeting not found")?;
     
-    let segments = if options.include_segments {
-        db.get_meeting_segments(&meeting_id)
-            .map_err(|e| format!("Failed to get meeting segments: {}", e))?
+    let (segments, words) = if options.include_segments {
+        let segments = db.get_meeting_segments(&meeting_id)
+            .map_err(|e| format!("Failed to get meeting segments: {}", e))?;
+        let words = db.get_meeting_words(&meeting_id, None)
+            .map_err(|e| format!("Failed to get meeting words: {}", e))?;
+        (segments, words)
     } else {
-        Vec::new()
+        (Vec::new(), Vec::new())
     };
     
     // Create main export directory with meeting-specific folder
@@ -2421,8 +4455,8 @@ async fn export_meeting_data(
         let vtt_file = segments_dir.join("segments.vtt");
         let txt_file = segments_dir.join("segments.txt");
         
-        export_segments_as_vtt(&segments, &vtt_file)?;
-        export_segments_as_txt(&segments, &txt_file)?;
+        export_segments_as_vtt(&segments, &words, &vtt_file)?;
+        export_segments_as_txt(&segments, &words, &txt_file)?;
         exported_files.push("Transcript Segments (vtt/txt)".to_string());
     }
     
@@ -2506,7 +4540,7 @@ fn export_meeting_minutes(meeting: &Meeting, file_path: &std::path::Path) -> Res
     }
 }
 
-fn export_segments_as_vtt(segments: &[MeetingSegment], file_path: &std::path::Path) -> Result<(), String> {
+fn export_segments_as_vtt(segments: &[MeetingSegment], words: &[SegmentWord], file_path: &std::path::Path) -> Result<(), String> {
     use std::fs::File;
     use std::io::Write;
     
@@ -2520,16 +4554,21 @@ fn export_segments_as_vtt(segments: &[MeetingSegment], file_path: &std::path::Pa
         let start_time = format_vtt_time(segment.start_time);
         let end_time = format_vtt_time(segment.end_time);
         
+        let text = mark_low_confidence(segment, words);
+        
         write_line!(file, "{}", i + 1);
         write_line!(file, "{} --> {}", start_time, end_time);
-        write_line!(file, "{}", segment.text);
+        match segment.speaker_label() {
+            Some(speaker) => write_line!(file, "<v {}>{}", speaker, text),
+            None => write_line!(file, "{}", text),
+        }
         write_line!(file, "");
     }
     
     Ok(())
 }
 
-fn export_segments_as_txt(segments: &[MeetingSegment], file_path: &std::path::Path) -> Result<(), String> {
+fn export_segments_as_txt(segments: &[MeetingSegment], words: &[SegmentWord], file_path: &std::path::Path) -> Result<(), String> {
     use std::fs::File;
     use std::io::Write;
     
@@ -2544,13 +4583,36 @@ fn export_segments_as_txt(segments: &[MeetingSegment], file_path: &std::path::Pa
         let start_time = format_time_readable(segment.start_time);
         let end_time = format_time_readable(segment.end_time);
         
-        write_line!(file, "[{} - {}] {}", start_time, end_time, segment.text);
+        let text = mark_low_confidence(segment, words);
+        
+        match segment.speaker_label() {
+            Some(speaker) => write_line!(file, "[{} - {}] {}: {}", start_time, end_time, speaker, text),
+            None => write_line!(file, "[{} - {}] {}", start_time, end_time, text),
+        }
         write_line!(file, "");
     }
     
     Ok(())
 }
 
+/// Segment text with the words Whisper was unsure of marked, e.g. "ask Tomasz(?) about it".
+/// Text changed since it was transcribed no longer matches its words and is left as is.
+fn mark_low_confidence(segment: &MeetingSegment, words: &[SegmentWord]) -> String {
+    let words: Vec<&SegmentWord> = words.iter().filter(|word| word.segment_id == segment.id).collect();
+    if words.is_empty() || words.iter().map(|word| word.text.as_str()).collect::<Vec<_>>().join(" ") != segment.text {
+        return segment.text.clone();
+    }
+
+    words.iter()
+        .map(|word| if word.probability < LOW_CONFIDENCE_PROBABILITY {
+            format!("{}(?)", word.text)
+        } else {
+            word.text.clone()
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
 fn export_audio_info(meeting: &Meeting, file_path: &std::path::Path) -> Result<(), String> {
     use std::fs::File;
     use std::io::Write;
@@ -2667,8 +4729,12 @@ fn export_as_txt(
         writeln!(file, "TRANSCRIPT SEGMENTS").map_err(|e| format!("Write error: {}", e))?;
         writeln!(file, "===================").map_err(|e| format!("Write error: {}", e))?;
         for (i, segment) in segments.iter().enumerate() {
-            writeln!(file, "[{}] {:.2}s - {:.2}s: {}", 
-                     i + 1, segment.start_time, segment.end_time, segment.text).map_err(|e| format!("Write error: {}", e))?;
+            match segment.speaker_label() {
+                Some(speaker) => writeln!(file, "[{}] {:.2}s - {:.2}s: {}: {}",
+                                          i + 1, segment.start_time, segment.end_time, speaker, segment.text),
+                None => writeln!(file, "[{}] {:.2}s - {:.2}s: {}", 
+                                 i + 1, segment.start_time, segment.end_time, segment.text),
+            }.map_err(|e| format!("Write error: {}", e))?;
         }
         writeln!(file).map_err(|e| format!("Write error: {}", e))?;
     }
@@ -2791,6 +4857,9 @@ fn export_as_markdown(
         for (i, segment) in segments.iter().enumerate() {
             writeln!(file, "### Segment {}", i + 1).map_err(|e| format!("Write error: {}", e))?;
             writeln!(file, "**Time:** {:.2}s - {:.2}s", segment.start_time, segment.end_time).map_err(|e| format!("Write error: {}", e))?;
+            if let Some(speaker) = segment.speaker_label() {
+                writeln!(file, "**Speaker:** {}", speaker).map_err(|e| format!("Write error: {}", e))?;
+            }
             writeln!(file, "{}", segment.text).map_err(|e| format!("Write error: {}", e))?;
             writeln!(file).map_err(|e| format!("Write error: {}", e))?;
         }
@@ -3105,9 +5174,31 @@ pub fn run() {
         .plugin(tauri_plugin_opener::init())
         .manage(AudioState::default())
         .manage(DatabaseState::default())
+        .manage(MinutesState::default())
+        .setup(|app| {
+            // Register recordings cut short by a crash before anything else touches them
+            let app_handle = app.handle().clone();
+            thread::spawn(move || {
+                let db_state = app_handle.state::<DatabaseState>();
+                let audio_state = app_handle.state::<AudioState>();
+                let result = db_state.initialize()
+                    .and_then(|_| recover_orphaned_recordings(&db_state, &audio_state));
+                match result {
+                    Ok(0) => {}
+                    Ok(count) => {
+                        println!("♻️ Recovered {} orphaned recordings", count);
+                        let _ = app_handle.emit("recordings-recovered", count);
+                    }
+                    Err(e) => eprintln!("❌ Recording recovery failed: {}", e),
+                }
+            });
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             start_recording, 
             stop_recording, 
+            pause_recording,
+            resume_recording,
             save_files,
             save_transcript_to_file,
             save_uploaded_audio,
@@ -3117,15 +5208,41 @@ pub fn run() {
             test_microphone_access,
             test_audio_system,
             initialize_whisper,
+            list_whisper_models,
+            select_whisper_model,
+            download_whisper_model,
+            delete_whisper_model,
+            reload_whisper_model,
+            set_realtime_whisper_model,
+            get_transcription_presets,
+            save_transcription_preset,
+            delete_transcription_preset,
+            set_active_transcription_preset,
+            get_glossary_terms,
+            add_glossary_term,
+            delete_glossary_term,
+            apply_glossary_corrections,
+            get_transcript_corrections,
+            revert_transcript_correction,
             transcribe_audio,
             transcribe_audio_with_segments,
+            finalize_realtime_transcript,
             enable_realtime_transcription,
             disable_realtime_transcription,
             get_recording_status,
             generate_meeting_minutes,
+            generate_meeting_minutes_stream,
+            cancel_meeting_minutes,
             save_meeting_minutes,
             get_gain_settings,
             set_gain_settings,
+            get_recording_channels,
+            set_recording_channels,
+            get_audio_processing,
+            set_audio_processing,
+            get_recording_storage,
+            set_recording_storage,
+            compress_recordings,
             // Database commands
             initialize_database,
             create_meeting,
@@ -3135,8 +5252,18 @@ pub fn run() {
             get_all_meetings,
             delete_meeting,
             search_meetings,
+            get_meeting_topics,
             add_meeting_segment,
             get_meeting_segments,
+            get_meeting_words,
+            get_meeting_speakers,
+            get_meeting_pauses,
+            rename_speaker,
+            extract_action_items,
+            get_meeting_action_items,
+            get_open_action_items,
+            mark_action_item_done,
+            reopen_action_item,
             save_transcript_to_database,
             update_meeting_transcript,
             save_meeting_minutes_to_database,
@@ -3151,3 +5278,44 @@ pub fn run() {
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn segment_exports_name_renamed_speakers() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::new(dir.path().join("meetings.db")).unwrap();
+        let meeting = db.create_meeting("Standup".to_string(), None).unwrap();
+        for (start_time, speaker, text) in [(0.0, Some("Speaker 1"), "Good morning."), (2.0, None, "Morning!")] {
+            db.add_meeting_segment(&MeetingSegment {
+                id: uuid::Uuid::new_v4().to_string(),
+                meeting_id: meeting.id.clone(),
+                start_time,
+                end_time: start_time + 2.0,
+                text: text.to_string(),
+                confidence: None,
+                speaker: speaker.map(str::to_string),
+                source: None,
+            }).unwrap();
+        }
+
+        db.rename_speaker(&meeting.id, "Speaker 1", "Alice").unwrap();
+        let segments = db.get_meeting_segments(&meeting.id).unwrap();
+        let vtt_path = dir.path().join("segments.vtt");
+        let txt_path = dir.path().join("segments.txt");
+        export_segments_as_vtt(&segments, &[], &vtt_path).unwrap();
+        export_segments_as_txt(&segments, &[], &txt_path).unwrap();
+
+        let vtt = std::fs::read_to_string(&vtt_path).unwrap();
+        assert!(vtt.contains("<v Alice>Good morning.\n"), "{}", vtt);
+        assert!(vtt.contains("\nMorning!\n"), "{}", vtt);
+        assert!(!vtt.contains("Speaker 1"), "{}", vtt);
+
+        let txt = std::fs::read_to_string(&txt_path).unwrap();
+        assert!(txt.contains("] Alice: Good morning.\n"), "{}", txt);
+        assert!(txt.contains("] Morning!\n"), "{}", txt);
+        assert!(!txt.contains("Speaker 1"), "{}", txt);
+    }
+}
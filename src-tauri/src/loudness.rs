@@ -0,0 +1,438 @@
+// Level control for recordings: automatic gain per source and a limiter on the
+// mix while capturing, and EBU R128 loudness measurement and normalization of
+// finished files.
+//
+// Automatic gain follows the speech level of a source and moves its gain
+// towards TARGET_SPEECH_DB, lowering it faster than raising it. Frames below
+// GATE_DB leave the gain alone, so pauses are not boosted into hiss. The
+// limiter looks a few milliseconds ahead, so its gain is already down when a
+// peak arrives instead of the peak being clipped.
+//
+// Loudness is measured as in ITU-R BS.1770 / EBU R128: the K-weighted mean
+// square over 400 ms blocks, gated at -70 LUFS and 10 LU below the level of
+// the blocks that pass that gate.
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::path::Path;
+
+pub const TARGET_LOUDNESS_LUFS: f64 = -23.0; // EBU R128
+
+pub const PROCESSING_SETTING: &str = "audio_processing";
+
+const TARGET_SPEECH_DB: f32 = -20.0; // RMS of speech after automatic gain
+const GATE_DB: f32 = -60.0; // Quieter frames count as silence
+const MIN_GAIN_DB: f32 = -20.0;
+const MAX_GAIN_DB: f32 = 30.0;
+const GAIN_RISE_DB_PER_SECOND: f32 = 3.0;
+const GAIN_FALL_DB_PER_SECOND: f32 = 12.0;
+const LEVEL_SECONDS: f32 = 0.4; // Time constant of the speech level estimate
+const GAIN_FRAME_SECONDS: f32 = 0.01;
+
+const LIMIT_DB: f32 = -1.0;
+const LOOKAHEAD_SECONDS: f32 = 0.005;
+const RELEASE_SECONDS: f32 = 0.1;
+
+/// How captured audio is levelled.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct AudioProcessing {
+    /// Adjust the gain of each source automatically, starting from the gain settings
+    pub auto_gain: bool,
+    /// Normalize finished recordings to TARGET_LOUDNESS_LUFS
+    pub normalize_loudness: bool,
+}
+
+impl Default for AudioProcessing {
+    fn default() -> Self {
+        Self {
+            auto_gain: true,
+            normalize_loudness: false,
+        }
+    }
+}
+
+fn db_to_gain(db: f32) -> f32 {
+    10f32.powf(db / 20.0)
+}
+
+/// Automatic gain for one source, applied as audio streams in.
+pub struct AutoGain {
+    frame_len: usize,
+    frame_power: f64,
+    frame_count: usize,
+    level_power: Option<f64>, // Smoothed power of recent speech; None until speech is heard
+    level_weight: f64,        // How much each speech frame moves the level
+    target_db: f32,           // Gain reached at the end of the current frame
+    gain_db: f32,             // Gain of the current sample
+    step_db: f32,             // Per-sample change towards `target_db`
+    max_rise_db: f32,         // Per frame
+    max_fall_db: f32,
+}
+
+impl AutoGain {
+    pub fn new(sample_rate: u32, initial_gain: f32) -> Self {
+        let frame_len = ((sample_rate as f32 * GAIN_FRAME_SECONDS) as usize).max(1);
+        let initial_db = (20.0 * initial_gain.max(1e-3).log10()).clamp(MIN_GAIN_DB, MAX_GAIN_DB);
+        Self {
+            frame_len,
+            frame_power: 0.0,
+            frame_count: 0,
+            level_power: None,
+            level_weight: (GAIN_FRAME_SECONDS / LEVEL_SECONDS) as f64,
+            target_db: initial_db,
+            gain_db: initial_db,
+            step_db: 0.0,
+            max_rise_db: GAIN_RISE_DB_PER_SECOND * GAIN_FRAME_SECONDS,
+            max_fall_db: GAIN_FALL_DB_PER_SECOND * GAIN_FRAME_SECONDS,
+        }
+    }
+
+    pub fn process(&mut self, input: &[f32]) -> Vec<f32> {
+        input.iter()
+            .map(|&sample| {
+                self.frame_power += (sample as f64) * (sample as f64);
+                self.frame_count += 1;
+                self.gain_db += self.step_db;
+                let output = sample * db_to_gain(self.gain_db);
+
+                if self.frame_count == self.frame_len {
+                    self.end_frame();
+                }
+                output
+            })
+            .collect()
+    }
+
+    /// Update the level with the frame just heard and glide to the gain it calls for
+    /// over the next frame.
+    fn end_frame(&mut self) {
+        let power = self.frame_power / self.frame_count as f64;
+        self.frame_power = 0.0;
+        self.frame_count = 0;
+
+        let power_db = 10.0 * power.max(1e-12).log10() as f32;
+        if power_db > GATE_DB {
+            let level = match self.level_power {
+                Some(level) => level + (power - level) * self.level_weight,
+                None => power,
+            };
+            self.level_power = Some(level);
+
+            let level_db = 10.0 * level.log10() as f32;
+            let wanted_db = (TARGET_SPEECH_DB - level_db).clamp(MIN_GAIN_DB, MAX_GAIN_DB);
+            self.target_db += (wanted_db - self.target_db).clamp(-self.max_fall_db, self.max_rise_db);
+        }
+
+        self.step_db = (self.target_db - self.gain_db) / self.frame_len as f32;
+    }
+}
+
+/// Look-ahead peak limiter. Output is delayed by `lookahead - 1` samples.
+pub struct Limiter {
+    lookahead: usize,
+    threshold: f32,
+    release: f32,                      // Per-sample recovery towards unity gain
+    index: usize,
+    minimum: VecDeque<(usize, f32)>,   // Sliding minimum of the gain each recent sample needs
+    released: f32,                     // That minimum, recovering no faster than `release`
+    recent: VecDeque<f32>,             // Last `lookahead` values of `released`
+    recent_sum: f64,
+    delay: VecDeque<f32>,
+}
+
+impl Limiter {
+    pub fn new(sample_rate: u32) -> Self {
+        let lookahead = ((sample_rate as f32 * LOOKAHEAD_SECONDS) as usize).max(1);
+        Self {
+            lookahead,
+            threshold: db_to_gain(LIMIT_DB),
+            release: 1.0 - (-1.0 / (RELEASE_SECONDS * sample_rate as f32)).exp(),
+            index: 0,
+            minimum: VecDeque::new(),
+            released: 1.0,
+            recent: std::iter::repeat_n(1.0, lookahead).collect(),
+            recent_sum: lookahead as f64,
+            delay: std::iter::repeat_n(0.0, lookahead - 1).collect(),
+        }
+    }
+
+    pub fn process(&mut self, input: &[f32]) -> Vec<f32> {
+        input.iter().map(|&sample| self.process_sample(sample)).collect()
+    }
+
+    /// The samples still held back by the look-ahead.
+    pub fn flush(&mut self) -> Vec<f32> {
+        self.process(&vec![0.0; self.lookahead - 1])
+    }
+
+    fn process_sample(&mut self, sample: f32) -> f32 {
+        let needed = if sample.abs() > self.threshold { self.threshold / sample.abs() } else { 1.0 };
+
+        while self.minimum.back().is_some_and(|&(_, gain)| gain >= needed) {
+            self.minimum.pop_back();
+        }
+        self.minimum.push_back((self.index, needed));
+        while self.minimum.front().is_some_and(|&(index, _)| index + self.lookahead <= self.index) {
+            self.minimum.pop_front();
+        }
+        self.index += 1;
+
+        let window_minimum = self.minimum.front().map(|&(_, gain)| gain).unwrap_or(1.0);
+        self.released = window_minimum.min(self.released + (1.0 - self.released) * self.release);
+
+        // Averaging over the look-ahead turns the drop into a ramp that still
+        // reaches the needed gain by the time the peak leaves the delay line
+        self.recent.push_back(self.released);
+        self.recent_sum += self.released as f64;
+        if let Some(oldest) = self.recent.pop_front() {
+            self.recent_sum -= oldest as f64;
+        }
+        let gain = (self.recent_sum / self.lookahead as f64) as f32;
+
+        // The clamp only catches rounding in the average, a few ulps at most
+        self.delay.push_back(sample);
+        let output = self.delay.pop_front().unwrap_or(0.0) * gain.min(1.0);
+        output.clamp(-self.threshold, self.threshold)
+    }
+}
+
+/// Limit a whole signal, without the look-ahead delay.
+pub fn limit(samples: &[f32], sample_rate: u32) -> Vec<f32> {
+    let mut limiter = Limiter::new(sample_rate);
+    let mut output = limiter.process(samples);
+    output.extend(limiter.flush());
+    output.drain(..limiter.lookahead - 1);
+    output
+}
+
+/// Second-order IIR section, transposed direct form II.
+struct Biquad {
+    b: [f64; 3],
+    a: [f64; 2],
+    state: [f64; 2],
+}
+
+impl Biquad {
+    fn process(&mut self, x: f64) -> f64 {
+        let y = self.b[0] * x + self.state[0];
+        self.state[0] = self.b[1] * x - self.a[0] * y + self.state[1];
+        self.state[1] = self.b[2] * x - self.a[1] * y;
+        y
+    }
+}
+
+/// The two K-weighting filters of BS.1770 (a high shelf for the head, then a
+/// high-pass), derived for any sample rate as libebur128 does.
+fn k_weighting(sample_rate: u32) -> [Biquad; 2] {
+    let rate = sample_rate as f64;
+
+    let f0 = 1681.974450955533;
+    let gain_db = 3.999843853973347;
+    let q = 0.7071752369554196;
+    let k = (std::f64::consts::PI * f0 / rate).tan();
+    let vh = 10f64.powf(gain_db / 20.0);
+    let vb = vh.powf(0.4996667741545416);
+    let a0 = 1.0 + k / q + k * k;
+    let shelf = Biquad {
+        b: [(vh + vb * k / q + k * k) / a0, 2.0 * (k * k - vh) / a0, (vh - vb * k / q + k * k) / a0],
+        a: [2.0 * (k * k - 1.0) / a0, (1.0 - k / q + k * k) / a0],
+        state: [0.0; 2],
+    };
+
+    let f0 = 38.13547087602444;
+    let q = 0.5003270373238773;
+    let k = (std::f64::consts::PI * f0 / rate).tan();
+    let a0 = 1.0 + k / q + k * k;
+    let high_pass = Biquad {
+        b: [1.0, -2.0, 1.0],
+        a: [2.0 * (k * k - 1.0) / a0, (1.0 - k / q + k * k) / a0],
+        state: [0.0; 2],
+    };
+
+    [shelf, high_pass]
+}
+
+/// BS.1770 weight of a channel; 5.1 audio is ordered L, R, C, LFE, Ls, Rs.
+fn channel_weight(channel: usize, channels: usize) -> f64 {
+    match (channels, channel) {
+        (6, 3) => 0.0,
+        (6, 4) | (6, 5) => 1.41,
+        _ => 1.0,
+    }
+}
+
+/// Integrated loudness of interleaved audio in LUFS, or None if it is shorter
+/// than one 400 ms block or entirely below the gate.
+pub fn integrated_loudness(samples: &[f32], channels: u16, sample_rate: u32) -> Option<f64> {
+    const ABSOLUTE_GATE_LUFS: f64 = -70.0;
+    const RELATIVE_GATE_LU: f64 = -10.0;
+
+    let channels = channels.max(1) as usize;
+    let mut filters: Vec<[Biquad; 2]> = (0..channels).map(|_| k_weighting(sample_rate)).collect();
+
+    // Weighted mean square of every 100 ms; blocks are four of these, overlapping by 75%
+    let step = (sample_rate as usize / 10).max(1);
+    let mut steps = Vec::new();
+    let mut sum = 0.0;
+    let mut count = 0;
+    for frame in samples.chunks_exact(channels) {
+        for (channel, (&sample, filter)) in frame.iter().zip(filters.iter_mut()).enumerate() {
+            let [shelf, high_pass] = filter;
+            let weighted = high_pass.process(shelf.process(sample as f64));
+            sum += channel_weight(channel, channels) * weighted * weighted;
+        }
+        count += 1;
+        if count == step {
+            steps.push(sum / step as f64);
+            sum = 0.0;
+            count = 0;
+        }
+    }
+
+    let loudness = |power: f64| -0.691 + 10.0 * power.log10();
+    let blocks: Vec<f64> = steps.windows(4)
+        .map(|window| window.iter().sum::<f64>() / 4.0)
+        .filter(|&power| power > 0.0 && loudness(power) > ABSOLUTE_GATE_LUFS)
+        .collect();
+    if blocks.is_empty() {
+        return None;
+    }
+
+    let mean = |blocks: &[f64]| blocks.iter().sum::<f64>() / blocks.len() as f64;
+    let relative_gate = loudness(mean(&blocks)) + RELATIVE_GATE_LU;
+    let gated: Vec<f64> = blocks.into_iter().filter(|&power| loudness(power) > relative_gate).collect();
+
+    Some(loudness(mean(&gated)))
+}
+
+/// Highest sample level in dBFS, or None for digital silence.
+pub fn peak_dbfs(samples: &[f32]) -> Option<f64> {
+    let peak = samples.iter().fold(0f32, |peak, sample| peak.max(sample.abs()));
+    (peak > 0.0).then(|| 20.0 * (peak as f64).log10())
+}
+
+/// Bring a mono WAV recording to TARGET_LOUDNESS_LUFS in place, limiting the
+/// peaks that the gain would push over. Returns the loudness measured before,
+/// or None if the file was left alone because it is silent.
+pub fn normalize_wav(path: &Path) -> Result<Option<f64>, String> {
+    let mut reader = hound::WavReader::open(path)
+        .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    let spec = reader.spec();
+    if spec.channels != 1 || spec.sample_format != hound::SampleFormat::Int {
+        return Err(format!("{} is not a mono PCM recording", path.display()));
+    }
+
+    let scale = (1i64 << (spec.bits_per_sample - 1)) as f32;
+    let samples = reader.samples::<i32>()
+        .map(|sample| sample.map(|sample| sample as f32 / scale))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    drop(reader);
+
+    let Some(loudness) = integrated_loudness(&samples, 1, spec.sample_rate) else {
+        return Ok(None);
+    };
+
+    let gain = 10f32.powf((TARGET_LOUDNESS_LUFS - loudness) as f32 / 20.0);
+    let amplified: Vec<f32> = samples.iter().map(|sample| sample * gain).collect();
+    let normalized = limit(&amplified, spec.sample_rate);
+
+    // Written beside the recording first, so a failure leaves the original intact
+    let partial_path = path.with_extension("wav.part");
+    let mut writer = hound::WavWriter::create(&partial_path, spec)
+        .map_err(|e| format!("Failed to create {}: {}", partial_path.display(), e))?;
+    for sample in normalized {
+        let value = (sample * scale).round().clamp(-scale, scale - 1.0) as i32;
+        writer.write_sample(value)
+            .map_err(|e| format!("Failed to write {}: {}", partial_path.display(), e))?;
+    }
+    writer.finalize()
+        .map_err(|e| format!("Failed to finalize {}: {}", partial_path.display(), e))?;
+
+    std::fs::rename(&partial_path, path)
+        .map_err(|e| format!("Failed to replace {}: {}", path.display(), e))?;
+    Ok(Some(loudness))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A 1 kHz stereo sine, `level_db` dBFS peak in each channel, for `seconds` per level.
+    fn stereo_sine(levels: &[(f64, f64)], sample_rate: u32) -> Vec<f32> {
+        let mut samples = Vec::new();
+        let mut frame = 0usize;
+        for &(level_db, seconds) in levels {
+            let amplitude = 10f64.powf(level_db / 20.0);
+            for _ in 0..(seconds * sample_rate as f64) as usize {
+                let sample = amplitude * (2.0 * std::f64::consts::PI * 1000.0 * frame as f64 / sample_rate as f64).sin();
+                samples.extend([sample as f32; 2]);
+                frame += 1;
+            }
+        }
+        samples
+    }
+
+    fn assert_loudness(samples: &[f32], sample_rate: u32, expected: f64) {
+        let measured = integrated_loudness(samples, 2, sample_rate).unwrap();
+        assert!((measured - expected).abs() <= 0.1, "{} Hz: {:.2} LUFS, expected {}", sample_rate, measured, expected);
+    }
+
+    // Test signals 1 to 4 of EBU Tech 3341; each must measure within 0.1 LU
+    #[test]
+    fn integrated_loudness_matches_ebu_tech_3341() {
+        for sample_rate in [48000, 44100] {
+            assert_loudness(&stereo_sine(&[(-23.0, 20.0)], sample_rate), sample_rate, -23.0);
+            assert_loudness(&stereo_sine(&[(-33.0, 20.0)], sample_rate), sample_rate, -33.0);
+            // The relative gate leaves out the quieter parts
+            assert_loudness(&stereo_sine(&[(-36.0, 10.0), (-23.0, 60.0), (-36.0, 10.0)], sample_rate), sample_rate, -23.0);
+            // The absolute gate leaves out the -72 dBFS parts, the relative gate the -36 dBFS ones
+            assert_loudness(
+                &stereo_sine(&[(-72.0, 10.0), (-36.0, 10.0), (-23.0, 20.0), (-36.0, 10.0), (-72.0, 10.0)], sample_rate),
+                sample_rate,
+                -23.0,
+            );
+        }
+    }
+
+    #[test]
+    fn integrated_loudness_is_none_for_silence_and_short_audio() {
+        assert_eq!(integrated_loudness(&vec![0.0; 2 * 48000], 2, 48000), None);
+        assert_eq!(integrated_loudness(&stereo_sine(&[(-23.0, 0.3)], 48000), 2, 48000), None);
+    }
+
+    #[test]
+    fn limit_never_exceeds_the_ceiling() {
+        let ceiling = db_to_gain(LIMIT_DB);
+        let sample_rate = 16000;
+
+        let mut seed = 1u32;
+        let mut noise = || {
+            seed = seed.wrapping_mul(1664525).wrapping_add(1013904223);
+            (seed >> 8) as f32 / (1u32 << 23) as f32 - 1.0
+        };
+        let signals: Vec<(&str, Vec<f32>)> = vec![
+            ("loud sine", (0..sample_rate).map(|i| 4.0 * (i as f32 * 0.3).sin()).collect()),
+            ("full-scale square", (0..sample_rate).map(|i| if (i / 8) % 2 == 0 { 1.0 } else { -1.0 }).collect()),
+            ("single-sample spikes", (0..sample_rate).map(|i| if i % 997 == 0 { 10.0 } else { 0.01 }).collect()),
+            ("spike at the start", std::iter::once(8.0).chain(std::iter::repeat_n(0.0, 100)).collect()),
+            ("spike at the end", std::iter::repeat_n(0.1, 100).chain(std::iter::once(-8.0)).collect()),
+            ("bursts of noise", (0..sample_rate).map(|i| noise() * if (i / 1600) % 2 == 0 { 6.0 } else { 0.2 }).collect()),
+        ];
+
+        for (name, signal) in signals {
+            let limited = limit(&signal, sample_rate);
+
+            assert_eq!(limited.len(), signal.len(), "{}", name);
+            let peak = limited.iter().fold(0f32, |peak, sample| peak.max(sample.abs()));
+            assert!(peak <= ceiling, "{}: peak {} over the ceiling {}", name, peak, ceiling);
+        }
+    }
+
+    #[test]
+    fn limit_leaves_quiet_audio_alone() {
+        let signal: Vec<f32> = (0..16000).map(|i| 0.5 * (i as f32 * 0.05).sin()).collect();
+
+        assert_eq!(limit(&signal, 16000), signal);
+    }
+}
@@ -0,0 +1,655 @@
+use crate::ai;
+use chrono::Local;
+use rusqlite::{params, Connection, Transaction};
+use std::path::{Path, PathBuf};
+
+/// A schema change, applied when `PRAGMA user_version` is below `version`.
+struct Migration {
+    version: i64,
+    description: &'static str,
+    apply: fn(&Transaction) -> rusqlite::Result<()>,
+}
+
+// Append new migrations at the end; never edit one that has shipped.
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "meetings and meeting_segments",
+        apply: create_meetings,
+    },
+    Migration {
+        version: 2,
+        description: "minutes sentiment, energy and topics",
+        apply: add_minutes_metadata,
+    },
+    Migration {
+        version: 3,
+        description: "action_items",
+        apply: create_action_items,
+    },
+    Migration {
+        version: 4,
+        description: "full-text search over meetings and segments",
+        apply: create_search_index,
+    },
+    Migration {
+        version: 5,
+        description: "segment speakers",
+        apply: add_segment_speakers,
+    },
+    Migration {
+        version: 6,
+        description: "segment audio source",
+        apply: add_segment_sources,
+    },
+    Migration {
+        version: 7,
+        description: "recording pauses",
+        apply: create_recording_pauses,
+    },
+    Migration {
+        version: 8,
+        description: "settings",
+        apply: create_settings,
+    },
+    Migration {
+        version: 9,
+        description: "glossary and transcript corrections",
+        apply: create_glossary,
+    },
+    Migration {
+        version: 10,
+        description: "segment words",
+        apply: create_segment_words,
+    },
+];
+
+/// Schema version written by a list of migrations.
+fn last_version(migrations: &[Migration]) -> i64 {
+    migrations.last().map(|m| m.version).unwrap_or(0)
+}
+
+/// Bring the database up to the latest schema version.
+///
+/// Pending migrations run in a single transaction, so a failure leaves the
+/// database at its previous version. Existing databases are copied next to
+/// the original before anything changes.
+pub fn migrate(conn: &mut Connection, db_path: &Path) -> Result<(), String> {
+    apply_migrations(conn, db_path, MIGRATIONS)
+}
+
+fn apply_migrations(conn: &mut Connection, db_path: &Path, migrations: &[Migration]) -> Result<(), String> {
+    let current = user_version(conn)
+        .map_err(|e| format!("Failed to read schema version: {}", e))?;
+    let latest = last_version(migrations);
+
+    if current > latest {
+        return Err(format!(
+            "Database schema version {} is newer than this app supports ({}). Please update the app.",
+            current, latest
+        ));
+    }
+
+    if current == latest {
+        return Ok(());
+    }
+
+    if has_tables(conn).map_err(|e| format!("Failed to inspect database: {}", e))? {
+        let backup_path = backup(conn, db_path, current)?;
+        println!("💾 Backed up database to {:?} before migrating", backup_path);
+    }
+
+    let tx = conn.transaction()
+        .map_err(|e| format!("Failed to start migration transaction: {}", e))?;
+
+    for migration in migrations.iter().filter(|m| m.version > current) {
+        println!("🛠️ Migrating database to version {}: {}", migration.version, migration.description);
+        (migration.apply)(&tx)
+            .map_err(|e| format!("Migration {} ({}) failed: {}", migration.version, migration.description, e))?;
+    }
+
+    tx.pragma_update(None, "user_version", latest)
+        .map_err(|e| format!("Failed to update schema version: {}", e))?;
+    tx.commit()
+        .map_err(|e| format!("Failed to commit migrations: {}", e))?;
+
+    println!("✅ Database schema migrated from version {} to {}", current, latest);
+    Ok(())
+}
+
+fn user_version(conn: &Connection) -> rusqlite::Result<i64> {
+    conn.query_row("PRAGMA user_version", [], |row| row.get(0))
+}
+
+fn has_tables(conn: &Connection) -> rusqlite::Result<bool> {
+    conn.query_row(
+        "SELECT EXISTS (SELECT 1 FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%')",
+        [],
+        |row| row.get(0),
+    )
+}
+
+/// Write a consistent copy of the database, e.g. `meetings.db.v2-20240501-093000.bak`.
+/// `VACUUM INTO` refuses to overwrite a file, so a second backup within the same
+/// second gets a counter: `meetings.db.v2-20240501-093000-2.bak`.
+fn backup(conn: &Connection, db_path: &Path, version: i64) -> Result<PathBuf, String> {
+    let file_name = db_path.file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or("meetings.db");
+    let stem = format!("{}.v{}-{}", file_name, version, Local::now().format("%Y%m%d-%H%M%S"));
+
+    let mut backup_path = db_path.with_file_name(format!("{}.bak", stem));
+    let mut counter = 2;
+    while backup_path.exists() {
+        backup_path = db_path.with_file_name(format!("{}-{}.bak", stem, counter));
+        counter += 1;
+    }
+
+    conn.execute("VACUUM INTO ?1", [backup_path.to_string_lossy()])
+        .map_err(|e| format!("Failed to back up database before migrating: {}", e))?;
+
+    Ok(backup_path)
+}
+
+/// Databases created before versioning (user_version 0) may already contain
+/// some of these columns, so migrations must tolerate them.
+fn add_column_if_missing(tx: &Transaction, table: &str, column: &str, definition: &str) -> rusqlite::Result<()> {
+    let mut stmt = tx.prepare(&format!("PRAGMA table_info({})", table))?;
+    let exists = stmt
+        .query_map([], |row| row.get::<_, String>(1))?
+        .filter_map(|name| name.ok())
+        .any(|name| name == column);
+
+    if !exists {
+        tx.execute(&format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition), [])?;
+    }
+
+    Ok(())
+}
+
+fn create_meetings(tx: &Transaction) -> rusqlite::Result<()> {
+    tx.execute_batch(
+        "CREATE TABLE IF NOT EXISTS meetings (
+            id TEXT PRIMARY KEY,
+            title TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            updated_at TEXT NOT NULL,
+            duration_seconds INTEGER,
+            audio_file_path TEXT,
+            transcript TEXT,
+            meeting_minutes TEXT,
+            language TEXT,
+            ai_provider TEXT
+        );
+
+        -- Detailed transcription segments
+        CREATE TABLE IF NOT EXISTS meeting_segments (
+            id TEXT PRIMARY KEY,
+            meeting_id TEXT NOT NULL,
+            start_time REAL NOT NULL,
+            end_time REAL NOT NULL,
+            text TEXT NOT NULL,
+            confidence REAL,
+            FOREIGN KEY(meeting_id) REFERENCES meetings(id) ON DELETE CASCADE
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_meetings_created_at ON meetings(created_at);
+        CREATE INDEX IF NOT EXISTS idx_segments_meeting_id ON meeting_segments(meeting_id);"
+    )
+}
+
+fn add_minutes_metadata(tx: &Transaction) -> rusqlite::Result<()> {
+    add_column_if_missing(tx, "meetings", "sentiment", "TEXT")?;
+    add_column_if_missing(tx, "meetings", "energy", "TEXT")?;
+
+    tx.execute_batch(
+        "CREATE TABLE IF NOT EXISTS meeting_topics (
+            meeting_id TEXT NOT NULL,
+            topic TEXT NOT NULL,
+            position INTEGER NOT NULL,
+            PRIMARY KEY(meeting_id, topic),
+            FOREIGN KEY(meeting_id) REFERENCES meetings(id) ON DELETE CASCADE
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_topics_topic ON meeting_topics(topic COLLATE NOCASE);"
+    )?;
+
+    backfill_minutes_metadata(tx)
+}
+
+/// Minutes saved before the metadata trailer was parsed still end with it; move
+/// it into the structured fields.
+fn backfill_minutes_metadata(tx: &Transaction) -> rusqlite::Result<()> {
+    let minutes: Vec<(String, String)> = {
+        let mut stmt = tx.prepare("SELECT id, meeting_minutes FROM meetings WHERE meeting_minutes IS NOT NULL")?;
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        rows.collect::<rusqlite::Result<_>>()?
+    };
+
+    let mut updated = 0;
+    for (meeting_id, text) in minutes {
+        let (body, Some(metadata)) = ai::split_minutes_trailer(&text) else { continue };
+
+        tx.execute(
+            "UPDATE meetings SET meeting_minutes = ?1, sentiment = ?2, energy = ?3 WHERE id = ?4",
+            params![body, metadata.sentiment, metadata.energy, meeting_id],
+        )?;
+        tx.execute("DELETE FROM meeting_topics WHERE meeting_id = ?1", [&meeting_id])?;
+        for (position, topic) in metadata.key_topics.iter().enumerate() {
+            tx.execute(
+                "INSERT OR IGNORE INTO meeting_topics (meeting_id, topic, position) VALUES (?1, ?2, ?3)",
+                params![meeting_id, topic, position as i64],
+            )?;
+        }
+        updated += 1;
+    }
+
+    if updated > 0 {
+        println!("🏷️ Parsed minutes metadata for {} existing meetings", updated);
+    }
+    Ok(())
+}
+
+fn create_action_items(tx: &Transaction) -> rusqlite::Result<()> {
+    tx.execute_batch(
+        "CREATE TABLE IF NOT EXISTS action_items (
+            id TEXT PRIMARY KEY,
+            meeting_id TEXT NOT NULL,
+            description TEXT NOT NULL,
+            owner TEXT,
+            due_date TEXT,
+            status TEXT NOT NULL DEFAULT 'open',
+            source_time REAL,
+            created_at TEXT NOT NULL,
+            completed_at TEXT,
+            FOREIGN KEY(meeting_id) REFERENCES meetings(id) ON DELETE CASCADE
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_action_items_meeting_id ON action_items(meeting_id);
+        CREATE INDEX IF NOT EXISTS idx_action_items_status ON action_items(status);"
+    )
+}
+
+// The FTS5 tables hold a copy of the text with the id of the row it came from;
+// triggers keep them in sync.
+fn create_search_index(tx: &Transaction) -> rusqlite::Result<()> {
+    tx.execute_batch(
+        "CREATE VIRTUAL TABLE IF NOT EXISTS meetings_fts USING fts5(
+            id UNINDEXED, title, transcript, meeting_minutes,
+            tokenize='porter unicode61 remove_diacritics 2'
+        );
+
+        CREATE TRIGGER IF NOT EXISTS meetings_fts_insert AFTER INSERT ON meetings BEGIN
+            INSERT INTO meetings_fts(id, title, transcript, meeting_minutes)
+            VALUES (new.id, new.title, new.transcript, new.meeting_minutes);
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS meetings_fts_delete AFTER DELETE ON meetings BEGIN
+            DELETE FROM meetings_fts WHERE id = old.id;
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS meetings_fts_update AFTER UPDATE OF id, title, transcript, meeting_minutes ON meetings BEGIN
+            DELETE FROM meetings_fts WHERE id = old.id;
+            INSERT INTO meetings_fts(id, title, transcript, meeting_minutes)
+            VALUES (new.id, new.title, new.transcript, new.meeting_minutes);
+        END;
+
+        CREATE VIRTUAL TABLE IF NOT EXISTS segments_fts USING fts5(
+            id UNINDEXED, text,
+            tokenize='porter unicode61 remove_diacritics 2'
+        );
+
+        CREATE TRIGGER IF NOT EXISTS segments_fts_insert AFTER INSERT ON meeting_segments BEGIN
+            INSERT INTO segments_fts(id, text) VALUES (new.id, new.text);
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS segments_fts_delete AFTER DELETE ON meeting_segments BEGIN
+            DELETE FROM segments_fts WHERE id = old.id;
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS segments_fts_update AFTER UPDATE OF id, text ON meeting_segments BEGIN
+            DELETE FROM segments_fts WHERE id = old.id;
+            INSERT INTO segments_fts(id, text) VALUES (new.id, new.text);
+        END;
+
+        -- Index the rows that existed before the triggers
+        INSERT INTO meetings_fts(id, title, transcript, meeting_minutes)
+        SELECT id, title, transcript, meeting_minutes FROM meetings;
+        INSERT INTO segments_fts(id, text) SELECT id, text FROM meeting_segments;"
+    )
+}
+
+fn add_segment_speakers(tx: &Transaction) -> rusqlite::Result<()> {
+    add_column_if_missing(tx, "meeting_segments", "speaker", "TEXT")
+}
+
+fn add_segment_sources(tx: &Transaction) -> rusqlite::Result<()> {
+    add_column_if_missing(tx, "meeting_segments", "source", "TEXT")
+}
+
+fn create_recording_pauses(tx: &Transaction) -> rusqlite::Result<()> {
+    tx.execute_batch(
+        "CREATE TABLE IF NOT EXISTS recording_pauses (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            meeting_id TEXT NOT NULL,
+            audio_offset REAL NOT NULL,
+            paused_at TEXT NOT NULL,
+            resumed_at TEXT,
+            FOREIGN KEY(meeting_id) REFERENCES meetings(id) ON DELETE CASCADE
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_recording_pauses_meeting_id ON recording_pauses(meeting_id);"
+    )
+}
+
+fn create_settings(tx: &Transaction) -> rusqlite::Result<()> {
+    tx.execute_batch(
+        "CREATE TABLE IF NOT EXISTS settings (
+            key TEXT PRIMARY KEY,
+            value TEXT NOT NULL
+        );"
+    )
+}
+
+fn create_glossary(tx: &Transaction) -> rusqlite::Result<()> {
+    tx.execute_batch(
+        "-- meeting_id is NULL for terms that apply to every meeting
+        CREATE TABLE IF NOT EXISTS glossary_terms (
+            id TEXT PRIMARY KEY,
+            meeting_id TEXT,
+            term TEXT NOT NULL,
+            variants TEXT NOT NULL DEFAULT '',
+            created_at TEXT NOT NULL,
+            FOREIGN KEY(meeting_id) REFERENCES meetings(id) ON DELETE CASCADE
+        );
+
+        CREATE TABLE IF NOT EXISTS transcript_corrections (
+            id TEXT PRIMARY KEY,
+            meeting_id TEXT NOT NULL,
+            segment_id TEXT NOT NULL,
+            term_id TEXT,
+            term TEXT NOT NULL,
+            original_text TEXT NOT NULL,
+            corrected_text TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            reverted_at TEXT,
+            FOREIGN KEY(meeting_id) REFERENCES meetings(id) ON DELETE CASCADE
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_glossary_terms_meeting_id ON glossary_terms(meeting_id);
+        CREATE INDEX IF NOT EXISTS idx_transcript_corrections_meeting_id ON transcript_corrections(meeting_id);"
+    )
+}
+
+fn create_segment_words(tx: &Transaction) -> rusqlite::Result<()> {
+    tx.execute_batch(
+        "CREATE TABLE IF NOT EXISTS segment_words (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            segment_id TEXT NOT NULL,
+            meeting_id TEXT NOT NULL,
+            position INTEGER NOT NULL,
+            start_time REAL NOT NULL,
+            end_time REAL NOT NULL,
+            text TEXT NOT NULL,
+            probability REAL NOT NULL,
+            FOREIGN KEY(segment_id) REFERENCES meeting_segments(id) ON DELETE CASCADE,
+            FOREIGN KEY(meeting_id) REFERENCES meetings(id) ON DELETE CASCADE
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_segment_words_segment_id ON segment_words(segment_id);
+        CREATE INDEX IF NOT EXISTS idx_segment_words_meeting_id ON segment_words(meeting_id);"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const V0_FIXTURE: &str = include_str!("../tests/fixtures/meetings-v0.sql");
+
+    fn v0_database(dir: &Path) -> (Connection, PathBuf) {
+        let db_path = dir.join("meetings.db");
+        let conn = Connection::open(&db_path).unwrap();
+        conn.execute_batch(V0_FIXTURE).unwrap();
+        (conn, db_path)
+    }
+
+    fn count(conn: &Connection, sql: &str) -> i64 {
+        conn.query_row(sql, [], |row| row.get(0)).unwrap()
+    }
+
+    fn backups(dir: &Path) -> Vec<PathBuf> {
+        let mut backups: Vec<PathBuf> = std::fs::read_dir(dir).unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "bak"))
+            .collect();
+        backups.sort();
+        backups
+    }
+
+    #[test]
+    fn upgrades_v0_database_and_keeps_its_data() {
+        let dir = tempfile::tempdir().unwrap();
+        let (mut conn, db_path) = v0_database(dir.path());
+
+        migrate(&mut conn, &db_path).unwrap();
+
+        assert_eq!(user_version(&conn).unwrap(), last_version(MIGRATIONS));
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM meetings"), 2);
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM meeting_segments"), 3);
+
+        let (title, transcript, duration): (String, String, i64) = conn.query_row(
+            "SELECT title, transcript, duration_seconds FROM meetings WHERE id = 'meeting-hiring'",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        ).unwrap();
+        assert_eq!(title, "Hiring sync");
+        assert_eq!(transcript, "Two backend engineers start in April.");
+        assert_eq!(duration, 1800);
+
+        let (text, confidence, speaker): (String, Option<f64>, Option<String>) = conn.query_row(
+            "SELECT text, confidence, speaker FROM meeting_segments WHERE id = 'segment-1'",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        ).unwrap();
+        assert_eq!(text, "We agreed to cut the travel budget");
+        assert_eq!(confidence, Some(0.92));
+        assert_eq!(speaker, None);
+
+        // Tables added later start out empty
+        for table in ["action_items", "recording_pauses", "settings", "glossary_terms", "transcript_corrections", "segment_words"] {
+            assert_eq!(count(&conn, &format!("SELECT COUNT(*) FROM {}", table)), 0, "{}", table);
+        }
+    }
+
+    #[test]
+    fn backs_up_the_original_before_migrating() {
+        let dir = tempfile::tempdir().unwrap();
+        let (mut conn, db_path) = v0_database(dir.path());
+
+        migrate(&mut conn, &db_path).unwrap();
+
+        let backups = backups(dir.path());
+        assert_eq!(backups.len(), 1);
+        let name = backups[0].file_name().unwrap().to_string_lossy().to_string();
+        assert!(name.starts_with("meetings.db.v0-"), "{}", name);
+
+        let backup = Connection::open(&backups[0]).unwrap();
+        assert_eq!(user_version(&backup).unwrap(), 0);
+        assert_eq!(count(&backup, "SELECT COUNT(*) FROM meetings"), 2);
+        assert_eq!(count(&backup, "SELECT COUNT(*) FROM sqlite_master WHERE name = 'action_items'"), 0);
+    }
+
+    #[test]
+    fn moves_minutes_trailer_into_metadata() {
+        let dir = tempfile::tempdir().unwrap();
+        let (mut conn, db_path) = v0_database(dir.path());
+
+        migrate(&mut conn, &db_path).unwrap();
+
+        let (minutes, sentiment, energy): (String, Option<String>, Option<String>) = conn.query_row(
+            "SELECT meeting_minutes, sentiment, energy FROM meetings WHERE id = 'meeting-budget'",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        ).unwrap();
+        assert_eq!(minutes, "## Summary\nThe team reviewed the quarterly budget.");
+        assert_eq!(sentiment.as_deref(), Some("Neutral"));
+        assert_eq!(energy.as_deref(), Some("Medium"));
+
+        let topics: Vec<String> = conn.prepare("SELECT topic FROM meeting_topics WHERE meeting_id = 'meeting-budget' ORDER BY position")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<rusqlite::Result<_>>()
+            .unwrap();
+        assert_eq!(topics, ["Budget", "Travel"]);
+
+        // Minutes without a trailer are left alone
+        let (minutes, sentiment): (String, Option<String>) = conn.query_row(
+            "SELECT meeting_minutes, sentiment FROM meetings WHERE id = 'meeting-hiring'",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        ).unwrap();
+        assert_eq!(minutes, "## Summary\nOnboarding plans for new engineers.");
+        assert_eq!(sentiment, None);
+    }
+
+    #[test]
+    fn indexes_existing_and_new_rows_for_search() {
+        let dir = tempfile::tempdir().unwrap();
+        let (mut conn, db_path) = v0_database(dir.path());
+
+        migrate(&mut conn, &db_path).unwrap();
+
+        let segment_hits = |term: &str| -> Vec<String> {
+            conn.prepare(
+                "SELECT s.id FROM segments_fts JOIN meeting_segments s ON s.id = segments_fts.id
+                 WHERE segments_fts MATCH ?1 ORDER BY s.id"
+            ).unwrap()
+                .query_map([term], |row| row.get(0))
+                .unwrap()
+                .collect::<rusqlite::Result<_>>()
+                .unwrap()
+        };
+        let meeting_hits = |term: &str| -> Vec<String> {
+            conn.prepare(
+                "SELECT m.id FROM meetings_fts JOIN meetings m ON m.id = meetings_fts.id
+                 WHERE meetings_fts MATCH ?1 ORDER BY m.id"
+            ).unwrap()
+                .query_map([term], |row| row.get(0))
+                .unwrap()
+                .collect::<rusqlite::Result<_>>()
+                .unwrap()
+        };
+
+        // Rows from before the migration; "engineers" matches "engineer" through the porter stemmer
+        assert_eq!(segment_hits("travel"), ["segment-1"]);
+        assert_eq!(segment_hits("engineer"), ["segment-3"]);
+        assert_eq!(meeting_hits("hiring"), ["meeting-hiring"]);
+        assert_eq!(meeting_hits("budget"), ["meeting-budget"]);
+
+        // Rows written afterwards are kept in sync by the triggers
+        conn.execute("UPDATE meeting_segments SET text = 'by fifteen percent.' WHERE id = 'segment-2'", []).unwrap();
+        conn.execute("DELETE FROM meeting_segments WHERE id = 'segment-3'", []).unwrap();
+        conn.execute("UPDATE meetings SET title = 'Budget and hiring' WHERE id = 'meeting-hiring'", []).unwrap();
+        assert_eq!(segment_hits("fifteen"), ["segment-2"]);
+        assert!(segment_hits("engineer").is_empty());
+        assert_eq!(meeting_hits("budget"), ["meeting-budget", "meeting-hiring"]);
+    }
+
+    #[test]
+    fn search_index_survives_vacuum() {
+        let dir = tempfile::tempdir().unwrap();
+        let (mut conn, db_path) = v0_database(dir.path());
+        migrate(&mut conn, &db_path).unwrap();
+
+        // Leave a gap in the rowids so VACUUM renumbers the rows after it
+        conn.execute("DELETE FROM meeting_segments WHERE id = 'segment-1'", []).unwrap();
+        conn.execute("DELETE FROM meetings WHERE id = 'meeting-budget'", []).unwrap();
+        conn.execute_batch("VACUUM").unwrap();
+
+        let segment: String = conn.query_row(
+            "SELECT s.id FROM segments_fts JOIN meeting_segments s ON s.id = segments_fts.id
+             WHERE segments_fts MATCH 'april'",
+            [],
+            |row| row.get(0),
+        ).unwrap();
+        assert_eq!(segment, "segment-3");
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM segments_fts"), count(&conn, "SELECT COUNT(*) FROM meeting_segments"));
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM meetings_fts WHERE meetings_fts MATCH 'budget'"), 0);
+    }
+
+    #[test]
+    fn migrating_a_current_database_changes_nothing() {
+        let dir = tempfile::tempdir().unwrap();
+        let (mut conn, db_path) = v0_database(dir.path());
+        migrate(&mut conn, &db_path).unwrap();
+
+        migrate(&mut conn, &db_path).unwrap();
+
+        assert_eq!(user_version(&conn).unwrap(), last_version(MIGRATIONS));
+        assert_eq!(backups(dir.path()).len(), 1);
+    }
+
+    #[test]
+    fn new_database_is_created_without_backup() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("meetings.db");
+        let mut conn = Connection::open(&db_path).unwrap();
+
+        migrate(&mut conn, &db_path).unwrap();
+
+        assert_eq!(user_version(&conn).unwrap(), last_version(MIGRATIONS));
+        assert!(backups(dir.path()).is_empty());
+    }
+
+    #[test]
+    fn failed_migration_rolls_back_every_pending_step() {
+        fn create_extra(tx: &Transaction) -> rusqlite::Result<()> {
+            tx.execute_batch("CREATE TABLE extra (id INTEGER)")
+        }
+        fn fail_halfway(tx: &Transaction) -> rusqlite::Result<()> {
+            tx.execute_batch("ALTER TABLE meetings ADD COLUMN half_done TEXT; SELECT * FROM missing_table;")
+        }
+        let migrations = [
+            Migration { version: 1, description: "meetings and meeting_segments", apply: create_meetings },
+            Migration { version: 2, description: "extra", apply: create_extra },
+            Migration { version: 3, description: "broken", apply: fail_halfway },
+        ];
+
+        let dir = tempfile::tempdir().unwrap();
+        let (mut conn, db_path) = v0_database(dir.path());
+
+        let error = apply_migrations(&mut conn, &db_path, &migrations).unwrap_err();
+
+        assert!(error.starts_with("Migration 3 (broken) failed"), "{}", error);
+        assert_eq!(user_version(&conn).unwrap(), 0);
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM sqlite_master WHERE name = 'extra'"), 0);
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM pragma_table_info('meetings') WHERE name = 'half_done'"), 0);
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM meetings"), 2);
+    }
+
+    #[test]
+    fn refuses_a_database_from_a_newer_version() {
+        let dir = tempfile::tempdir().unwrap();
+        let (mut conn, db_path) = v0_database(dir.path());
+        conn.pragma_update(None, "user_version", last_version(MIGRATIONS) + 1).unwrap();
+
+        let error = migrate(&mut conn, &db_path).unwrap_err();
+
+        assert!(error.contains("newer than this app supports"), "{}", error);
+        assert!(backups(dir.path()).is_empty());
+    }
+
+    #[test]
+    fn backups_in_the_same_second_get_distinct_names() {
+        let dir = tempfile::tempdir().unwrap();
+        let (conn, db_path) = v0_database(dir.path());
+
+        let first = backup(&conn, &db_path, 0).unwrap();
+        let second = backup(&conn, &db_path, 0).unwrap();
+
+        assert_ne!(first, second);
+        assert!(first.exists() && second.exists());
+    }
+}
@@ -0,0 +1,438 @@
+// Whisper model files: listing, downloading and deleting `ggml-*.bin` files
+// in the models directory.
+//
+// Downloads go to `<name>.part` and are resumed with an HTTP range request if
+// that file already exists. The file is only renamed into place once its
+// SHA-256 matches, so a model that shows up in the list is always complete.
+//
+// `load` turns a model file into a `WhisperContext` and reports what was loaded.
+
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use whisper_rs::{WhisperContext, WhisperContextParameters};
+
+const HUGGINGFACE: &str = "https://huggingface.co";
+const WHISPER_CPP_REPO: &str = "ggerganov/whisper.cpp";
+// tinydiarize models are published separately, as in whisper.cpp's download-ggml-model.sh
+const TINYDIARIZE_REPO: &str = "akashmjn/tinydiarize-whisper.cpp";
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Preferred models when none has been selected, best first.
+pub const MODEL_PRIORITY: [(&str, &str); 8] = [
+    ("ggml-large-v3-turbo.bin", "Large V3 Turbo (Fast, supports Indonesian with language param)"),
+    ("ggml-large-v3.bin", "Large V3 Multilingual (RECOMMENDED: Best for Indonesian)"),
+    ("ggml-medium.bin", "Medium Multilingual (Good balance for Indonesian)"),
+    ("ggml-small.bin", "Small Multilingual (Faster, good for Indonesian)"),
+    ("ggml-small.en.bin", "Small English (English only)"),
+    ("ggml-base.en.bin", "Base English (English only)"),
+    ("ggml-medium.en.bin", "Medium English (English only)"),
+    ("ggml-small.en-tdrz.bin", "Small English with speaker turn detection (English only)"),
+];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelInfo {
+    pub name: String,
+    pub path: String,
+    pub size_bytes: u64,
+    pub multilingual: bool,
+    pub description: Option<String>,
+    pub selected: bool, // Chosen in settings
+    pub loaded: bool,   // Currently used for transcription
+    pub realtime: bool, // Loaded separately for real-time transcription
+}
+
+/// Parameters of a loaded model.
+#[derive(Debug, Clone, Serialize)]
+pub struct LoadedModel {
+    pub name: String,
+    pub path: String,
+    pub model_type: String, // "tiny", "base", "small", "medium" or "large"
+    pub multilingual: bool,
+    pub vocab_size: i32,
+    pub audio_layers: i32,
+    pub text_layers: i32,
+    pub mel_bands: i32,
+    pub ftype: i32, // Weight type, e.g. 1 for f16
+    pub load_seconds: f32,
+}
+
+pub fn models_dir() -> Result<PathBuf, String> {
+    let home_dir = dirs::home_dir().ok_or("Could not find home directory")?;
+    let models_dir = home_dir.join("Documents").join("MeetingRecorder").join("MeetingRecordings").join("models");
+    std::fs::create_dir_all(&models_dir).map_err(|e| e.to_string())?;
+    Ok(models_dir)
+}
+
+/// Model names are plain file names like `ggml-small.bin`; anything else could escape the models directory.
+pub fn validate_model_name(name: &str) -> Result<(), String> {
+    let valid = name.starts_with("ggml-")
+        && name.ends_with(".bin")
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '.' | '_'))
+        && !name.contains("..");
+
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("Invalid model name: {}", name))
+    }
+}
+
+/// English-only models are named `*.en.bin` or `*.en-<variant>.bin`.
+pub fn is_multilingual(name: &str) -> bool {
+    !name.contains(".en.") && !name.contains(".en-")
+}
+
+pub fn description(name: &str) -> Option<&'static str> {
+    MODEL_PRIORITY.iter().find(|(file, _)| *file == name).map(|(_, description)| *description)
+}
+
+/// Installed models, sorted by name.
+pub fn list_models(dir: &Path, selected: Option<&str>, loaded: Option<&Path>, realtime: Option<&Path>) -> Vec<ModelInfo> {
+    let Ok(entries) = std::fs::read_dir(dir) else { return Vec::new() };
+
+    let mut models: Vec<ModelInfo> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let name = entry.file_name().to_str()?.to_string();
+            validate_model_name(&name).ok()?;
+            let size_bytes = entry.metadata().ok()?.len();
+            let path = entry.path();
+            Some(ModelInfo {
+                multilingual: is_multilingual(&name),
+                description: description(&name).map(|d| d.to_string()),
+                selected: selected == Some(name.as_str()),
+                loaded: loaded == Some(path.as_path()),
+                realtime: realtime == Some(path.as_path()),
+                path: path.to_string_lossy().to_string(),
+                size_bytes,
+                name,
+            })
+        })
+        .collect();
+
+    models.sort_by(|a, b| a.name.cmp(&b.name));
+    models
+}
+
+pub fn load(path: &Path) -> Result<(WhisperContext, LoadedModel), String> {
+    let started = Instant::now();
+    let ctx = WhisperContext::new_with_params(&path.to_string_lossy(), WhisperContextParameters::default())
+        .map_err(|e| format!("Failed to load {}: {}", path.display(), e))?;
+
+    let info = LoadedModel {
+        name: path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default(),
+        path: path.to_string_lossy().to_string(),
+        model_type: ctx.model_type_readable().unwrap_or_else(|_| "unknown".to_string()),
+        multilingual: ctx.is_multilingual(),
+        vocab_size: ctx.model_n_vocab(),
+        audio_layers: ctx.model_n_audio_layer(),
+        text_layers: ctx.model_n_text_layer(),
+        mel_bands: ctx.model_n_mels(),
+        ftype: ctx.model_ftype(),
+        load_seconds: started.elapsed().as_secs_f32(),
+    };
+
+    Ok((ctx, info))
+}
+
+/// Hugging Face repository a model is downloaded from by default.
+fn source_repo(name: &str) -> &'static str {
+    if name.contains("-tdrz") {
+        TINYDIARIZE_REPO
+    } else {
+        WHISPER_CPP_REPO
+    }
+}
+
+pub fn default_url(name: &str) -> String {
+    format!("{}/{}/resolve/main/{}", HUGGINGFACE, source_repo(name), name)
+}
+
+/// SHA-256 of a model in its Hugging Face repository, from its Git LFS metadata.
+pub async fn published_sha256(client: &reqwest::Client, name: &str) -> Result<String, String> {
+    let tree_url = format!("{}/api/models/{}/tree/main", HUGGINGFACE, source_repo(name));
+    let files: serde_json::Value = client.get(&tree_url)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch model checksums: {}", e))?
+        .error_for_status()
+        .map_err(|e| format!("Failed to fetch model checksums: {}", e))?
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse model checksums: {}", e))?;
+
+    files.as_array()
+        .and_then(|files| files.iter().find(|file| file["path"] == name))
+        .and_then(|file| file["lfs"]["oid"].as_str())
+        .map(|sha| sha.to_lowercase())
+        .ok_or_else(|| format!("No checksum published for {}", name))
+}
+
+/// Download `url` to `dest`, resuming a previous partial download, and verify it.
+/// `on_progress` gets the bytes downloaded so far and the total size if known.
+pub async fn download<F>(client: &reqwest::Client, url: &str, dest: &Path, expected_sha256: &str, mut on_progress: F) -> Result<(), String>
+where
+    F: FnMut(u64, Option<u64>),
+{
+    let part_path = part_path(dest);
+    let io_error = |e: std::io::Error| format!("Failed to write {}: {}", part_path.display(), e);
+
+    let mut downloaded = std::fs::metadata(&part_path).map(|m| m.len()).unwrap_or(0);
+
+    let mut request = client.get(url);
+    if downloaded > 0 {
+        println!("⏯️ Resuming download of {} at {} bytes", url, downloaded);
+        request = request.header(reqwest::header::RANGE, format!("bytes={}-", downloaded));
+    }
+
+    let mut response = request.send().await
+        .map_err(|e| format!("Failed to download {}: {}", url, e))?;
+
+    let total = match response.status() {
+        // The partial file already holds everything the server has
+        reqwest::StatusCode::RANGE_NOT_SATISFIABLE => Some(downloaded),
+        reqwest::StatusCode::PARTIAL_CONTENT => content_range_total(&response)
+            .or_else(|| response.content_length().map(|len| downloaded + len)),
+        status if status.is_success() => {
+            // The server ignored the range, start over
+            downloaded = 0;
+            response.content_length()
+        }
+        status => return Err(format!("Failed to download {}: HTTP {}", url, status)),
+    };
+
+    if response.status() != reqwest::StatusCode::RANGE_NOT_SATISFIABLE {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .append(downloaded > 0)
+            .truncate(downloaded == 0)
+            .open(&part_path)
+            .map_err(io_error)?;
+
+        on_progress(downloaded, total);
+        let mut last_progress = Instant::now();
+
+        while let Some(chunk) = response.chunk().await.map_err(|e| format!("Download of {} interrupted: {}", url, e))? {
+            file.write_all(&chunk).map_err(io_error)?;
+            downloaded += chunk.len() as u64;
+
+            if last_progress.elapsed() >= PROGRESS_INTERVAL {
+                on_progress(downloaded, total);
+                last_progress = Instant::now();
+            }
+        }
+
+        file.sync_all().map_err(io_error)?;
+        on_progress(downloaded, total);
+    }
+
+    if let Some(total) = total {
+        if downloaded < total {
+            return Err(format!("Download of {} incomplete: {} of {} bytes", url, downloaded, total));
+        }
+    }
+
+    // Hashing a large model takes seconds; keep it off the async runtime
+    let hash_path = part_path.clone();
+    let actual_sha256 = tauri::async_runtime::spawn_blocking(move || sha256_file(&hash_path))
+        .await
+        .map_err(|e| format!("Failed to verify {}: {}", part_path.display(), e))??;
+    if !actual_sha256.eq_ignore_ascii_case(expected_sha256.trim()) {
+        // Resuming would only append to corrupt data
+        let _ = std::fs::remove_file(&part_path);
+        return Err(format!(
+            "Checksum mismatch for {}: expected {}, got {}",
+            dest.display(), expected_sha256.trim(), actual_sha256
+        ));
+    }
+
+    std::fs::rename(&part_path, dest)
+        .map_err(|e| format!("Failed to move model into place: {}", e))?;
+
+    Ok(())
+}
+
+/// `ggml-small.bin` -> `ggml-small.bin.part`
+pub fn part_path(dest: &Path) -> PathBuf {
+    let mut name = dest.file_name().unwrap_or_default().to_os_string();
+    name.push(".part");
+    dest.with_file_name(name)
+}
+
+/// Total size from a `Content-Range: bytes 100-999/1000` header.
+fn content_range_total(response: &reqwest::Response) -> Option<u64> {
+    response.headers()
+        .get(reqwest::header::CONTENT_RANGE)?
+        .to_str().ok()?
+        .rsplit('/')
+        .next()?
+        .parse().ok()
+}
+
+pub fn sha256_file(path: &Path) -> Result<String, String> {
+    let mut file = File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 1 << 20];
+
+    loop {
+        let read = file.read(&mut buffer).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::Matcher;
+
+    const MODEL: &[u8] = b"ggml model weights for the download tests";
+
+    fn sha256(data: &[u8]) -> String {
+        format!("{:x}", Sha256::digest(data))
+    }
+
+    #[tokio::test]
+    async fn downloads_and_verifies_a_model() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server.mock("GET", "/ggml-tiny.bin")
+            .with_body(MODEL)
+            .create_async()
+            .await;
+        let dir = tempfile::tempdir().unwrap();
+        let dest = dir.path().join("ggml-tiny.bin");
+        let mut progress = Vec::new();
+
+        download(&reqwest::Client::new(), &format!("{}/ggml-tiny.bin", server.url()), &dest, &sha256(MODEL), |done, total| {
+            progress.push((done, total));
+        }).await.unwrap();
+
+        mock.assert_async().await;
+        assert_eq!(std::fs::read(&dest).unwrap(), MODEL);
+        assert!(!part_path(&dest).exists());
+        assert_eq!(progress.last(), Some(&(MODEL.len() as u64, Some(MODEL.len() as u64))));
+    }
+
+    #[tokio::test]
+    async fn resumes_a_partial_download_with_a_range_request() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server.mock("GET", "/ggml-tiny.bin")
+            .match_header("range", "bytes=10-")
+            .with_status(206)
+            .with_header("content-range", &format!("bytes 10-{}/{}", MODEL.len() - 1, MODEL.len()))
+            .with_body(&MODEL[10..])
+            .create_async()
+            .await;
+        let dir = tempfile::tempdir().unwrap();
+        let dest = dir.path().join("ggml-tiny.bin");
+        std::fs::write(part_path(&dest), &MODEL[..10]).unwrap();
+        let mut progress = Vec::new();
+
+        download(&reqwest::Client::new(), &format!("{}/ggml-tiny.bin", server.url()), &dest, &sha256(MODEL), |done, total| {
+            progress.push((done, total));
+        }).await.unwrap();
+
+        mock.assert_async().await;
+        assert_eq!(std::fs::read(&dest).unwrap(), MODEL);
+        assert_eq!(progress.first(), Some(&(10, Some(MODEL.len() as u64))));
+    }
+
+    #[tokio::test]
+    async fn starts_over_when_the_server_ignores_the_range() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server.mock("GET", "/ggml-tiny.bin")
+            .match_header("range", Matcher::Any)
+            .with_body(MODEL)
+            .create_async()
+            .await;
+        let dir = tempfile::tempdir().unwrap();
+        let dest = dir.path().join("ggml-tiny.bin");
+        std::fs::write(part_path(&dest), b"stale bytes").unwrap();
+
+        download(&reqwest::Client::new(), &format!("{}/ggml-tiny.bin", server.url()), &dest, &sha256(MODEL), |_, _| {}).await.unwrap();
+
+        mock.assert_async().await;
+        assert_eq!(std::fs::read(&dest).unwrap(), MODEL);
+    }
+
+    #[tokio::test]
+    async fn finishes_a_download_that_was_already_complete() {
+        let mut server = mockito::Server::new_async().await;
+        server.mock("GET", "/ggml-tiny.bin")
+            .with_status(416)
+            .create_async()
+            .await;
+        let dir = tempfile::tempdir().unwrap();
+        let dest = dir.path().join("ggml-tiny.bin");
+        std::fs::write(part_path(&dest), MODEL).unwrap();
+
+        download(&reqwest::Client::new(), &format!("{}/ggml-tiny.bin", server.url()), &dest, &sha256(MODEL), |_, _| {}).await.unwrap();
+
+        assert_eq!(std::fs::read(&dest).unwrap(), MODEL);
+    }
+
+    #[tokio::test]
+    async fn checksum_mismatch_discards_the_download() {
+        let mut server = mockito::Server::new_async().await;
+        server.mock("GET", "/ggml-tiny.bin")
+            .with_body(MODEL)
+            .create_async()
+            .await;
+        let dir = tempfile::tempdir().unwrap();
+        let dest = dir.path().join("ggml-tiny.bin");
+
+        let error = download(&reqwest::Client::new(), &format!("{}/ggml-tiny.bin", server.url()), &dest, &sha256(b"other"), |_, _| {})
+            .await
+            .unwrap_err();
+
+        assert!(error.starts_with("Checksum mismatch"), "{}", error);
+        assert!(!dest.exists());
+        assert!(!part_path(&dest).exists());
+    }
+
+    #[tokio::test]
+    async fn http_errors_are_reported() {
+        let mut server = mockito::Server::new_async().await;
+        server.mock("GET", "/ggml-tiny.bin")
+            .with_status(404)
+            .create_async()
+            .await;
+        let dir = tempfile::tempdir().unwrap();
+        let dest = dir.path().join("ggml-tiny.bin");
+
+        let error = download(&reqwest::Client::new(), &format!("{}/ggml-tiny.bin", server.url()), &dest, &sha256(MODEL), |_, _| {})
+            .await
+            .unwrap_err();
+
+        assert!(error.contains("404"), "{}", error);
+        assert!(!dest.exists());
+    }
+
+    #[test]
+    fn tinydiarize_models_come_from_their_own_repository() {
+        assert_eq!(default_url("ggml-small.bin"), "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-small.bin");
+        assert_eq!(
+            default_url("ggml-small.en-tdrz.bin"),
+            "https://huggingface.co/akashmjn/tinydiarize-whisper.cpp/resolve/main/ggml-small.en-tdrz.bin"
+        );
+    }
+
+    #[test]
+    fn model_names_cannot_leave_the_models_directory() {
+        assert!(validate_model_name("ggml-small.en-tdrz.bin").is_ok());
+        assert!(validate_model_name("../ggml-small.bin").is_err());
+        assert!(validate_model_name("ggml-..bin").is_err());
+        assert!(validate_model_name("model.bin").is_err());
+        assert!(!is_multilingual("ggml-small.en-tdrz.bin"));
+        assert!(is_multilingual("ggml-large-v3.bin"));
+    }
+}
@@ -0,0 +1,315 @@
+// Sliding-window state for real-time transcription.
+//
+// Each window is the new chunk plus the audio of the previous window's last
+// segment. That segment is only provisional: it was cut off by the chunk
+// boundary or lacked context, so it is transcribed again with the next chunk
+// and replaced. Everything before it is committed and never changes.
+//
+// Committed text is passed to Whisper as the initial prompt, and words a new
+// window repeats from the end of the committed text are dropped.
+
+use crate::{TranscriptionResult, TranscriptionSegment, TranscriptionWord};
+
+const SAMPLE_RATE: usize = 16000;
+const MAX_CARRY_SAMPLES: usize = SAMPLE_RATE * 5; // Commit longer segments instead of redoing them
+const PROMPT_WORDS: usize = 40; // Whisper's prompt holds ~224 tokens
+const MAX_OVERLAP_WORDS: usize = 8;
+const MIN_OVERLAP_WORDS: usize = 2; // A single repeated word is too often genuine
+
+/// What one window changed in the real-time transcript.
+#[derive(Debug, Default)]
+pub struct WindowUpdate {
+    /// Segments that are final from now on
+    pub committed: Vec<TranscriptionSegment>,
+    /// Tentative tail, replacing the previous update's
+    pub provisional: Vec<TranscriptionSegment>,
+    /// Recording time covered by the window, in seconds
+    pub start: f32,
+    pub end: f32,
+    pub language: Option<String>,
+    pub language_probability: Option<f32>,
+}
+
+#[derive(Default)]
+pub struct SlidingWindow {
+    carry: Vec<f32>,
+    carry_start: usize, // Sample position of `carry` in the recording
+    provisional: Vec<TranscriptionSegment>,
+    committed_words: Vec<String>, // Tail of the committed text, for the prompt and de-duplication
+}
+
+impl SlidingWindow {
+    /// Transcribe `chunk`, which starts `offset` samples into the recording.
+    /// `transcribe` gets the window audio and the prompt; its times are relative to the window.
+    pub fn push<F>(&mut self, offset: usize, chunk: &[f32], transcribe: F) -> Result<WindowUpdate, String>
+    where
+        F: FnOnce(&[f32], Option<&str>) -> Result<TranscriptionResult, String>,
+    {
+        // Audio was skipped since the last window, so the provisional tail can't be redone
+        let contiguous = !self.carry.is_empty() && self.carry_start + self.carry.len() == offset;
+
+        let (window_start, window) = if contiguous {
+            let mut window = self.carry.clone();
+            window.extend_from_slice(chunk);
+            (self.carry_start, window)
+        } else {
+            (offset, chunk.to_vec())
+        };
+
+        let prompt = self.committed_words.join(" ");
+        let result = transcribe(&window, (!prompt.is_empty()).then_some(prompt.as_str()))?;
+
+        let mut committed = Vec::new();
+        if !contiguous {
+            committed = std::mem::take(&mut self.provisional);
+            self.remember(&committed);
+        }
+        self.provisional.clear();
+        self.carry.clear();
+
+        let window_offset = window_start as f32 / SAMPLE_RATE as f32;
+        let mut segments: Vec<TranscriptionSegment> = result.segments.into_iter()
+            .map(|segment| TranscriptionSegment {
+                start: segment.start + window_offset,
+                end: segment.end + window_offset,
+                words: segment.words.into_iter()
+                    .map(|word| TranscriptionWord {
+                        start: word.start + window_offset,
+                        end: word.end + window_offset,
+                        ..word
+                    })
+                    .collect(),
+                ..segment
+            })
+            .collect();
+        self.drop_repeated_words(&mut segments);
+
+        // Keep the last segment open if it is short enough to transcribe again
+        if let Some(last) = segments.last() {
+            let last_start = ((last.start - window_offset).max(0.0) * SAMPLE_RATE as f32) as usize;
+            if last_start < window.len() && window.len() - last_start <= MAX_CARRY_SAMPLES {
+                self.carry = window[last_start..].to_vec();
+                self.carry_start = window_start + last_start;
+                self.provisional = segments.split_off(segments.len() - 1);
+            }
+        }
+
+        self.remember(&segments);
+        committed.extend(segments);
+
+        Ok(WindowUpdate {
+            committed,
+            provisional: self.provisional.clone(),
+            start: window_offset,
+            end: (window_start + window.len()) as f32 / SAMPLE_RATE as f32,
+            language: result.language,
+            language_probability: result.language_probability,
+        })
+    }
+
+    /// Commit the provisional tail once no more audio is coming.
+    pub fn finish(&mut self) -> WindowUpdate {
+        let committed = std::mem::take(&mut self.provisional);
+        let start = self.carry_start as f32 / SAMPLE_RATE as f32;
+        let end = (self.carry_start + self.carry.len()) as f32 / SAMPLE_RATE as f32;
+        self.carry.clear();
+        self.remember(&committed);
+
+        WindowUpdate {
+            committed,
+            start,
+            end,
+            ..Default::default()
+        }
+    }
+
+    fn remember(&mut self, segments: &[TranscriptionSegment]) {
+        for segment in segments {
+            self.committed_words.extend(segment.text.split_whitespace().map(|word| word.replace('\0', "")));
+        }
+        let excess = self.committed_words.len().saturating_sub(PROMPT_WORDS);
+        self.committed_words.drain(..excess);
+    }
+
+    /// Remove the longest run of words at the start of `segments` that repeats
+    /// the end of the committed text. Segments left empty are dropped.
+    fn drop_repeated_words(&self, segments: &mut Vec<TranscriptionSegment>) {
+        let new_words: Vec<String> = segments.iter()
+            .flat_map(|segment| segment.text.split_whitespace())
+            .take(MAX_OVERLAP_WORDS)
+            .map(normalize_word)
+            .collect();
+        let committed: Vec<String> = self.committed_words.iter().map(|word| normalize_word(word)).collect();
+
+        let max = new_words.len().min(committed.len());
+        let overlap = (MIN_OVERLAP_WORDS..=max).rev()
+            .find(|&n| committed[committed.len() - n..] == new_words[..n])
+            .unwrap_or(0);
+
+        let mut remaining = overlap;
+        for segment in segments.iter_mut() {
+            if remaining == 0 {
+                break;
+            }
+            let words: Vec<&str> = segment.text.split_whitespace().collect();
+            let dropped = remaining.min(words.len());
+            segment.text = words[dropped..].join(" ");
+            segment.words.drain(..dropped.min(segment.words.len()));
+            remaining -= dropped;
+        }
+        segments.retain(|segment| !segment.text.is_empty());
+    }
+}
+
+fn normalize_word(word: &str) -> String {
+    word.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(start: f32, end: f32, text: &str) -> TranscriptionSegment {
+        TranscriptionSegment {
+            start,
+            end,
+            text: text.to_string(),
+            speaker: None,
+            source: None,
+            speaker_turn_next: false,
+            confidence: None,
+            words: text.split_whitespace()
+                .map(|word| TranscriptionWord { start, end, text: word.to_string(), probability: 1.0 })
+                .collect(),
+        }
+    }
+
+    fn result(segments: Vec<TranscriptionSegment>) -> Result<TranscriptionResult, String> {
+        Ok(TranscriptionResult {
+            full_text: segments.iter().map(|segment| segment.text.as_str()).collect::<Vec<_>>().join(" "),
+            segments,
+            language: Some("en".to_string()),
+            language_probability: None,
+        })
+    }
+
+    fn texts(segments: &[TranscriptionSegment]) -> Vec<&str> {
+        segments.iter().map(|segment| segment.text.as_str()).collect()
+    }
+
+    fn seconds(seconds: f32) -> usize {
+        (seconds * SAMPLE_RATE as f32) as usize
+    }
+
+    #[test]
+    fn last_segment_is_redone_with_the_next_chunk() {
+        let mut window = SlidingWindow::default();
+
+        let first = window.push(0, &vec![0.0; seconds(3.0)], |audio, prompt| {
+            assert_eq!(audio.len(), seconds(3.0));
+            assert_eq!(prompt, None);
+            result(vec![segment(0.0, 1.5, "Good morning everyone."), segment(1.5, 3.0, "Let's get")])
+        }).unwrap();
+
+        assert_eq!(texts(&first.committed), ["Good morning everyone."]);
+        assert_eq!(texts(&first.provisional), ["Let's get"]);
+        assert_eq!((first.start, first.end), (0.0, 3.0));
+
+        // The next window starts where the provisional segment did
+        let second = window.push(seconds(3.0), &vec![0.0; seconds(3.0)], |audio, prompt| {
+            assert_eq!(audio.len(), seconds(4.5));
+            assert_eq!(prompt, Some("Good morning everyone."));
+            result(vec![segment(0.0, 2.0, "Let's get started."), segment(2.0, 4.5, "First item")])
+        }).unwrap();
+
+        assert_eq!(texts(&second.committed), ["Let's get started."]);
+        assert_eq!((second.committed[0].start, second.committed[0].end), (1.5, 3.5));
+        assert_eq!(second.committed[0].words[0].start, 1.5);
+        assert_eq!(texts(&second.provisional), ["First item"]);
+        assert_eq!((second.start, second.end), (1.5, 6.0));
+
+        let last = window.finish();
+        assert_eq!(texts(&last.committed), ["First item"]);
+        assert_eq!((last.start, last.end), (3.5, 6.0));
+        assert!(window.finish().committed.is_empty());
+    }
+
+    #[test]
+    fn a_gap_commits_the_provisional_segment() {
+        let mut window = SlidingWindow::default();
+        window.push(0, &vec![0.0; seconds(3.0)], |_, _| {
+            result(vec![segment(0.0, 2.0, "Any questions?")])
+        }).unwrap();
+
+        // A silent chunk was skipped, so this chunk doesn't continue the last one
+        let update = window.push(seconds(6.0), &vec![0.0; seconds(3.0)], |audio, _| {
+            assert_eq!(audio.len(), seconds(3.0));
+            result(vec![segment(0.0, 3.0, "Yes, about the budget.")])
+        }).unwrap();
+
+        assert_eq!(texts(&update.committed), ["Any questions?"]);
+        assert_eq!(texts(&update.provisional), ["Yes, about the budget."]);
+        assert_eq!(update.provisional[0].start, 6.0);
+    }
+
+    #[test]
+    fn long_segments_are_committed_right_away() {
+        let mut window = SlidingWindow::default();
+
+        let update = window.push(0, &vec![0.0; seconds(10.0)], |_, _| {
+            result(vec![segment(0.0, 2.0, "Short one."), segment(2.0, 10.0, "A long monologue about the roadmap")])
+        }).unwrap();
+
+        assert_eq!(texts(&update.committed), ["Short one.", "A long monologue about the roadmap"]);
+        assert!(update.provisional.is_empty());
+    }
+
+    #[test]
+    fn words_repeated_from_the_committed_text_are_dropped() {
+        let mut window = SlidingWindow::default();
+        window.remember(&[segment(0.0, 2.0, "We should ship it on Friday.")]);
+
+        let mut segments = vec![segment(0.0, 1.0, "on friday"), segment(1.0, 2.0, "Friday, and then review it.")];
+        window.drop_repeated_words(&mut segments);
+
+        // The first segment only repeated committed words and is gone
+        assert_eq!(texts(&segments), ["Friday, and then review it."]);
+    }
+
+    #[test]
+    fn overlap_spans_segments_and_trims_their_words() {
+        let mut window = SlidingWindow::default();
+        window.remember(&[segment(0.0, 2.0, "the budget for next year")]);
+
+        let mut segments = vec![segment(0.0, 1.0, "next"), segment(1.0, 3.0, "year is still open")];
+        window.drop_repeated_words(&mut segments);
+
+        assert_eq!(texts(&segments), ["is still open"]);
+        assert_eq!(segments[0].words.len(), 3);
+        assert_eq!(segments[0].words[0].text, "is");
+    }
+
+    #[test]
+    fn a_single_repeated_word_is_kept() {
+        let mut window = SlidingWindow::default();
+        window.remember(&[segment(0.0, 1.0, "That works.")]);
+
+        let mut segments = vec![segment(0.0, 1.0, "Works for me too.")];
+        window.drop_repeated_words(&mut segments);
+
+        assert_eq!(texts(&segments), ["Works for me too."]);
+    }
+
+    #[test]
+    fn prompt_keeps_only_the_latest_words() {
+        let mut window = SlidingWindow::default();
+        let text: Vec<String> = (0..PROMPT_WORDS + 10).map(|i| format!("w{}", i)).collect();
+        window.remember(&[segment(0.0, 10.0, &text.join(" "))]);
+
+        assert_eq!(window.committed_words.len(), PROMPT_WORDS);
+        assert_eq!(window.committed_words[0], "w10");
+    }
+}
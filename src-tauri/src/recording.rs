@@ -0,0 +1,256 @@
+// Recording files: the mix and optional per-source tracks are written to disk
+// as audio arrives, so a crash only loses the last few seconds.
+//
+// hound rewrites the WAV header on `flush()`, which we do every few seconds.
+// Anything written after the last flush is still on disk; `repair_wav` fixes
+// the header sizes of such files at the next startup.
+
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+pub const SAMPLE_RATE: u32 = 16000; // Whisper expects 16kHz
+const FLUSH_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Suffixes of the files written next to `recording_<meeting id>.wav`.
+pub const CHANNEL_SUFFIXES: [&str; 3] = ["stereo", "mic", "system"];
+
+pub const CHANNELS_SETTING: &str = "recording_channels";
+
+/// How the microphone and system audio are kept apart besides the mono mix.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RecordingChannels {
+    /// Only the mix
+    #[default]
+    Mix,
+    /// Also a stereo WAV with the microphone left and system audio right
+    Stereo,
+    /// Also one WAV per source
+    Stems,
+}
+
+type Writer = hound::WavWriter<BufWriter<File>>;
+
+enum TrackWriters {
+    None,
+    Stereo(Writer),
+    Stems { mic: Writer, system: Writer },
+}
+
+/// Open WAV files of a recording in progress.
+pub struct RecordingWriter {
+    mix: Writer,
+    tracks: TrackWriters,
+    channel_files: Vec<PathBuf>,
+    last_flush: Instant,
+}
+
+impl RecordingWriter {
+    pub fn create(path: &Path, channels: RecordingChannels) -> Result<Self, String> {
+        let mix = create_writer(path, 1)?;
+        let mut channel_files = Vec::new();
+
+        let tracks = match channels {
+            RecordingChannels::Mix => TrackWriters::None,
+            RecordingChannels::Stereo => {
+                let stereo_path = channel_file_path(path, "stereo");
+                let writer = create_writer(&stereo_path, 2)?;
+                channel_files.push(stereo_path);
+                TrackWriters::Stereo(writer)
+            }
+            RecordingChannels::Stems => {
+                let mic_path = channel_file_path(path, "mic");
+                let system_path = channel_file_path(path, "system");
+                let mic = create_writer(&mic_path, 1)?;
+                let system = create_writer(&system_path, 1)?;
+                channel_files.push(mic_path);
+                channel_files.push(system_path);
+                TrackWriters::Stems { mic, system }
+            }
+        };
+
+        Ok(Self {
+            mix,
+            tracks,
+            channel_files,
+            last_flush: Instant::now(),
+        })
+    }
+
+    /// Append one mixer tick. `mic` and `system` are gain-adjusted and padded
+    /// with silence to the length of `mixed` so every file stays aligned.
+    pub fn write(&mut self, mixed: &[f32], mic: &[f32], system: &[f32]) -> Result<(), String> {
+        write_frames(&mut self.mix, &[mixed], mixed.len())?;
+
+        match &mut self.tracks {
+            TrackWriters::None => {}
+            TrackWriters::Stereo(writer) => write_frames(writer, &[mic, system], mixed.len())?,
+            TrackWriters::Stems { mic: mic_writer, system: system_writer } => {
+                write_frames(mic_writer, &[mic], mixed.len())?;
+                write_frames(system_writer, &[system], mixed.len())?;
+            }
+        }
+
+        if self.last_flush.elapsed() >= FLUSH_INTERVAL {
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Make the files on disk valid up to the samples written so far.
+    pub fn flush(&mut self) -> Result<(), String> {
+        self.mix.flush().map_err(|e| format!("Failed to flush recording: {}", e))?;
+        match &mut self.tracks {
+            TrackWriters::None => {}
+            TrackWriters::Stereo(writer) => writer.flush()
+                .map_err(|e| format!("Failed to flush channel track: {}", e))?,
+            TrackWriters::Stems { mic, system } => {
+                mic.flush().map_err(|e| format!("Failed to flush channel track: {}", e))?;
+                system.flush().map_err(|e| format!("Failed to flush channel track: {}", e))?;
+            }
+        }
+        self.last_flush = Instant::now();
+        Ok(())
+    }
+
+    /// Samples written to the mix so far.
+    pub fn sample_count(&self) -> usize {
+        self.mix.len() as usize
+    }
+
+    /// Finalize every file and return the paths of the channel tracks.
+    pub fn finish(self) -> Result<Vec<PathBuf>, String> {
+        self.mix.finalize().map_err(|e| format!("Failed to finalize WAV file: {}", e))?;
+        match self.tracks {
+            TrackWriters::None => {}
+            TrackWriters::Stereo(writer) => writer.finalize()
+                .map_err(|e| format!("Failed to finalize channel track: {}", e))?,
+            TrackWriters::Stems { mic, system } => {
+                mic.finalize().map_err(|e| format!("Failed to finalize channel track: {}", e))?;
+                system.finalize().map_err(|e| format!("Failed to finalize channel track: {}", e))?;
+            }
+        }
+        Ok(self.channel_files)
+    }
+}
+
+fn create_writer(path: &Path, channels: u16) -> Result<Writer, String> {
+    let spec = hound::WavSpec {
+        channels,
+        sample_rate: SAMPLE_RATE,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+
+    hound::WavWriter::create(path, spec)
+        .map_err(|e| format!("Failed to create WAV file {}: {}", path.display(), e))
+}
+
+/// Write `frames` frames, interleaving one slice per channel (missing samples are silence).
+fn write_frames(writer: &mut Writer, channels: &[&[f32]], frames: usize) -> Result<(), String> {
+    for i in 0..frames {
+        for channel in channels {
+            let sample = channel.get(i).copied().unwrap_or(0.0).clamp(-1.0, 1.0);
+            writer.write_sample((sample * i16::MAX as f32) as i16)
+                .map_err(|e| format!("Failed to write sample: {}", e))?;
+        }
+    }
+    Ok(())
+}
+
+/// `recording_x.wav` -> `recording_x_{suffix}.wav`, and likewise for archived `.flac` recordings
+pub fn channel_file_path(audio_path: &Path, suffix: &str) -> PathBuf {
+    let stem = audio_path.file_stem().and_then(|s| s.to_str()).unwrap_or("recording");
+    let extension = audio_path.extension().and_then(|s| s.to_str()).unwrap_or("wav");
+    audio_path.with_file_name(format!("{}_{}.{}", stem, suffix, extension))
+}
+
+/// Mix files of recordings made by `start_recording`, with the meeting ID from their name.
+pub fn find_recordings(dir: &Path) -> Vec<(PathBuf, String)> {
+    let Ok(entries) = std::fs::read_dir(dir) else { return Vec::new() };
+
+    entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter_map(|path| {
+            if path.extension().and_then(|ext| ext.to_str()) != Some("wav") {
+                return None;
+            }
+            let stem = path.file_stem()?.to_str()?;
+            let meeting_id = stem.strip_prefix("recording_")?;
+            if CHANNEL_SUFFIXES.iter().any(|suffix| meeting_id.ends_with(&format!("_{}", suffix))) {
+                return None;
+            }
+            Some((path.clone(), meeting_id.to_string()))
+        })
+        .collect()
+}
+
+/// Fix the RIFF and data chunk sizes of a WAV file whose writer never finalized it.
+/// Returns whether the header was changed.
+pub fn repair_wav(path: &Path) -> Result<bool, String> {
+    let io_error = |e: std::io::Error| format!("Failed to repair {}: {}", path.display(), e);
+
+    let mut file = OpenOptions::new().read(true).write(true).open(path).map_err(io_error)?;
+    let file_len = file.metadata().map_err(io_error)?.len();
+
+    let mut riff = [0u8; 12];
+    file.read_exact(&mut riff).map_err(io_error)?;
+    if &riff[0..4] != b"RIFF" || &riff[8..12] != b"WAVE" {
+        return Err(format!("{} is not a WAV file", path.display()));
+    }
+
+    // Walk the chunks up to "data", picking up the frame size from "fmt "
+    let mut block_align = 0u64;
+    let mut pos = 12u64;
+    let data_start = loop {
+        if pos + 8 > file_len {
+            return Err(format!("{} has no audio data", path.display()));
+        }
+        let mut header = [0u8; 8];
+        file.seek(SeekFrom::Start(pos)).map_err(io_error)?;
+        file.read_exact(&mut header).map_err(io_error)?;
+        let size = u32::from_le_bytes([header[4], header[5], header[6], header[7]]) as u64;
+
+        if &header[0..4] == b"data" {
+            break pos + 8;
+        }
+        if &header[0..4] == b"fmt " {
+            let mut fmt = [0u8; 14];
+            file.read_exact(&mut fmt).map_err(io_error)?;
+            block_align = u16::from_le_bytes([fmt[12], fmt[13]]) as u64;
+        }
+        pos += 8 + size + (size & 1);
+    };
+
+    if block_align == 0 {
+        return Err(format!("{} has no format chunk", path.display()));
+    }
+
+    // Drop a partially written frame at the end
+    let data_len = ((file_len - data_start) / block_align * block_align).min(u32::MAX as u64 - data_start);
+    let riff_len = data_start + data_len - 8;
+
+    let mut sizes = [0u8; 4];
+    file.seek(SeekFrom::Start(4)).map_err(io_error)?;
+    file.read_exact(&mut sizes).map_err(io_error)?;
+    let stored_riff_len = u32::from_le_bytes(sizes) as u64;
+    file.seek(SeekFrom::Start(data_start - 4)).map_err(io_error)?;
+    file.read_exact(&mut sizes).map_err(io_error)?;
+    let stored_data_len = u32::from_le_bytes(sizes) as u64;
+
+    if stored_riff_len == riff_len && stored_data_len == data_len && file_len == data_start + data_len {
+        return Ok(false);
+    }
+
+    file.set_len(data_start + data_len).map_err(io_error)?;
+    file.seek(SeekFrom::Start(4)).map_err(io_error)?;
+    file.write_all(&(riff_len as u32).to_le_bytes()).map_err(io_error)?;
+    file.seek(SeekFrom::Start(data_start - 4)).map_err(io_error)?;
+    file.write_all(&(data_len as u32).to_le_bytes()).map_err(io_error)?;
+    file.sync_all().map_err(io_error)?;
+
+    Ok(true)
+}
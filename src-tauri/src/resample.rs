@@ -0,0 +1,281 @@
+// Band-limited sample rate conversion for capture and file loading.
+//
+// Each output sample is a windowed-sinc (Kaiser) interpolation of the input
+// around its position. When downsampling, the sinc is stretched so it also
+// acts as the anti-aliasing low-pass filter below the output Nyquist
+// frequency. The kernel is tabulated once and interpolated linearly, so any
+// pair of rates works, e.g. 44.1 kHz to 16 kHz.
+//
+// A `Resampler` keeps the input it still needs between calls, so audio fed in
+// callback-sized buffers comes out the same as if it had been resampled in one
+// piece, without clicks at buffer edges.
+
+const ZERO_CROSSINGS: usize = 24; // Kernel half-width in zero crossings of the sinc
+const TABLE_RESOLUTION: usize = 512; // Kernel points per zero crossing
+const KAISER_BETA: f64 = 9.0; // ~90 dB stopband
+const ROLLOFF: f64 = 0.92; // Passband edge as a fraction of the lower Nyquist frequency
+
+pub struct Resampler {
+    input_rate: u64,
+    output_rate: u64,
+    cutoff: f64,        // Low-pass cutoff, relative to the input Nyquist frequency
+    half_width: usize,  // Kernel half-width in input samples
+    table: Vec<f32>,
+    history: Vec<f32>,  // Input still needed, starting `half_width` samples before `position`
+    // Time of the next output sample: `position` samples of `history` plus
+    // `phase / output_rate` of one. Kept exact, so long streams don't drift.
+    position: usize,
+    phase: u64,
+}
+
+impl Resampler {
+    pub fn new(input_rate: u32, output_rate: u32) -> Self {
+        let output_rate = output_rate.max(1);
+        let step = input_rate as f64 / output_rate as f64;
+        let cutoff = ROLLOFF * (1.0 / step).min(1.0);
+        let half_width = (ZERO_CROSSINGS as f64 / cutoff).ceil() as usize;
+
+        let table = (0..=ZERO_CROSSINGS * TABLE_RESOLUTION + 1)
+            .map(|i| {
+                let x = i as f64 / TABLE_RESOLUTION as f64;
+                (sinc(x) * kaiser(x / ZERO_CROSSINGS as f64)) as f32
+            })
+            .collect();
+
+        Self {
+            input_rate: input_rate as u64,
+            output_rate: output_rate as u64,
+            cutoff,
+            half_width,
+            table,
+            history: vec![0.0; half_width],
+            position: half_width,
+            phase: 0,
+        }
+    }
+
+    fn is_passthrough(&self) -> bool {
+        self.input_rate == self.output_rate
+    }
+
+    fn advance(&mut self) {
+        self.phase += self.input_rate;
+        self.position += (self.phase / self.output_rate) as usize;
+        self.phase %= self.output_rate;
+    }
+
+    /// Resample the next piece of a stream. Output lags the input by the
+    /// kernel half-width; `flush` returns what is still held back.
+    pub fn process(&mut self, input: &[f32]) -> Vec<f32> {
+        if self.is_passthrough() {
+            return input.to_vec();
+        }
+
+        self.history.extend_from_slice(input);
+
+        let capacity = input.len() as u64 * self.output_rate / self.input_rate + 1;
+        let mut output = Vec::with_capacity(capacity as usize);
+        while self.position + self.half_width < self.history.len() {
+            output.push(self.interpolate());
+            self.advance();
+        }
+
+        // Keep only the input the next output samples still reach back to
+        let consumed = self.position.saturating_sub(self.half_width).min(self.history.len());
+        self.history.drain(..consumed);
+        self.position -= consumed;
+
+        output
+    }
+
+    /// Output for the end of the stream, then start over for a new one.
+    pub fn flush(&mut self) -> Vec<f32> {
+        if self.is_passthrough() {
+            return Vec::new();
+        }
+
+        let end = self.history.len();
+        self.history.resize(self.history.len() + self.half_width + 1, 0.0);
+
+        let mut output = Vec::new();
+        while self.position < end {
+            output.push(self.interpolate());
+            self.advance();
+        }
+
+        self.history = vec![0.0; self.half_width];
+        self.position = self.half_width;
+        self.phase = 0;
+        output
+    }
+
+    /// Output sample at the current position.
+    fn interpolate(&self) -> f32 {
+        let center = self.position;
+        let fraction = self.phase as f64 / self.output_rate as f64;
+        let scale = self.cutoff * TABLE_RESOLUTION as f64; // Table steps per input sample
+
+        // Walk outwards from `position`: first the samples at or before it, then those after
+        let mut sum = 0.0f32;
+        let mut table_position = fraction * scale;
+        for index in (center + 1 - self.half_width..=center).rev() {
+            match self.coefficient(table_position) {
+                Some(coefficient) => sum += self.history[index] * coefficient,
+                None => break,
+            }
+            table_position += scale;
+        }
+        let mut table_position = (1.0 - fraction) * scale;
+        for index in center + 1..=center + self.half_width {
+            match self.coefficient(table_position) {
+                Some(coefficient) => sum += self.history[index] * coefficient,
+                None => break,
+            }
+            table_position += scale;
+        }
+
+        sum * self.cutoff as f32
+    }
+
+    /// Kernel value `table_position` table steps from the center; None past its end.
+    fn coefficient(&self, table_position: f64) -> Option<f32> {
+        let i = table_position as usize;
+        let next = self.table.get(i + 1)?;
+        let weight = (table_position - i as f64) as f32;
+        Some(self.table[i] + (next - self.table[i]) * weight)
+    }
+}
+
+/// Resample a whole signal.
+pub fn resample(input: &[f32], input_rate: u32, output_rate: u32) -> Vec<f32> {
+    let mut resampler = Resampler::new(input_rate, output_rate);
+    let mut output = resampler.process(input);
+    output.extend(resampler.flush());
+    output
+}
+
+fn sinc(x: f64) -> f64 {
+    if x == 0.0 {
+        1.0
+    } else {
+        let px = std::f64::consts::PI * x;
+        px.sin() / px
+    }
+}
+
+/// Kaiser window at `x` in [0, 1] from the center.
+fn kaiser(x: f64) -> f64 {
+    bessel_i0(KAISER_BETA * (1.0 - x * x).max(0.0).sqrt()) / bessel_i0(KAISER_BETA)
+}
+
+/// Zeroth-order modified Bessel function of the first kind, by its power series.
+fn bessel_i0(x: f64) -> f64 {
+    let mut sum = 1.0;
+    let mut term = 1.0;
+    let half_x = x / 2.0;
+    for k in 1..50 {
+        term *= (half_x / k as f64) * (half_x / k as f64);
+        sum += term;
+        if term < sum * 1e-12 {
+            break;
+        }
+    }
+    sum
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sine(frequency: f64, rate: u32, len: usize) -> Vec<f32> {
+        (0..len)
+            .map(|i| (0.5 * (2.0 * std::f64::consts::PI * frequency * i as f64 / rate as f64).sin()) as f32)
+            .collect()
+    }
+
+    fn rms(signal: &[f32]) -> f64 {
+        (signal.iter().map(|&s| (s as f64).powi(2)).sum::<f64>() / signal.len() as f64).sqrt()
+    }
+
+    /// Level of `signal` relative to `reference`, in dB.
+    fn db(signal: &[f32], reference: &[f32]) -> f64 {
+        20.0 * (rms(signal) / rms(reference)).log10()
+    }
+
+    // Skips the edges, where the kernel reaches past the start and end of the input
+    fn steady(signal: &[f32]) -> &[f32] {
+        &signal[500..signal.len() - 500]
+    }
+
+    #[test]
+    fn tones_above_the_output_nyquist_frequency_are_filtered_out() {
+        for frequency in [8500.0, 9000.0, 10000.0, 12000.0, 20000.0] {
+            let input = sine(frequency, 48000, 48000);
+
+            let output = resample(&input, 48000, 16000);
+
+            assert_eq!(output.len(), 16000);
+            let alias = db(steady(&output), &input);
+            assert!(alias < -80.0, "{} Hz aliases at {:.1} dB", frequency, alias);
+        }
+    }
+
+    #[test]
+    fn tones_in_the_passband_come_through_clean() {
+        for (frequency, input_rate, output_rate) in [
+            (1000.0, 48000, 16000),
+            (6000.0, 48000, 16000),
+            (3000.0, 44100, 16000),
+            (1000.0, 16000, 48000),
+        ] {
+            let output = resample(&sine(frequency, input_rate, input_rate as usize), input_rate, output_rate);
+
+            // Output samples fall at the same times as the input, without delay
+            let expected = sine(frequency, output_rate, output.len());
+            let error: Vec<f32> = output.iter().zip(&expected).map(|(out, exp)| out - exp).collect();
+            let snr = -db(steady(&error), steady(&expected));
+            assert!(snr > 80.0, "{} Hz at {} -> {} Hz: SNR {:.1} dB", frequency, input_rate, output_rate, snr);
+        }
+    }
+
+    #[test]
+    fn chunked_input_gives_the_same_output_as_one_buffer() {
+        // Noise-like input from a fixed linear congruential generator
+        let mut seed = 12345u32;
+        let input: Vec<f32> = (0..20000)
+            .map(|_| {
+                seed = seed.wrapping_mul(1664525).wrapping_add(1013904223);
+                (seed >> 8) as f32 / (1u32 << 23) as f32 - 1.0
+            })
+            .collect();
+
+        for (input_rate, output_rate) in [(48000, 16000), (44100, 16000), (16000, 48000)] {
+            let whole = resample(&input, input_rate, output_rate);
+
+            // Callback-sized buffers of uneven lengths, including empty and single samples
+            let mut resampler = Resampler::new(input_rate, output_rate);
+            let mut chunked = Vec::new();
+            let mut rest = input.as_slice();
+            for size in [0, 1, 7, 480, 1024, 3, 441].iter().cycle() {
+                if rest.is_empty() {
+                    break;
+                }
+                let (chunk, remaining) = rest.split_at((*size).min(rest.len()));
+                chunked.extend(resampler.process(chunk));
+                rest = remaining;
+            }
+            chunked.extend(resampler.flush());
+
+            assert!(chunked == whole, "{} -> {} Hz differs when chunked", input_rate, output_rate);
+        }
+    }
+
+    #[test]
+    fn equal_rates_pass_through() {
+        let input = sine(440.0, 16000, 1000);
+        let mut resampler = Resampler::new(16000, 16000);
+
+        assert_eq!(resampler.process(&input), input);
+        assert!(resampler.flush().is_empty());
+    }
+}
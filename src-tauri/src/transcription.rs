@@ -0,0 +1,184 @@
+// Whisper decoding options and the named presets they are saved as.
+//
+// Built-in presets trade speed for accuracy. Presets saved by the user are
+// stored as one JSON object in the `transcription_presets` setting and replace
+// a built-in preset of the same name.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use whisper_rs::{FullParams, SamplingStrategy};
+
+pub const PRESETS_SETTING: &str = "transcription_presets";
+pub const ACTIVE_PRESET_SETTING: &str = "transcription_preset";
+pub const DEFAULT_PRESET: &str = "balanced";
+
+const BEAM_PATIENCE: f32 = -1.0; // whisper.cpp default, i.e. no early stopping
+const TEMPERATURE_INCREMENT: f32 = 0.2;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TranscriptionOptions {
+    /// Beam width; 1 decodes greedily
+    pub beam_size: u32,
+    /// Decoder threads; None uses every core
+    pub threads: Option<u32>,
+    /// Names and terms Whisper should expect, e.g. the meeting agenda
+    pub initial_prompt: Option<String>,
+    /// Probability of "no speech" above which a window is left empty
+    pub no_speech_threshold: f32,
+    /// Decode again at higher temperatures when the output looks like a hallucination
+    pub temperature_fallback: bool,
+    /// Keep out sound descriptions like "[music]" or "(applause)"
+    pub suppress_non_speech: bool,
+    /// Translate into English instead of transcribing
+    pub translate: bool,
+    /// Split segments longer than this many characters; 0 keeps Whisper's segments
+    pub max_segment_length: u32,
+    /// How files with more than one channel become mono
+    pub channel_mix: ChannelMix,
+}
+
+/// Which channels of a multichannel file are transcribed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChannelMix {
+    /// Average of all channels
+    #[default]
+    Average,
+    /// The loudest channel in each short frame, e.g. the microphone nearest to whoever speaks
+    Loudest,
+    /// One channel, counted from 0
+    Channel(u16),
+}
+
+impl Default for TranscriptionOptions {
+    fn default() -> Self {
+        Self {
+            beam_size: 1,
+            threads: None,
+            initial_prompt: None,
+            no_speech_threshold: 0.6,
+            temperature_fallback: true,
+            suppress_non_speech: false,
+            translate: false,
+            max_segment_length: 0,
+            channel_mix: ChannelMix::Average,
+        }
+    }
+}
+
+impl TranscriptionOptions {
+    pub fn thread_count(&self) -> i32 {
+        let threads = self.threads
+            .map(|threads| threads as usize)
+            .unwrap_or_else(|| std::thread::available_parallelism().map(|n| n.get()).unwrap_or(4));
+        threads.max(1) as i32
+    }
+
+    /// Whisper parameters for these options. Language, speaker turns and
+    /// printing are left to the caller. `carried_prompt` is text from earlier
+    /// audio and follows the configured prompt.
+    pub fn full_params<'a, 'b>(&self, carried_prompt: Option<&str>) -> FullParams<'a, 'b> {
+        let strategy = if self.beam_size > 1 {
+            SamplingStrategy::BeamSearch { beam_size: self.beam_size as i32, patience: BEAM_PATIENCE }
+        } else {
+            SamplingStrategy::Greedy { best_of: 1 }
+        };
+
+        let mut params = FullParams::new(strategy);
+        params.set_n_threads(self.thread_count());
+        params.set_translate(self.translate);
+        params.set_no_speech_thold(self.no_speech_threshold);
+        params.set_temperature_inc(if self.temperature_fallback { TEMPERATURE_INCREMENT } else { 0.0 });
+        params.set_suppress_non_speech_tokens(self.suppress_non_speech);
+
+        if self.max_segment_length > 0 {
+            // whisper.cpp needs token timestamps to split segments
+            params.set_token_timestamps(true);
+            params.set_split_on_word(true);
+            params.set_max_len(self.max_segment_length as i32);
+        }
+
+        let prompt = [self.initial_prompt.as_deref(), carried_prompt]
+            .into_iter()
+            .flatten()
+            .map(str::trim)
+            .filter(|text| !text.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ");
+        if !prompt.is_empty() {
+            // A NUL byte would make whisper-rs panic
+            params.set_initial_prompt(&prompt.replace('\0', ""));
+        }
+
+        params
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TranscriptionPreset {
+    pub name: String,
+    pub options: TranscriptionOptions,
+    pub builtin: bool,
+}
+
+/// Every preset and the name of the one used by default.
+#[derive(Debug, Clone, Serialize)]
+pub struct TranscriptionPresets {
+    pub active: String,
+    pub presets: Vec<TranscriptionPreset>,
+}
+
+fn builtin_presets() -> Vec<(&'static str, TranscriptionOptions)> {
+    vec![
+        ("fast", TranscriptionOptions {
+            temperature_fallback: false,
+            suppress_non_speech: true,
+            ..Default::default()
+        }),
+        (DEFAULT_PRESET, TranscriptionOptions::default()),
+        ("accurate", TranscriptionOptions {
+            beam_size: 5,
+            suppress_non_speech: true,
+            ..Default::default()
+        }),
+    ]
+}
+
+pub fn is_builtin(name: &str) -> bool {
+    builtin_presets().iter().any(|(builtin, _)| *builtin == name)
+}
+
+/// Presets saved in the `transcription_presets` setting; unreadable JSON counts as none.
+pub fn parse_saved(saved: Option<&str>) -> BTreeMap<String, TranscriptionOptions> {
+    saved
+        .and_then(|json| serde_json::from_str(json).ok())
+        .unwrap_or_default()
+}
+
+/// Built-in presets (with saved changes) first, then the user's own, by name.
+pub fn all_presets(saved: Option<&str>) -> Vec<TranscriptionPreset> {
+    let mut saved = parse_saved(saved);
+
+    let mut presets: Vec<TranscriptionPreset> = builtin_presets().into_iter()
+        .map(|(name, options)| TranscriptionPreset {
+            options: saved.remove(name).unwrap_or(options),
+            name: name.to_string(),
+            builtin: true,
+        })
+        .collect();
+
+    presets.extend(saved.into_iter().map(|(name, options)| TranscriptionPreset {
+        name,
+        options,
+        builtin: false,
+    }));
+
+    presets
+}
+
+pub fn find_preset(saved: Option<&str>, name: &str) -> Option<TranscriptionOptions> {
+    all_presets(saved).into_iter()
+        .find(|preset| preset.name == name)
+        .map(|preset| preset.options)
+}
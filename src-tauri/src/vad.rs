@@ -0,0 +1,317 @@
+// Energy-based voice activity detection for 16kHz mono audio.
+//
+// Frames whose energy is well above the estimated noise floor count as speech.
+// Used to cut real-time chunks at pauses instead of fixed windows, and to drop
+// long silences before Whisper sees a recording. `TimeMap` translates times in
+// trimmed audio back to the original recording.
+
+const SAMPLE_RATE: usize = 16000;
+const FRAME_SIZE: usize = 480; // 30ms
+const NOISE_PERCENTILE: f32 = 0.1;
+const SPEECH_ABOVE_NOISE_DB: f32 = 10.0;
+const MIN_SPEECH_DB: f32 = -50.0; // Never call anything quieter than this speech
+const MAX_NOISE_FLOOR_DB: f32 = -40.0; // In a window of nearly all speech, even the quietest frames are speech
+const MIN_SPEECH_FRAMES: usize = 8; // 240ms
+const MIN_SILENCE_FRAMES: usize = 10; // 300ms; shorter gaps are pauses inside a phrase
+const PADDING_FRAMES: usize = 7; // ~200ms kept around speech when trimming
+
+/// Per-frame speech decisions for `audio`.
+fn speech_frames(audio: &[f32]) -> Vec<bool> {
+    let levels: Vec<f32> = audio.chunks(FRAME_SIZE)
+        .map(|frame| {
+            let energy = frame.iter().map(|s| s * s).sum::<f32>() / frame.len() as f32;
+            10.0 * (energy + 1e-10).log10()
+        })
+        .collect();
+
+    if levels.is_empty() {
+        return Vec::new();
+    }
+
+    let mut sorted = levels.clone();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let noise_floor = sorted[((sorted.len() - 1) as f32 * NOISE_PERCENTILE) as usize].min(MAX_NOISE_FLOOR_DB);
+    let threshold = (noise_floor + SPEECH_ABOVE_NOISE_DB).max(MIN_SPEECH_DB);
+
+    let mut frames: Vec<bool> = levels.iter().map(|&level| level > threshold).collect();
+
+    // Fill short gaps so a phrase stays one span
+    let mut i = 0;
+    while i < frames.len() {
+        if frames[i] {
+            i += 1;
+            continue;
+        }
+        let gap_start = i;
+        while i < frames.len() && !frames[i] {
+            i += 1;
+        }
+        if gap_start > 0 && i < frames.len() && i - gap_start < MIN_SILENCE_FRAMES {
+            frames[gap_start..i].iter_mut().for_each(|frame| *frame = true);
+        }
+    }
+
+    frames
+}
+
+/// Speech spans as sample ranges, with short blips dropped.
+pub fn detect_speech(audio: &[f32]) -> Vec<(usize, usize)> {
+    let frames = speech_frames(audio);
+    let mut spans = Vec::new();
+
+    let mut i = 0;
+    while i < frames.len() {
+        if !frames[i] {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        while i < frames.len() && frames[i] {
+            i += 1;
+        }
+        if i - start >= MIN_SPEECH_FRAMES {
+            spans.push((start * FRAME_SIZE, (i * FRAME_SIZE).min(audio.len())));
+        }
+    }
+
+    spans
+}
+
+pub fn has_speech(audio: &[f32]) -> bool {
+    !detect_speech(audio).is_empty()
+}
+
+/// Where to end the next real-time chunk, or `None` to wait for more audio.
+///
+/// Cuts in the middle of the last pause between `min_samples` and `max_samples`.
+/// Without a pause, waits until `max_samples` and then cuts at the quietest frame.
+pub fn next_chunk_end(audio: &[f32], min_samples: usize, max_samples: usize) -> Option<usize> {
+    if audio.len() < min_samples {
+        return None;
+    }
+
+    let window = &audio[..audio.len().min(max_samples)];
+    let frames = speech_frames(window);
+    let min_frame = min_samples / FRAME_SIZE;
+
+    // Last silence run long enough to be a real pause, centred after min_samples
+    let mut cut = None;
+    let mut i = 0;
+    while i < frames.len() {
+        if frames[i] {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        while i < frames.len() && !frames[i] {
+            i += 1;
+        }
+        let middle = (start + i) / 2;
+        if i - start >= MIN_SILENCE_FRAMES && middle >= min_frame {
+            cut = Some(middle);
+        }
+    }
+
+    if let Some(frame) = cut {
+        return Some((frame * FRAME_SIZE).min(window.len()));
+    }
+
+    if audio.len() < max_samples {
+        return None;
+    }
+
+    // No pause in the whole window: cut at the quietest frame past min_samples
+    let quietest = window.chunks(FRAME_SIZE)
+        .enumerate()
+        .skip(min_frame)
+        .map(|(index, frame)| (index, frame.iter().map(|s| s * s).sum::<f32>() / frame.len() as f32))
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(index, _)| index * FRAME_SIZE)
+        .unwrap_or(max_samples);
+
+    Some(quietest.max(min_samples).min(max_samples))
+}
+
+/// A stretch of original audio kept in the trimmed audio.
+#[derive(Debug, Clone, Copy)]
+struct Piece {
+    trimmed_start: usize,
+    original_start: usize,
+    len: usize,
+}
+
+/// Maps times in trimmed audio back to the original recording.
+#[derive(Debug, Clone, Default)]
+pub struct TimeMap {
+    pieces: Vec<Piece>,
+}
+
+impl TimeMap {
+    fn locate(&self, seconds: f32, at_end: bool) -> f32 {
+        let sample = (seconds.max(0.0) * SAMPLE_RATE as f32) as usize;
+        // An end time on a piece boundary belongs to the piece before it
+        let index = self.pieces
+            .partition_point(|piece| if at_end { piece.trimmed_start < sample } else { piece.trimmed_start <= sample })
+            .saturating_sub(1);
+
+        match self.pieces.get(index) {
+            Some(piece) => {
+                let offset = sample.saturating_sub(piece.trimmed_start).min(piece.len);
+                (piece.original_start + offset) as f32 / SAMPLE_RATE as f32
+            }
+            None => seconds,
+        }
+    }
+
+    /// Original time of a segment start.
+    pub fn start_to_original(&self, seconds: f32) -> f32 {
+        self.locate(seconds, false)
+    }
+
+    /// Original time of a segment end.
+    pub fn end_to_original(&self, seconds: f32) -> f32 {
+        self.locate(seconds, true)
+    }
+}
+
+/// Remove silences, keeping a little padding around speech.
+/// Returns the audio unchanged (with an identity map) if no speech is found.
+pub fn trim_silence(audio: &[f32]) -> (Vec<f32>, TimeMap) {
+    let padding = PADDING_FRAMES * FRAME_SIZE;
+    let spans = detect_speech(audio);
+
+    if spans.is_empty() {
+        let map = TimeMap {
+            pieces: vec![Piece { trimmed_start: 0, original_start: 0, len: audio.len() }],
+        };
+        return (audio.to_vec(), map);
+    }
+
+    // Pad each span and merge the ones that now overlap
+    let mut ranges: Vec<(usize, usize)> = Vec::with_capacity(spans.len());
+    for (start, end) in spans {
+        let start = start.saturating_sub(padding);
+        let end = (end + padding).min(audio.len());
+        match ranges.last_mut() {
+            Some(last) if start <= last.1 => last.1 = last.1.max(end),
+            _ => ranges.push((start, end)),
+        }
+    }
+
+    let mut trimmed = Vec::with_capacity(ranges.iter().map(|(start, end)| end - start).sum());
+    let mut pieces = Vec::with_capacity(ranges.len());
+    for (start, end) in ranges {
+        pieces.push(Piece {
+            trimmed_start: trimmed.len(),
+            original_start: start,
+            len: end - start,
+        });
+        trimmed.extend_from_slice(&audio[start..end]);
+    }
+
+    (trimmed, TimeMap { pieces })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `seconds` of a 220 Hz tone (speech) or of faint noise (silence).
+    fn audio(parts: &[(bool, f32)]) -> Vec<f32> {
+        let mut state = 22_695_477u32;
+        let mut samples = Vec::new();
+        for &(speech, seconds) in parts {
+            for i in 0..(seconds * SAMPLE_RATE as f32) as usize {
+                state = state.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+                let noise = 0.001 * ((state >> 16) as f32 / 32768.0 - 1.0);
+                let tone = if speech {
+                    0.3 * (2.0 * std::f32::consts::PI * 220.0 * i as f32 / SAMPLE_RATE as f32).sin()
+                } else {
+                    0.0
+                };
+                samples.push(tone + noise);
+            }
+        }
+        samples
+    }
+
+    fn seconds(samples: usize) -> f32 {
+        samples as f32 / SAMPLE_RATE as f32
+    }
+
+    const MIN: usize = SAMPLE_RATE * 3;
+    const MAX: usize = SAMPLE_RATE * 10;
+
+    #[test]
+    fn waits_for_the_minimum_chunk_length() {
+        let speech = audio(&[(true, 1.0), (false, 1.0)]);
+        assert_eq!(next_chunk_end(&speech, MIN, MAX), None);
+    }
+
+    #[test]
+    fn cuts_in_the_middle_of_a_pause() {
+        let speech = audio(&[(true, 4.0), (false, 1.0), (true, 2.0)]);
+
+        let cut = seconds(next_chunk_end(&speech, MIN, MAX).unwrap());
+
+        assert!((cut - 4.5).abs() < 0.1, "cut at {}s", cut);
+    }
+
+    #[test]
+    fn cuts_at_the_last_pause() {
+        let speech = audio(&[(true, 3.5), (false, 0.6), (true, 2.0), (false, 0.6), (true, 1.0)]);
+
+        let cut = seconds(next_chunk_end(&speech, MIN, MAX).unwrap());
+
+        assert!((cut - 6.4).abs() < 0.1, "cut at {}s", cut);
+    }
+
+    #[test]
+    fn ignores_pauses_before_the_minimum() {
+        let speech = audio(&[(true, 1.0), (false, 1.0), (true, 4.0)]);
+        assert_eq!(next_chunk_end(&speech, MIN, MAX), None);
+    }
+
+    #[test]
+    fn short_gaps_are_not_pauses() {
+        let speech = audio(&[(true, 4.0), (false, 0.15), (true, 2.0)]);
+        assert_eq!(next_chunk_end(&speech, MIN, MAX), None);
+    }
+
+    #[test]
+    fn cuts_at_the_maximum_without_a_pause() {
+        let mut speech = audio(&[(true, 12.0)]);
+        // A dip too short to be a pause, in the frame at 7s
+        let dip = 7 * SAMPLE_RATE / FRAME_SIZE * FRAME_SIZE;
+        speech[dip..dip + FRAME_SIZE].iter_mut().for_each(|sample| *sample *= 0.1);
+
+        let cut = next_chunk_end(&speech, MIN, MAX).unwrap();
+
+        assert_eq!(cut, dip);
+        assert_eq!(next_chunk_end(&audio(&[(true, 12.0)]), MIN, MAX).map(|cut| (MIN..=MAX).contains(&cut)), Some(true));
+    }
+
+    #[test]
+    fn silence_has_no_speech() {
+        assert!(!has_speech(&audio(&[(false, 3.0)])));
+        assert!(has_speech(&audio(&[(false, 1.0), (true, 0.5), (false, 1.0)])));
+        // Blips shorter than MIN_SPEECH_FRAMES are dropped
+        assert!(!has_speech(&audio(&[(false, 1.0), (true, 0.1), (false, 1.0)])));
+    }
+
+    #[test]
+    fn trimmed_times_map_back_to_the_recording() {
+        let recording = audio(&[(false, 5.0), (true, 1.0), (false, 5.0), (true, 1.0), (false, 2.0)]);
+
+        let (trimmed, map) = trim_silence(&recording);
+
+        // Both phrases plus ~200ms of padding on each side
+        assert!((seconds(trimmed.len()) - 2.84).abs() < 0.1, "{}s", seconds(trimmed.len()));
+        let first_start = map.start_to_original(0.21);
+        assert!((first_start - 5.0).abs() < 0.05, "{}", first_start);
+        let second_start = map.start_to_original(1.42 + 0.21);
+        assert!((second_start - 11.0).abs() < 0.05, "{}", second_start);
+        let first_end = map.end_to_original(1.42);
+        assert!((first_end - 6.2).abs() < 0.05, "{}", first_end);
+    }
+}
@@ -0,0 +1,255 @@
+#!/usr/bin/env python3
+"""Writes the tiny audio files that decode.rs is tested against.
+
+No encoder is needed: WAV and FLAC (verbatim subframes) hold a 440 Hz sine,
+the lossy formats are built frame by frame and hold silence. Run from this
+directory; the files are committed, so this only matters when changing them.
+"""
+
+import math
+import struct
+import wave
+import zlib
+
+
+def sine(frames, rate, channels, bits, amplitude=0.5):
+    peak = (1 << (bits - 1)) - 1
+    return [[round(amplitude * peak * math.sin(2 * math.pi * 440 * (i / rate) + c))
+             for c in range(channels)] for i in range(frames)]
+
+
+class MsbBits:
+    def __init__(self):
+        self.bits = []
+
+    def write(self, value, count):
+        self.bits += [(value >> (count - 1 - i)) & 1 for i in range(count)]
+
+    def bytes(self):
+        bits = self.bits + [0] * (-len(self.bits) % 8)
+        return bytes(int("".join(map(str, bits[i:i + 8])), 2) for i in range(0, len(bits), 8))
+
+
+class LsbBits(MsbBits):
+    def write(self, value, count):
+        self.bits += [(value >> i) & 1 for i in range(count)]
+
+    def bytes(self):
+        bits = self.bits + [0] * (-len(self.bits) % 8)
+        return bytes(sum(bit << i for i, bit in enumerate(bits[n:n + 8])) for n in range(0, len(bits), 8))
+
+
+def crc(data, poly, width):
+    top, mask, value = 1 << (width - 1), (1 << width) - 1, 0
+    for byte in data:
+        value ^= byte << (width - 8)
+        for _ in range(8):
+            value = ((value << 1) ^ poly) & mask if value & top else (value << 1) & mask
+    return value
+
+
+# WAV: 16 kHz mono 16-bit, 0.25 s
+def write_wav():
+    with wave.open("sine-16k-mono.wav", "wb") as wav:
+        wav.setnchannels(1)
+        wav.setsampwidth(2)
+        wav.setframerate(16000)
+        wav.writeframes(b"".join(struct.pack("<h", s[0]) for s in sine(4000, 16000, 1, 16)))
+
+
+# FLAC: 22.05 kHz stereo 16-bit, 4410 frames in a 4096 block and a short last one
+def write_flac():
+    rate, channels, bits, frames, block = 22050, 2, 16, 4410, 4096
+    samples = sine(frames, rate, channels, bits)
+
+    info = MsbBits()
+    info.write(block, 16)
+    info.write(block, 16)
+    info.write(0, 24)
+    info.write(0, 24)
+    info.write(rate, 20)
+    info.write(channels - 1, 3)
+    info.write(bits - 1, 5)
+    info.write(frames, 36)
+    out = b"fLaC" + bytes([0x80, 0, 0, 34]) + info.bytes() + bytes(16)
+
+    for number, start in enumerate(range(0, frames, block)):
+        chunk = samples[start:start + block]
+        header = MsbBits()
+        header.write(0xFFF8, 16)
+        header.write(0b1100 if len(chunk) == 4096 else 0b0111, 4)
+        header.write(0b0110, 4)  # 22.05 kHz
+        header.write(channels - 1, 4)  # independent channels
+        header.write(0b100, 3)  # 16-bit
+        header.write(0, 1)
+        header.write(number, 8)  # frame numbers below 128 are one byte
+        if len(chunk) != 4096:
+            header.write(len(chunk) - 1, 16)
+        frame = header.bytes()
+        frame += bytes([crc(frame, 0x07, 8)])
+        for channel in range(channels):
+            frame += bytes([0x02])  # verbatim subframe
+            frame += b"".join(struct.pack(">h", s[channel]) for s in chunk)
+        frame += struct.pack(">H", crc(frame, 0x8005, 16))
+        out += frame
+
+    open("sine-22k-stereo.flac", "wb").write(out)
+
+
+# MP3: MPEG-1 layer III, 44.1 kHz stereo, 64 kbit/s, 10 silent frames
+def write_mp3():
+    frame = bytes([0xFF, 0xFB, 0x50, 0x00]) + bytes(144 * 64000 // 44100 - 4)
+    open("silence-44k-stereo.mp3", "wb").write(frame * 10)
+
+
+def ogg_page(packets, serial, sequence, granule, flags):
+    lacing = b""
+    for packet in packets:
+        lacing += bytes([255] * (len(packet) // 255) + [len(packet) % 255])
+    page = (b"OggS" + bytes([0, flags]) + struct.pack("<qII", granule, serial, sequence)
+            + bytes(4) + bytes([len(lacing)]) + lacing + b"".join(packets))
+    checksum = crc(page, 0x04C11DB7, 32)
+    return page[:22] + struct.pack("<I", checksum) + page[26:]
+
+
+# Ogg Vorbis: 48 kHz mono, 256-sample blocks whose floors are all unused
+def write_vorbis():
+    rate, packets = 48000, 41
+    ident = (b"\x01vorbis" + struct.pack("<IBIiii", 0, 1, rate, 0, 64000, 0)
+             + bytes([0xB8, 1]))  # blocks of 256 and 2048 samples
+    vendor = b"fixture"
+    comment = b"\x03vorbis" + struct.pack("<I", len(vendor)) + vendor + struct.pack("<I", 0) + b"\x01"
+
+    setup = LsbBits()
+    for byte in b"\x05vorbis":
+        setup.write(byte, 8)
+    setup.write(0, 8)  # one codebook
+    setup.write(0x564342, 24)
+    setup.write(1, 16)  # dimensions
+    setup.write(2, 24)  # entries
+    setup.write(0, 1)  # unordered
+    setup.write(0, 1)  # not sparse
+    setup.write(0, 5)
+    setup.write(0, 5)  # both entries one bit long
+    setup.write(0, 4)  # no lookup
+    setup.write(0, 6)
+    setup.write(0, 16)  # time domain placeholder
+    setup.write(0, 6)
+    setup.write(1, 16)  # one floor of type 1
+    setup.write(0, 5)  # no partitions
+    setup.write(0, 2)  # multiplier 1
+    setup.write(4, 4)  # range bits
+    setup.write(0, 6)
+    setup.write(0, 16)  # one residue of type 0
+    setup.write(0, 24)
+    setup.write(0, 24)
+    setup.write(0, 24)
+    setup.write(0, 6)  # one classification
+    setup.write(0, 8)  # classbook
+    setup.write(0, 3)
+    setup.write(0, 1)  # no cascade
+    setup.write(0, 6)
+    setup.write(0, 16)  # one mapping of type 0
+    setup.write(0, 1)  # one submap
+    setup.write(0, 1)  # no coupling
+    setup.write(0, 2)
+    setup.write(0, 8)
+    setup.write(0, 8)  # floor 0
+    setup.write(0, 8)  # residue 0
+    setup.write(0, 6)  # one mode
+    setup.write(0, 1)  # short blocks
+    setup.write(0, 16)
+    setup.write(0, 16)
+    setup.write(0, 8)  # mapping 0
+    setup.write(1, 1)  # framing
+
+    # Each packet after the first completes 128 samples
+    audio = [b"\x00"] * packets
+    out = (ogg_page([ident], 1, 0, 0, 0x02)
+           + ogg_page([comment, setup.bytes()], 1, 1, 0, 0)
+           + ogg_page(audio, 1, 2, (packets - 1) * 128, 0x04))
+    open("silence-48k-mono.ogg", "wb").write(out)
+
+
+# Ogg Opus: headers and one silent packet, for the unsupported-codec error
+def write_opus():
+    head = b"OpusHead" + struct.pack("<BBHIhB", 1, 2, 312, 48000, 0, 0)
+    vendor = b"fixture"
+    tags = b"OpusTags" + struct.pack("<I", len(vendor)) + vendor + struct.pack("<I", 0)
+    out = (ogg_page([head], 2, 0, 0, 0x02)
+           + ogg_page([tags], 2, 1, 0, 0)
+           + ogg_page([bytes([0xF8, 0xFF, 0xFE])], 2, 2, 960, 0x04))
+    open("silence-48k-stereo.opus", "wb").write(out)
+
+
+def box(kind, payload):
+    return struct.pack(">I", len(payload) + 8) + kind + payload
+
+
+def descriptor(tag, payload):
+    return bytes([tag, len(payload)]) + payload
+
+
+# M4A: AAC-LC, 44.1 kHz mono, 10 silent frames of 1024 samples
+def write_m4a():
+    rate, frames = 44100, 10
+    duration = frames * 1024
+
+    element = MsbBits()
+    element.write(0, 3)  # single channel element
+    element.write(0, 4)
+    element.write(100, 8)  # global gain
+    element.write(0, 1)
+    element.write(0, 2)  # only long window
+    element.write(0, 1)
+    element.write(0, 6)  # no scale factor bands
+    element.write(0, 1)
+    element.write(0, 3)  # no pulse, TNS or gain control data
+    element.write(7, 3)  # end
+    frame = element.bytes()
+
+    config = MsbBits()
+    config.write(2, 5)  # AAC-LC
+    config.write(4, 4)  # 44.1 kHz
+    config.write(1, 4)  # mono
+    config.write(0, 3)
+    esds = box(b"esds", bytes(4) + descriptor(3, struct.pack(">HB", 1, 0)
+               + descriptor(4, bytes([0x40, 0x15]) + bytes(3) + struct.pack(">II", 64000, 64000)
+                            + descriptor(5, config.bytes()))
+               + descriptor(6, b"\x02")))
+    mp4a = box(b"mp4a", bytes(6) + struct.pack(">HHHIHHHHI", 1, 0, 0, 0, 1, 16, 0, 0, rate << 16) + esds)
+
+    matrix = struct.pack(">9I", 0x10000, 0, 0, 0, 0x10000, 0, 0, 0, 0x40000000)
+    stbl = box(b"stbl",
+               box(b"stsd", struct.pack(">II", 0, 1) + mp4a)
+               + box(b"stts", struct.pack(">IIII", 0, 1, frames, 1024))
+               + box(b"stsc", struct.pack(">IIIII", 0, 1, 1, frames, 1))
+               + box(b"stsz", struct.pack(">III", 0, 0, frames) + struct.pack(">I", len(frame)) * frames)
+               + box(b"stco", struct.pack(">II", 0, 1) + b"OFFS"))
+    minf = box(b"minf",
+               box(b"smhd", bytes(8))
+               + box(b"dinf", box(b"dref", struct.pack(">II", 0, 1) + box(b"url ", struct.pack(">I", 1))))
+               + stbl)
+    mdia = box(b"mdia",
+               box(b"mdhd", struct.pack(">IIIIIHH", 0, 0, 0, rate, duration, 0x55C4, 0))
+               + box(b"hdlr", struct.pack(">II", 0, 0) + b"soun" + bytes(12) + b"SoundHandler\0")
+               + minf)
+    tkhd = box(b"tkhd", struct.pack(">IIIIII", 7, 0, 0, 1, 0, duration) + bytes(8)
+               + struct.pack(">HHHH", 0, 0, 0x100, 0) + matrix + struct.pack(">II", 0, 0))
+    mvhd = box(b"mvhd", struct.pack(">IIIIIIH", 0, 0, 0, rate, duration, 0x10000, 0x100) + bytes(10)
+               + matrix + bytes(24) + struct.pack(">I", 2))
+    moov = box(b"moov", mvhd + box(b"trak", tkhd + mdia))
+    ftyp = box(b"ftyp", b"M4A " + struct.pack(">I", 0) + b"M4A isommp42")
+
+    offset = len(ftyp) + len(moov) + 8
+    moov = moov.replace(b"OFFS", struct.pack(">I", offset))
+    open("silence-44k-mono.m4a", "wb").write(ftyp + moov + box(b"mdat", frame * frames))
+
+
+if __name__ == "__main__":
+    write_wav()
+    write_flac()
+    write_mp3()
+    write_vorbis()
+    write_opus()
+    write_m4a()
@@ -0,0 +1,57 @@
+-- A database as written before schema versioning (user_version 0): the
+-- tables created by the first release, with two meetings.
+
+CREATE TABLE meetings (
+    id TEXT PRIMARY KEY,
+    title TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    duration_seconds INTEGER,
+    audio_file_path TEXT,
+    transcript TEXT,
+    meeting_minutes TEXT,
+    language TEXT,
+    ai_provider TEXT
+);
+
+CREATE TABLE meeting_segments (
+    id TEXT PRIMARY KEY,
+    meeting_id TEXT NOT NULL,
+    start_time REAL NOT NULL,
+    end_time REAL NOT NULL,
+    text TEXT NOT NULL,
+    confidence REAL,
+    FOREIGN KEY(meeting_id) REFERENCES meetings(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_meetings_created_at ON meetings(created_at);
+CREATE INDEX idx_segments_meeting_id ON meeting_segments(meeting_id);
+
+INSERT INTO meetings VALUES (
+    'meeting-budget', 'Quarterly budget review',
+    '2024-03-01T10:00:00+01:00', '2024-03-01T11:00:00+01:00', 3600,
+    '/recordings/recording_meeting-budget.wav',
+    'We agreed to cut the travel budget by ten percent.',
+    '## Summary
+The team reviewed the quarterly budget.
+
+---
+KEY_TOPICS: Budget, Travel
+SENTIMENT: Neutral
+ENERGY: Medium',
+    'en', 'openai'
+);
+
+INSERT INTO meetings VALUES (
+    'meeting-hiring', 'Hiring sync',
+    '2024-03-02T09:00:00+01:00', '2024-03-02T09:30:00+01:00', 1800,
+    NULL,
+    'Two backend engineers start in April.',
+    '## Summary
+Onboarding plans for new engineers.',
+    'en', 'ollama'
+);
+
+INSERT INTO meeting_segments VALUES ('segment-1', 'meeting-budget', 0.0, 4.5, 'We agreed to cut the travel budget', 0.92);
+INSERT INTO meeting_segments VALUES ('segment-2', 'meeting-budget', 4.5, 6.0, 'by ten percent.', 0.88);
+INSERT INTO meeting_segments VALUES ('segment-3', 'meeting-hiring', 0.0, 3.2, 'Two backend engineers start in April.', NULL);
@@ -4,10 +4,15 @@ import ReactMarkdown from 'react-markdown';
 import remarkGfm from 'remark-gfm';
 import TranscriptionSegments, { TranscriptionResult } from './components/TranscriptionSegments';
 import MeetingsManager from "./components/MeetingsManager";
+import ModelManager from "./components/ModelManager";
+import GlossaryManager from "./components/GlossaryManager";
+import RecordingStorage from "./components/RecordingStorage";
+import TranscriptionPresets, { TranscriptionOptions, loadActiveTranscriptionOptions } from "./components/TranscriptionPresets";
 import { 
   Mic, 
   Square, 
   Play, 
+  Pause,
   FileText, 
   Bot, 
   Sparkles, 
@@ -29,6 +34,11 @@ import {
 } from 'lucide-react';
 import "./App.css";
 
+interface AudioProcessing {
+  auto_gain: boolean;
+  normalize_loudness: boolean;
+}
+
 function App() {
   // Supported languages for Whisper transcription
   const supportedLanguages = [
@@ -58,6 +68,7 @@ function App() {
   ];
 
   const [isRecording, setIsRecording] = useState(false);
+  const [isPaused, setIsPaused] = useState(false);
   const [recordingTime, setRecordingTime] = useState(0);
   const [transcript, setTranscript] = useState("");
   const [transcriptionResult, setTranscriptionResult] = useState<TranscriptionResult | null>(null);
@@ -66,8 +77,10 @@ function App() {
   const [transcriptionProgress, setTranscriptionProgress] = useState(0);
   const [lastRecordingPath, setLastRecordingPath] = useState("");
   const [isRealtimeEnabled, setIsRealtimeEnabled] = useState(false);
+  const [transcriptionOptions, setTranscriptionOptions] = useState<TranscriptionOptions | null>(null); // From the preset in use
   const [recordingStatus, setRecordingStatus] = useState("Not recording");
   const [realtimeTranscript, setRealtimeTranscript] = useState("");
+  const [realtimeProvisional, setRealtimeProvisional] = useState(""); // May still be revised
   const [error, setError] = useState<string | null>(null);
   const [showSettings, setShowSettings] = useState(false);
   const [currentView, setCurrentView] = useState<'recording' | 'meetings'>('recording');
@@ -75,6 +88,8 @@ function App() {
   const [isGeneratingMinutes, setIsGeneratingMinutes] = useState(false);
   const [micGain, setMicGain] = useState(2.5);
   const [systemGain, setSystemGain] = useState(1.5);
+  const [recordingChannels, setRecordingChannels] = useState<'mix' | 'stereo' | 'stems'>('mix');
+  const [audioProcessing, setAudioProcessing] = useState<AudioProcessing>({ auto_gain: true, normalize_loudness: false });
   const [selectedLanguage, setSelectedLanguage] = useState('en'); // Default to English
   const [currentMeetingId, setCurrentMeetingId] = useState<string | null>(null); // Track current meeting
   
@@ -110,13 +125,15 @@ function App() {
     let interval: number;
     if (isRecording) {
       interval = setInterval(() => {
-        setRecordingTime((prev) => prev + 1);
+        if (!isPaused) {
+          setRecordingTime((prev) => prev + 1);
+        }
         // Update recording status
         updateRecordingStatus();
       }, 1000);
     }
     return () => clearInterval(interval);
-  }, [isRecording]);
+  }, [isRecording, isPaused]);
 
   // Load audio devices and gain settings on component mount
   useEffect(() => {
@@ -144,9 +161,9 @@ function App() {
     if (!file) return;
 
     // Check file type
-    const allowedTypes = ['audio/wav', 'audio/mpeg', 'audio/mp3', 'audio/mp4', 'audio/m4a'];
-    if (!allowedTypes.includes(file.type) && !file.name.match(/\.(wav|mp3|mp4|m4a)$/i)) {
-      showError("Please select a valid audio file (WAV, MP3, MP4, M4A)");
+    const allowedTypes = ['audio/wav', 'audio/mpeg', 'audio/mp3', 'audio/mp4', 'audio/m4a', 'audio/aac', 'audio/ogg', 'audio/flac', 'audio/webm', 'video/webm'];
+    if (!allowedTypes.includes(file.type) && !file.name.match(/\.(wav|mp3|mp4|m4a|aac|ogg|oga|flac|webm|mkv|mka)$/i)) {
+      showError("Please select a valid audio file (WAV, MP3, M4A, AAC, OGG, FLAC, WebM)");
       return;
     }
 
@@ -199,6 +216,9 @@ function App() {
       // Initialize database
       await invoke("initialize_database");
       console.log("Database auto-initialized");
+      loadRecordingChannels();
+      loadAudioProcessing();
+      setTranscriptionOptions(await loadActiveTranscriptionOptions().catch(() => null));
       
       // Initialize Whisper
       await initializeWhisper();
@@ -218,11 +238,14 @@ function App() {
         // Import listen dynamically to ensure it's available
         const { listen: listenFn } = await import("@tauri-apps/api/event");
         
-        unlisten = await listenFn<string>('realtime-transcript', (event) => {
+        unlisten = await listenFn<RealtimeTranscript>('realtime-transcript', (event) => {
           console.log('Received real-time transcript:', event.payload);
+          const text = event.payload.text;
+          setRealtimeProvisional(event.payload.provisional.map(segment => segment.text).join(" "));
+          if (!text) return;
           setRealtimeTranscript(prev => {
             // Append new transcript with a space if there's existing content
-            return prev ? `${prev} ${event.payload}` : event.payload;
+            return prev ? `${prev} ${text}` : text;
           });
         });
         
@@ -325,6 +348,43 @@ function App() {
     }
   };
 
+  const loadRecordingChannels = async () => {
+    try {
+      const channels = await invoke<'mix' | 'stereo' | 'stems'>("get_recording_channels");
+      setRecordingChannels(channels);
+    } catch (error) {
+      console.error("Failed to load recording channels:", error);
+    }
+  };
+
+  const updateRecordingChannels = async (channels: 'mix' | 'stereo' | 'stems') => {
+    try {
+      await invoke("set_recording_channels", { channels });
+      setRecordingChannels(channels);
+    } catch (error) {
+      console.error("Failed to update recording channels:", error);
+      showError(`Failed to update recording channels: ${error}`);
+    }
+  };
+
+  const loadAudioProcessing = async () => {
+    try {
+      setAudioProcessing(await invoke<AudioProcessing>("get_audio_processing"));
+    } catch (error) {
+      console.error("Failed to load audio processing:", error);
+    }
+  };
+
+  const updateAudioProcessing = async (processing: AudioProcessing) => {
+    try {
+      await invoke("set_audio_processing", { processing });
+      setAudioProcessing(processing);
+    } catch (error) {
+      console.error("Failed to update audio processing:", error);
+      showError(`Failed to update audio processing: ${error}`);
+    }
+  };
+
   const updateRecordingStatus = async () => {
     try {
       const status = await invoke<string>("get_recording_status");
@@ -379,13 +439,17 @@ function App() {
     try {
       clearError();
       setIsRecording(true);
+      setIsPaused(false);
       setRecordingTime(0);
       setTranscript("");
       setRealtimeTranscript("");
+      setRealtimeProvisional("");
       setMeetingMinutes("");
       setCurrentMeetingId(null); // Clear previous meeting ID
       
-      const result = await invoke<StartRecordingResult>("start_recording");
+      const result = await invoke<StartRecordingResult>("start_recording", {
+        language: selectedLanguage === 'auto' ? null : selectedLanguage
+      });
       console.log("Recording started:", result);
       
       // Store the meeting ID and audio file path from the start
@@ -402,6 +466,21 @@ function App() {
     }
   };
 
+  const togglePause = async () => {
+    try {
+      if (isPaused) {
+        await invoke<string>("resume_recording");
+        setIsPaused(false);
+      } else {
+        await invoke<string>("pause_recording");
+        setIsPaused(true);
+      }
+    } catch (error) {
+      console.error("Failed to pause/resume recording:", error);
+      showError(`Failed to ${isPaused ? 'resume' : 'pause'} recording: ${error}`);
+    }
+  };
+
   // Define the RecordingResult interface to match the Rust struct
   interface RecordingResult {
     success: boolean;
@@ -409,20 +488,86 @@ function App() {
     audio_file_path: string | null;
     duration_seconds: number;
     sample_count: number;
+    channel_files: string[];
+  }
+
+  // Payloads of the real-time transcription events
+  interface RealtimeTranscript {
+    meeting_id: string | null;
+    chunk_index: number;
+    start: number;
+    end: number;
+    segments: TranscriptionResult['segments'];
+    text: string;
+    provisional: TranscriptionResult['segments'];
+    language: string | null;
+  }
+
+  interface RealtimeTranscriptFinished {
+    meeting_id: string | null;
+    chunks: number;
+    complete: boolean;
   }
 
+  // Start listening before stopping, so the event can't be missed
+  const listenForRealtimeFinish = async (timeoutMs: number) => {
+    const { listen } = await import("@tauri-apps/api/event");
+    let resolveFinished: (payload: RealtimeTranscriptFinished | null) => void = () => {};
+    const finished = new Promise<RealtimeTranscriptFinished | null>(resolve => { resolveFinished = resolve; });
+    const unlisten = await listen<RealtimeTranscriptFinished>('realtime-transcript-finished', (event) => {
+      resolveFinished(event.payload);
+    });
+    const timer = setTimeout(() => resolveFinished(null), timeoutMs);
+    // Wrapped so awaiting the listener doesn't also wait for the event
+    return {
+      finished: finished.finally(() => {
+        clearTimeout(timer);
+        unlisten();
+      }),
+    };
+  };
+
+  // Use the segments saved during real-time transcription instead of transcribing again
+  const saveRealtimeTranscript = async (meetingId: string): Promise<boolean> => {
+    try {
+      setIsTranscribing(true);
+      setTranscript("Finishing real-time transcript...");
+      const result = await invoke<TranscriptionResult>("finalize_realtime_transcript", { meetingId });
+      setTranscript(result.full_text);
+      setTranscriptionResult(result);
+      showError("SUCCESS: Real-time transcript saved to database!");
+      return true;
+    } catch (error) {
+      console.error("Failed to save real-time transcript:", error);
+      return false;
+    } finally {
+      setIsTranscribing(false);
+    }
+  };
+
   const stopRecording = async () => {
     try {
       clearError();
+      const realtimeListener = isRealtimeEnabled ? await listenForRealtimeFinish(120000) : null;
       const result = await invoke<RecordingResult>("stop_recording");
       console.log("Recording stopped:", result);
       
       setIsRecording(false);
+      setIsPaused(false);
       
       // Use the structured response instead of parsing a message string
       if (result.success && result.audio_file_path) {
         setLastRecordingPath(result.audio_file_path);
         
+        // Real-time transcription already covered the whole recording
+        if (realtimeListener && currentMeetingId) {
+          setTranscript("Finishing real-time transcript...");
+          const realtime = await realtimeListener.finished;
+          if (realtime?.complete && realtime.meeting_id === currentMeetingId && await saveRealtimeTranscript(currentMeetingId)) {
+            return;
+          }
+        }
+        
         try {
           // Auto-transcribe and save to database
           await autoTranscribeAndSave(result.audio_file_path);
@@ -463,7 +608,10 @@ function App() {
       // Transcribe audio
       const transcriptionResult = await invoke<TranscriptionResult>("transcribe_audio_with_segments", { 
         audioPath: audioPath,
-        language: languageParam 
+        meetingId: meetingId,
+        language: languageParam,
+        trimSilence: true,
+        options: transcriptionOptions
       });
       
       // Complete progress
@@ -479,7 +627,7 @@ function App() {
         title: `Uploaded Audio - ${new Date().toLocaleDateString()} ${new Date().toLocaleTimeString()}`,
         transcript: transcriptionResult.full_text,
         segments: transcriptionResult.segments,
-        language: languageParam,
+        language: languageParam ?? transcriptionResult.language ?? null,
         audioFilePath: audioPath
       });
       
@@ -523,7 +671,10 @@ function App() {
       // Transcribe audio
       const transcriptionResult = await invoke<TranscriptionResult>("transcribe_audio_with_segments", { 
         audioPath: audioPath,
-        language: languageParam 
+        meetingId: currentMeetingId,
+        language: languageParam,
+        trimSilence: true,
+        options: transcriptionOptions
       });
       
       // Complete progress
@@ -539,7 +690,7 @@ function App() {
          title: `Meeting ${new Date().toLocaleDateString()} ${new Date().toLocaleTimeString()}`,
          transcript: transcriptionResult.full_text,
          segments: transcriptionResult.segments,
-         language: languageParam,
+         language: languageParam ?? transcriptionResult.language ?? null, // Detected language when set to auto
          audioFilePath: audioPath
        });
        
@@ -599,7 +750,10 @@ function App() {
       const languageParam = selectedLanguage === 'auto' ? null : selectedLanguage;
       const result = await invoke<TranscriptionResult>("transcribe_audio_with_segments", { 
         audioPath: lastRecordingPath,
-        language: languageParam 
+        meetingId: currentMeetingId,
+        language: languageParam,
+        trimSilence: true,
+        options: transcriptionOptions
       });
       console.log("Transcription result:", result);
       
@@ -628,25 +782,98 @@ function App() {
     setShowSettings(!showSettings);
   };
 
+  // Define the MeetingMinutesResult interface to match the Rust struct
+  interface MeetingMinutesResult {
+    meeting_minutes: string;
+    ai_provider: string;
+    model: string;
+    key_topics: string[];
+    sentiment?: string;
+    energy?: string;
+  }
+
+  // The backend strips the KEY_TOPICS/SENTIMENT/ENERGY trailer and returns the values separately
+  const showMeetingMinutesResult = (result: MeetingMinutesResult) => {
+    setMeetingMinutes(result.meeting_minutes);
+    if (result.key_topics.length || result.sentiment || result.energy) {
+      setParsedMetadata({
+        keyTopics: result.key_topics,
+        sentiment: result.sentiment || '',
+        energy: result.energy || '',
+        cleanedMinutes: result.meeting_minutes
+      });
+    } else {
+      setParsedMetadata(null);
+    }
+  };
+
   const generateMeetingMinutes = async () => {
     if (!transcript.trim()) {
       showError("No transcript available to generate meeting minutes. Please transcribe audio first.");
       return;
     }
 
+    let unlistenTokens: (() => void) | undefined;
+    let unlistenProgress: (() => void) | undefined;
+    let unlistenActionItems: (() => void) | undefined;
+
     try {
       clearError();
       setIsGeneratingMinutes(true);
-      setMeetingMinutes(`Generating meeting minutes with OpenAI...`);
+      setMeetingMinutes(`Generating meeting minutes...`);
       
       const languageParam = selectedLanguage === 'auto' ? null : selectedLanguage;
-      const result = await invoke<string>('generate_meeting_minutes', { 
+
+      if (currentMeetingId) {
+        // Stream tokens into the view; the backend saves the final minutes to the database
+        const { listen: listenFn } = await import("@tauri-apps/api/event");
+        let streamed = "";
+        unlistenTokens = await listenFn<{ meeting_id: string; token: string }>('meeting-minutes-token', (event) => {
+          if (event.payload.meeting_id !== currentMeetingId) return;
+          streamed += event.payload.token;
+          setMeetingMinutes(streamed);
+        });
+
+        // Long meetings are summarized in parts before the final minutes stream in
+        unlistenProgress = await listenFn<{ meeting_id: string | null; stage: string; current: number; total: number }>('meeting-minutes-progress', (event) => {
+          if (event.payload.meeting_id !== currentMeetingId || streamed) return;
+          const { stage, current, total } = event.payload;
+          if (stage === 'merging') {
+            setMeetingMinutes(`Merging partial summaries into meeting minutes...`);
+          } else {
+            setMeetingMinutes(`${stage === 'condensing' ? 'Condensing' : 'Summarizing'} part ${current} of ${total}...`);
+          }
+        });
+
+        // The minutes are saved even when extracting their action items fails or is cancelled
+        let actionItemsError = null as string | null; // Set from the event callback
+        unlistenActionItems = await listenFn<{ meeting_id: string; error: string }>('action-items-error', (event) => {
+          if (event.payload.meeting_id === currentMeetingId) actionItemsError = event.payload.error;
+        });
+
+        const result = await invoke<MeetingMinutesResult>('generate_meeting_minutes_stream', {
+          meetingId: currentMeetingId,
+          transcript,
+          language: languageParam
+        });
+        console.log(`Meeting minutes streamed with ${result.ai_provider} (${result.model})`);
+
+        showMeetingMinutesResult(result);
+        if (actionItemsError) {
+          showError(`Meeting minutes saved, but action items could not be extracted: ${actionItemsError}`);
+        } else {
+          showError("SUCCESS: Meeting minutes generated and saved to database automatically!");
+        }
+        return;
+      }
+
+      const result = await invoke<MeetingMinutesResult>('generate_meeting_minutes', { 
         transcript,
         language: languageParam 
       });
-      console.log("Meeting minutes generated:", result);
+      console.log(`Meeting minutes generated with ${result.ai_provider} (${result.model}):`, result.meeting_minutes);
       
-      setMeetingMinutes(result);
+      showMeetingMinutesResult(result);
       
       // Auto-save meeting minutes to database
       await autoSaveMeetingMinutes(result);
@@ -654,14 +881,33 @@ function App() {
     } catch (error) {
       console.error("Failed to generate meeting minutes:", error);
       setMeetingMinutes("");
-      showError(`Failed to generate meeting minutes with OpenAI: ${error}`);
+      showError(`Failed to generate meeting minutes: ${error}`);
     } finally {
+      if (unlistenTokens) {
+        unlistenTokens();
+      }
+      if (unlistenProgress) {
+        unlistenProgress();
+      }
+      if (unlistenActionItems) {
+        unlistenActionItems();
+      }
       setIsGeneratingMinutes(false);
     }
   };
 
+  const cancelMeetingMinutes = async () => {
+    if (!currentMeetingId) return;
+
+    try {
+      await invoke("cancel_meeting_minutes", { meetingId: currentMeetingId });
+    } catch (error) {
+      console.error("Failed to cancel meeting minutes generation:", error);
+    }
+  };
+
   // Auto-save meeting minutes to database
-  const autoSaveMeetingMinutes = async (minutes: string) => {
+  const autoSaveMeetingMinutes = async (result: MeetingMinutesResult) => {
     if (!currentMeetingId) {
       console.warn("No current meeting ID available for saving minutes");
       return;
@@ -670,8 +916,11 @@ function App() {
     try {
       await invoke("save_meeting_minutes_to_database", {
         meetingId: currentMeetingId,
-        meetingMinutes: minutes,
-        aiProvider: 'openai'
+        meetingMinutes: result.meeting_minutes,
+        aiProvider: result.ai_provider,
+        keyTopics: result.key_topics,
+        sentiment: result.sentiment,
+        energy: result.energy
       });
       
       console.log("Meeting minutes auto-saved to database");
@@ -690,6 +939,7 @@ function App() {
     setTranscript("");
     setTranscriptionResult(null);
     setRealtimeTranscript("");
+    setRealtimeProvisional("");
     setMeetingMinutes("");
     setLastRecordingPath("");
     setRecordingStatus("Not recording");
@@ -792,7 +1042,7 @@ function App() {
 
                 {/* AI Provider Info */}
                 <div className="text-sm text-gray-600 bg-gray-50 border border-gray-200 rounded-lg px-3 py-2">
-                  Using OpenAI for meeting minutes generation
+                  Using OpenAI (or local Ollama when no API key is set) for meeting minutes generation
                 </div>
               </div>
 
@@ -836,9 +1086,9 @@ function App() {
                 </div>
               )}
               <div className="flex items-center space-x-2">
-                <div className={`w-2 h-2 rounded-full ${isRecording ? 'bg-red-500 animate-pulse' : 'bg-green-500'}`}></div>
+                <div className={`w-2 h-2 rounded-full ${isRecording ? (isPaused ? 'bg-yellow-500' : 'bg-red-500 animate-pulse') : 'bg-green-500'}`}></div>
                 <span className="text-sm font-medium text-gray-700">
-                  {isRecording ? 'Recording' : 'Ready'}
+                  {isRecording ? (isPaused ? 'Paused' : 'Recording') : 'Ready'}
                 </span>
               </div>
             </div>
@@ -942,6 +1192,23 @@ function App() {
                       )}
                       {isRecording ? 'Stop Recording' : 'Start Recording'}
                     </button>
+                    {isRecording && (
+                      <button
+                        className={`ml-4 px-8 py-4 rounded-xl font-semibold text-lg transition-all duration-200 shadow-xl flex items-center ${
+                          isPaused
+                            ? 'bg-green-500 hover:bg-green-600 text-white'
+                            : 'bg-yellow-500 hover:bg-yellow-600 text-white'
+                        }`}
+                        onClick={togglePause}
+                      >
+                        {isPaused ? (
+                          <Play className="w-6 h-6 mr-3" />
+                        ) : (
+                          <Pause className="w-6 h-6 mr-3" />
+                        )}
+                        {isPaused ? 'Resume' : 'Pause'}
+                      </button>
+                    )}
                   </div>
 
                   {/* Secondary Controls */}
@@ -951,7 +1218,7 @@ function App() {
                       <input
                         ref={fileInputRef}
                         type="file"
-                        accept="audio/*,.wav,.mp3,.mp4,.m4a"
+                        accept="audio/*,.wav,.mp3,.mp4,.m4a,.aac,.ogg,.oga,.flac,.webm,.mkv,.mka"
                         onChange={handleAudioUpload}
                         className="hidden"
                         disabled={isUploadingAudio}
@@ -1033,7 +1300,7 @@ function App() {
           </div>
           
           <div className="space-y-6">
-            {isRealtimeEnabled && isRecording && realtimeTranscript && (
+            {isRealtimeEnabled && isRecording && (realtimeTranscript || realtimeProvisional) && (
               <div className="bg-gradient-to-r from-green-50 to-emerald-50 border border-green-200 rounded-xl p-6">
                 <div className="flex items-center mb-4">
                   <div className="w-3 h-3 bg-red-500 rounded-full animate-pulse mr-2"></div>
@@ -1042,6 +1309,9 @@ function App() {
                 <div className="bg-white rounded-lg p-4 border border-green-200 min-h-[120px] max-h-[300px] overflow-y-auto">
                   <p className="text-gray-800 leading-relaxed whitespace-pre-wrap">
                     {realtimeTranscript}
+                    {realtimeProvisional && (
+                      <span className="text-gray-400">{realtimeTranscript ? ' ' : ''}{realtimeProvisional}</span>
+                    )}
                   </p>
                 </div>
               </div>
@@ -1086,6 +1356,7 @@ function App() {
               </div>
               <h3 className="text-2xl font-bold text-gray-900">AI Meeting Minutes</h3>
             </div>
+            <div className="flex items-center">
             {transcript && !isRecording && (
               <button 
                 className="bg-gradient-to-r from-purple-500 to-pink-500 hover:from-purple-600 hover:to-pink-600 text-white px-6 py-3 rounded-xl font-medium transition-all duration-200 transform hover:scale-105 shadow-lg disabled:opacity-50 disabled:cursor-not-allowed disabled:transform-none flex items-center"
@@ -1096,6 +1367,16 @@ function App() {
                 {isGeneratingMinutes ? 'Generating...' : 'Generate Minutes'}
               </button>
             )}
+            {isGeneratingMinutes && currentMeetingId && (
+              <button
+                className="ml-3 bg-gray-100 hover:bg-gray-200 text-gray-700 px-4 py-3 rounded-xl font-medium transition-colors flex items-center"
+                onClick={cancelMeetingMinutes}
+              >
+                <X className="w-4 h-4 mr-2" />
+                Cancel
+              </button>
+            )}
+            </div>
           </div>
           
           <div className="bg-gray-50 rounded-xl p-6 min-h-[200px] max-h-[500px] overflow-y-auto">
@@ -1270,6 +1551,17 @@ function App() {
                   </label>
                 </div>
 
+                {/* Whisper Models */}
+                <ModelManager onMessage={showError} />
+
+                {/* Decoding presets */}
+                <TranscriptionPresets onMessage={showError} onActiveChange={setTranscriptionOptions} />
+
+                {/* Shared glossary */}
+                <GlossaryManager onMessage={showError} />
+
+                {/* WAV or FLAC storage of recordings */}
+                <RecordingStorage onMessage={showError} />
 
                 {/* Audio Gain Settings */}
                 <div className="space-y-6">
@@ -1339,6 +1631,50 @@ function App() {
                         High (3.0/2.0)
                       </button>
                     </div>
+
+                    <div className="space-y-2 pt-2">
+                      <label className="flex items-center space-x-3 cursor-pointer">
+                        <input
+                          type="checkbox"
+                          checked={audioProcessing.auto_gain}
+                          onChange={(e) => updateAudioProcessing({ ...audioProcessing, auto_gain: e.target.checked })}
+                          className="w-5 h-5 text-blue-600 bg-gray-100 border-gray-300 rounded focus:ring-blue-500 focus:ring-2"
+                        />
+                        <span className="text-sm font-medium text-gray-700">Automatic gain control</span>
+                      </label>
+                      <p className="text-xs text-gray-500">
+                        Keeps quiet microphones and loud participants at a steady level. The gains above are where each source starts.
+                      </p>
+                      <label className="flex items-center space-x-3 cursor-pointer">
+                        <input
+                          type="checkbox"
+                          checked={audioProcessing.normalize_loudness}
+                          onChange={(e) => updateAudioProcessing({ ...audioProcessing, normalize_loudness: e.target.checked })}
+                          className="w-5 h-5 text-blue-600 bg-gray-100 border-gray-300 rounded focus:ring-blue-500 focus:ring-2"
+                        />
+                        <span className="text-sm font-medium text-gray-700">Normalize loudness of finished recordings (EBU R128, -23 LUFS)</span>
+                      </label>
+                    </div>
+
+                    <div className="space-y-2 pt-2">
+                      <label htmlFor="recording-channels" className="block text-sm font-medium text-gray-700">
+                        Separate Channels:
+                      </label>
+                      <select
+                        id="recording-channels"
+                        value={recordingChannels}
+                        onChange={(e) => updateRecordingChannels(e.target.value as 'mix' | 'stereo' | 'stems')}
+                        disabled={isRecording}
+                        className="w-full px-3 py-2 border border-gray-300 rounded-lg focus:ring-2 focus:ring-blue-500 focus:border-blue-500 bg-white"
+                      >
+                        <option value="mix">Mix only</option>
+                        <option value="stereo">Stereo file (microphone left, system right)</option>
+                        <option value="stems">Separate microphone and system files</option>
+                      </select>
+                      <p className="text-xs text-gray-500">
+                        Kept next to the mix. Transcript segments are then labelled as local or remote speaker.
+                      </p>
+                    </div>
                   </div>
                 </div>
 
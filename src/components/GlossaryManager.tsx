@@ -0,0 +1,193 @@
+import React, { useState, useEffect } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { BookOpen, Plus, Trash2, Undo2, Wand2 } from 'lucide-react';
+
+interface GlossaryTerm {
+  id: string;
+  meeting_id: string | null;
+  term: string;
+  variants: string[];
+  created_at: string;
+}
+
+interface TranscriptCorrection {
+  id: string;
+  segment_id: string;
+  term: string;
+  original_text: string;
+  corrected_text: string;
+  created_at: string;
+  reverted_at: string | null;
+}
+
+interface GlossaryManagerProps {
+  // Without a meeting only the terms shared by every meeting are managed
+  meetingId?: string;
+  onMessage: (message: string) => void;
+  onTranscriptChanged?: () => void;
+}
+
+const GlossaryManager: React.FC<GlossaryManagerProps> = ({ meetingId, onMessage, onTranscriptChanged }) => {
+  const [terms, setTerms] = useState<GlossaryTerm[]>([]);
+  const [corrections, setCorrections] = useState<TranscriptCorrection[]>([]);
+  const [term, setTerm] = useState('');
+  const [variants, setVariants] = useState('');
+  const [meetingOnly, setMeetingOnly] = useState(!!meetingId);
+
+  const load = async () => {
+    try {
+      setTerms(await invoke<GlossaryTerm[]>('get_glossary_terms', { meetingId: meetingId ?? null }));
+      if (meetingId) {
+        setCorrections(await invoke<TranscriptCorrection[]>('get_transcript_corrections', { meetingId }));
+      }
+    } catch (error) {
+      console.error('Failed to load glossary:', error);
+    }
+  };
+
+  useEffect(() => {
+    load();
+  }, [meetingId]);
+
+  const addTerm = async () => {
+    try {
+      await invoke<GlossaryTerm>('add_glossary_term', {
+        meetingId: meetingId && meetingOnly ? meetingId : null,
+        term,
+        variants: variants.split(',').map(v => v.trim()).filter(Boolean),
+      });
+      setTerm('');
+      setVariants('');
+      await load();
+    } catch (error) {
+      onMessage(`Failed to add glossary term: ${error}`);
+    }
+  };
+
+  const deleteTerm = async (id: string) => {
+    try {
+      await invoke('delete_glossary_term', { id });
+      await load();
+    } catch (error) {
+      onMessage(`Failed to delete glossary term: ${error}`);
+    }
+  };
+
+  const applyGlossary = async () => {
+    if (!meetingId) return;
+    try {
+      const applied = await invoke<TranscriptCorrection[]>('apply_glossary_corrections', { meetingId });
+      onMessage(applied.length > 0 ? `SUCCESS: Made ${applied.length} corrections` : 'SUCCESS: Nothing to correct');
+      await load();
+      if (applied.length > 0) onTranscriptChanged?.();
+    } catch (error) {
+      onMessage(`Failed to apply glossary: ${error}`);
+    }
+  };
+
+  const revert = async (id: string) => {
+    try {
+      await invoke('revert_transcript_correction', { id });
+      await load();
+      onTranscriptChanged?.();
+    } catch (error) {
+      onMessage(`Failed to undo correction: ${error}`);
+    }
+  };
+
+  const inputClass = "px-3 py-2 border border-gray-300 rounded-lg focus:ring-2 focus:ring-blue-500 focus:border-blue-500 bg-white text-sm";
+
+  return (
+    <div className="space-y-4">
+      <h4 className="text-lg font-semibold text-gray-800 flex items-center">
+        <BookOpen className="w-5 h-5 mr-2" />
+        {meetingId ? 'Meeting Glossary' : 'Glossary'}
+      </h4>
+      <p className="text-xs text-gray-500">
+        Terms are given to Whisper as hints. Misheard variants (comma-separated) are replaced in saved transcripts, and every replacement can be undone.
+      </p>
+
+      <div className="flex flex-wrap items-center gap-2">
+        <input type="text" placeholder="Term, e.g. kubectl" value={term}
+          onChange={(e) => setTerm(e.target.value)} className={`flex-1 min-w-[8rem] ${inputClass}`} />
+        <input type="text" placeholder="Misheard as, e.g. cube control, kube ctl" value={variants}
+          onChange={(e) => setVariants(e.target.value)} className={`flex-[2] min-w-[12rem] ${inputClass}`} />
+        {meetingId && (
+          <label className="flex items-center space-x-1 text-sm text-gray-600">
+            <input type="checkbox" checked={meetingOnly} onChange={(e) => setMeetingOnly(e.target.checked)} />
+            <span>This meeting only</span>
+          </label>
+        )}
+        <button
+          className="bg-blue-600 hover:bg-blue-700 text-white px-3 py-2 rounded-lg text-sm disabled:opacity-50 flex items-center"
+          onClick={addTerm}
+          disabled={!term.trim()}
+        >
+          <Plus className="w-4 h-4 mr-1" />
+          Add
+        </button>
+      </div>
+
+      {terms.length > 0 && (
+        <div className="divide-y divide-gray-100 bg-gray-50 rounded-lg border border-gray-200">
+          {terms.map(t => (
+            <div key={t.id} className="flex items-center justify-between px-4 py-2 text-sm">
+              <div className="min-w-0">
+                <span className="font-medium text-gray-900">{t.term}</span>
+                {t.variants.length > 0 && (
+                  <span className="text-gray-500 ml-2">← {t.variants.join(', ')}</span>
+                )}
+                {meetingId && !t.meeting_id && (
+                  <span className="ml-2 text-xs bg-gray-200 text-gray-600 px-2 py-0.5 rounded-full">All meetings</span>
+                )}
+              </div>
+              <button className="p-1 rounded text-red-600 hover:bg-red-50" onClick={() => deleteTerm(t.id)} title="Delete term">
+                <Trash2 className="w-4 h-4" />
+              </button>
+            </div>
+          ))}
+        </div>
+      )}
+
+      {meetingId && (
+        <div className="space-y-2">
+          <div className="flex items-center justify-between">
+            <h5 className="font-medium text-gray-800">Corrections</h5>
+            <button
+              className="text-sm px-3 py-1.5 rounded-lg bg-purple-50 text-purple-700 hover:bg-purple-100 flex items-center"
+              onClick={applyGlossary}
+            >
+              <Wand2 className="w-4 h-4 mr-1" />
+              Apply glossary
+            </button>
+          </div>
+          {corrections.length === 0 ? (
+            <p className="text-sm text-gray-500">No corrections made to this transcript.</p>
+          ) : (
+            <div className="divide-y divide-gray-100 bg-gray-50 rounded-lg border border-gray-200">
+              {[...corrections].reverse().map(c => (
+                <div key={c.id} className={`flex items-start justify-between gap-3 px-4 py-2 text-sm ${c.reverted_at ? 'opacity-50' : ''}`}>
+                  <div className="min-w-0">
+                    <div className="text-xs text-gray-500 mb-1">
+                      {c.term} · {new Date(c.created_at).toLocaleString()}
+                      {c.reverted_at && ` · undone ${new Date(c.reverted_at).toLocaleString()}`}
+                    </div>
+                    <div className="text-gray-400 line-through">{c.original_text}</div>
+                    <div className="text-gray-800">{c.corrected_text}</div>
+                  </div>
+                  {!c.reverted_at && (
+                    <button className="p-1 rounded text-gray-600 hover:bg-gray-200 flex-shrink-0" onClick={() => revert(c.id)} title="Undo correction">
+                      <Undo2 className="w-4 h-4" />
+                    </button>
+                  )}
+                </div>
+              ))}
+            </div>
+          )}
+        </div>
+      )}
+    </div>
+  );
+};
+
+export default GlossaryManager;
@@ -27,7 +27,14 @@ import {
   Loader,
   Lightbulb,
   Music,
+  Tag,
+  ListChecks,
+  Square,
+  CheckSquare,
+  BookOpen,
 } from 'lucide-react';
+import GlossaryManager from './GlossaryManager';
+import { SegmentText } from './TranscriptionSegments';
 import ReactMarkdown from 'react-markdown';
 import remarkGfm from 'remark-gfm';
 import { Prism as SyntaxHighlighter } from 'react-syntax-highlighter';
@@ -44,6 +51,51 @@ interface Meeting {
   meeting_minutes?: string;
   language?: string;
   ai_provider?: string;
+  key_topics?: string[];
+  sentiment?: 'Positive' | 'Neutral' | 'Negative';
+  energy?: 'High' | 'Medium' | 'Low';
+}
+
+interface ActionItem {
+  id: string;
+  meeting_id: string;
+  meeting_title?: string;
+  description: string;
+  owner?: string;
+  due_date?: string;
+  status: 'open' | 'done';
+  source_time?: number;
+  created_at: string;
+  completed_at?: string;
+}
+
+interface SearchResult {
+  meeting: Meeting;
+  segment_id?: string;
+  start_time?: number;
+  end_time?: number;
+  snippet: string;
+  rank: number;
+}
+
+interface TopicCount {
+  topic: string;
+  meeting_count: number;
+}
+
+interface RecordingPause {
+  meeting_id: string;
+  audio_offset: number;
+  paused_at: string;
+  resumed_at: string | null;
+}
+
+interface SegmentWord {
+  segment_id: string;
+  start_time: number;
+  end_time: number;
+  text: string;
+  probability: number;
 }
 
 interface MeetingSegment {
@@ -53,15 +105,24 @@ interface MeetingSegment {
   end_time: number;
   text: string;
   confidence?: number;
+  speaker?: string | null;
+  source?: 'local' | 'remote' | null;
 }
 
 const MeetingsManager: React.FC = () => {
   const [meetings, setMeetings] = useState<Meeting[]>([]);
   const [selectedMeeting, setSelectedMeeting] = useState<Meeting | null>(null);
   const [segments, setSegments] = useState<MeetingSegment[]>([]);
+  const [segmentWords, setSegmentWords] = useState<Record<string, SegmentWord[]>>({});
+  const [pauses, setPauses] = useState<RecordingPause[]>([]);
+  const [editingSpeaker, setEditingSpeaker] = useState<string | null>(null);
+  const [speakerNameDraft, setSpeakerNameDraft] = useState('');
+  const cancelSpeakerRenameRef = useRef(false);
   const [isLoadingMeetingDetails, setIsLoadingMeetingDetails] = useState(false);
   const currentMeetingIdRef = useRef<string | null>(null);
   const [searchQuery, setSearchQuery] = useState('');
+  const [searchHits, setSearchHits] = useState<Record<string, SearchResult[]>>({});
+  const [pendingSeek, setPendingSeek] = useState<{ meetingId: string; time: number } | null>(null);
   const [newMeetingTitle, setNewMeetingTitle] = useState('');
   const [showCreateDialog, setShowCreateDialog] = useState(false);
   const [isLoading, setIsLoading] = useState(false);
@@ -82,6 +143,11 @@ const MeetingsManager: React.FC = () => {
   // AI generation state
   const [isGeneratingMinutes, setIsGeneratingMinutes] = useState(false);
 
+  // Action items state
+  const [actionItems, setActionItems] = useState<ActionItem[]>([]);
+  const [showAllOpenItems, setShowAllOpenItems] = useState(false);
+  const [isExtractingActionItems, setIsExtractingActionItems] = useState(false);
+
   // Delete confirmation modal state
   const [showDeleteModal, setShowDeleteModal] = useState(false);
   const [meetingToDelete, setMeetingToDelete] = useState<Meeting | null>(null);
@@ -110,9 +176,23 @@ const MeetingsManager: React.FC = () => {
   const [customDateFrom, setCustomDateFrom] = useState('');
   const [customDateTo, setCustomDateTo] = useState('');
 
+  // Topic and sentiment filter state
+  const [topics, setTopics] = useState<TopicCount[]>([]);
+  const [topicFilter, setTopicFilter] = useState<string | null>(null);
+  const [sentimentFilter, setSentimentFilter] = useState<'all' | 'Positive' | 'Neutral' | 'Negative'>('all');
+
 
   useEffect(() => {
     loadMeetings();
+
+    // Recordings recovered after a crash show up as new meetings
+    let unlisten: (() => void) | undefined;
+    import('@tauri-apps/api/event').then(async ({ listen }) => {
+      unlisten = await listen<number>('recordings-recovered', () => loadMeetings());
+    });
+    return () => {
+      if (unlisten) unlisten();
+    };
   }, []);
 
   // Remove automatic audio loading - now it's lazy loaded when user clicks show audio player
@@ -133,19 +213,34 @@ const MeetingsManager: React.FC = () => {
       
       // Load meeting segments (they will be cleared in the function if needed)
       loadMeetingSegments(selectedMeeting.id);
+      setShowAllOpenItems(false);
+      loadActionItems(selectedMeeting.id, false);
     } else {
       // Clear everything if no meeting selected
       setSegments([]);
+      setActionItems([]);
       setIsLoadingMeetingDetails(false);
       setParsedMetadata(null);
     }
   }, [selectedMeeting?.id]); // Only depend on the ID to prevent unnecessary re-renders
 
+  // Load the audio for a search hit once its meeting is selected; the seek happens on loadedmetadata
+  useEffect(() => {
+    if (pendingSeek && selectedMeeting?.id === pendingSeek.meetingId && selectedMeeting.audio_file_path) {
+      if (!audioDataUrl) {
+        loadAudioFile(selectedMeeting.audio_file_path);
+      }
+      setShowAudioPlayer(true);
+    }
+  }, [pendingSeek, selectedMeeting?.id]);
+
   const loadMeetings = async () => {
     setIsLoading(true);
     try {
       const allMeetings = await invoke<Meeting[]>('get_all_meetings');
       setMeetings(allMeetings);
+      setSearchHits({});
+      setTopics(await invoke<TopicCount[]>('get_meeting_topics'));
       setError(null);
     } catch (error) {
       console.error('Failed to load meetings:', error);
@@ -163,10 +258,22 @@ const MeetingsManager: React.FC = () => {
 
     setIsLoading(true);
     try {
-      const searchResults = await invoke<Meeting[]>('search_meetings', { 
+      const searchResults = await invoke<SearchResult[]>('search_meetings', { 
         query: searchQuery 
       });
-      setMeetings(searchResults);
+
+      // Results are ranked per hit; list each meeting once, in order of its best hit
+      const hits: Record<string, SearchResult[]> = {};
+      const rankedMeetings: Meeting[] = [];
+      for (const result of searchResults) {
+        if (!hits[result.meeting.id]) {
+          hits[result.meeting.id] = [];
+          rankedMeetings.push(result.meeting);
+        }
+        hits[result.meeting.id].push(result);
+      }
+      setMeetings(rankedMeetings);
+      setSearchHits(hits);
       setError(null);
     } catch (error) {
       console.error('Failed to search meetings:', error);
@@ -177,6 +284,29 @@ const MeetingsManager: React.FC = () => {
   };
 
 
+  // Select the meeting of a search hit and seek its audio to the matching segment
+  const openSearchHit = (hit: SearchResult) => {
+    if (selectedMeeting?.id !== hit.meeting.id) {
+      setSelectedMeeting(hit.meeting);
+    }
+    if (hit.start_time == null) return;
+
+    setActiveTab('segments');
+    if (audioRef.current && audioDataUrl && selectedMeeting?.id === hit.meeting.id) {
+      jumpToSegment(hit.start_time);
+    } else if (hit.meeting.audio_file_path) {
+      setPendingSeek({ meetingId: hit.meeting.id, time: hit.start_time });
+    }
+  };
+
+  // Render a search snippet, highlighting the <mark>ed terms without injecting HTML
+  const renderSnippet = (snippet: string) =>
+    snippet.split(/(<mark>.*?<\/mark>)/g).map((part, i) =>
+      part.startsWith('<mark>')
+        ? <mark key={i} className="bg-yellow-200 rounded px-0.5">{part.slice(6, -7)}</mark>
+        : <span key={i}>{part}</span>
+    );
+
   const deleteMeeting = (meetingId: string) => {
     console.log('🗑️ Delete button clicked for meeting:', meetingId);
     
@@ -342,6 +472,12 @@ const MeetingsManager: React.FC = () => {
   const handleAudioLoadedMetadata = () => {
     if (audioRef.current) {
       setDuration(audioRef.current.duration || 0);
+
+      if (pendingSeek && pendingSeek.meetingId === selectedMeeting?.id) {
+        audioRef.current.currentTime = pendingSeek.time;
+        setCurrentTime(pendingSeek.time);
+        setPendingSeek(null);
+      }
     }
   };
 
@@ -357,6 +493,46 @@ const MeetingsManager: React.FC = () => {
   };
 
 
+  // Speakers in order of first appearance, with their segment counts
+  const meetingSpeakers = segments.reduce<{ name: string; count: number }[]>((speakers, segment) => {
+    if (!segment.speaker) return speakers;
+    const existing = speakers.find(speaker => speaker.name === segment.speaker);
+    if (existing) {
+      existing.count += 1;
+    } else {
+      speakers.push({ name: segment.speaker, count: 1 });
+    }
+    return speakers;
+  }, []);
+
+  const startRenameSpeaker = (speaker: string) => {
+    setEditingSpeaker(speaker);
+    setSpeakerNameDraft(speaker);
+  };
+
+  const saveSpeakerName = async () => {
+    if (!selectedMeeting || !editingSpeaker) return;
+    const newName = speakerNameDraft.trim();
+    if (cancelSpeakerRenameRef.current || !newName || newName === editingSpeaker) {
+      cancelSpeakerRenameRef.current = false;
+      setEditingSpeaker(null);
+      return;
+    }
+
+    try {
+      await invoke<string>('rename_speaker', {
+        meetingId: selectedMeeting.id,
+        speaker: editingSpeaker,
+        newName
+      });
+      setEditingSpeaker(null);
+      await loadMeetingSegments(selectedMeeting.id);
+    } catch (error) {
+      console.error('Failed to rename speaker:', error);
+      alert(`Failed to rename speaker: ${error}`);
+    }
+  };
+
   const loadMeetingSegments = useCallback(async (meetingId: string) => {
     // Only set loading if this is a different meeting than currently displayed
     const isDifferentMeeting = currentMeetingIdRef.current !== meetingId;
@@ -370,18 +546,27 @@ const MeetingsManager: React.FC = () => {
     currentMeetingIdRef.current = meetingId;
     
     try {
-      const meetingSegments = await invoke<MeetingSegment[]>('get_meeting_segments', {
-        meetingId: meetingId
-      });
+      const [meetingSegments, meetingPauses, meetingWords] = await Promise.all([
+        invoke<MeetingSegment[]>('get_meeting_segments', { meetingId: meetingId }),
+        invoke<RecordingPause[]>('get_meeting_pauses', { meetingId: meetingId }),
+        invoke<SegmentWord[]>('get_meeting_words', { meetingId: meetingId })
+      ]);
       
       // Only update if this is still the selected meeting (prevent race conditions)
       if (currentMeetingIdRef.current === meetingId) {
         setSegments(meetingSegments);
+        setPauses(meetingPauses);
+        setSegmentWords(meetingWords.reduce<Record<string, SegmentWord[]>>((bySegment, word) => {
+          bySegment[word.segment_id] = [...(bySegment[word.segment_id] || []), word];
+          return bySegment;
+        }, {}));
       }
     } catch (error) {
       console.error('Failed to load meeting segments:', error);
       if (currentMeetingIdRef.current === meetingId) {
         setSegments([]);
+        setPauses([]);
+        setSegmentWords({});
       }
     } finally {
       // Only clear loading if this is still the current meeting
@@ -391,6 +576,54 @@ const MeetingsManager: React.FC = () => {
     }
   }, []);
 
+  // Action item functions
+  const loadActionItems = async (meetingId: string, allOpen: boolean) => {
+    try {
+      const items = allOpen
+        ? await invoke<ActionItem[]>('get_open_action_items')
+        : await invoke<ActionItem[]>('get_meeting_action_items', { meetingId });
+      setActionItems(items);
+    } catch (error) {
+      console.error('Failed to load action items:', error);
+      setActionItems([]);
+    }
+  };
+
+  const toggleActionItem = async (item: ActionItem) => {
+    try {
+      const updated = await invoke<ActionItem>(
+        item.status === 'done' ? 'reopen_action_item' : 'mark_action_item_done',
+        { id: item.id }
+      );
+      setActionItems(prev => showAllOpenItems && updated.status === 'done'
+        ? prev.filter(i => i.id !== updated.id)
+        : prev.map(i => i.id === updated.id ? updated : i));
+    } catch (error) {
+      console.error('Failed to update action item:', error);
+      setError(`Failed to update action item: ${error}`);
+    }
+  };
+
+  const extractActionItems = async () => {
+    if (!selectedMeeting) return;
+
+    try {
+      setError(null);
+      setIsExtractingActionItems(true);
+      const items = await invoke<ActionItem[]>('extract_action_items', {
+        meetingId: selectedMeeting.id,
+        language: selectedMeeting.language === 'auto' ? null : selectedMeeting.language
+      });
+      setShowAllOpenItems(false);
+      setActionItems(items);
+    } catch (error) {
+      console.error('Failed to extract action items:', error);
+      setError(`Failed to extract action items: ${error}`);
+    } finally {
+      setIsExtractingActionItems(false);
+    }
+  };
+
   // Audio player functions
 
   const handleSeek = (e: React.ChangeEvent<HTMLInputElement>) => {
@@ -420,6 +653,12 @@ const MeetingsManager: React.FC = () => {
     return `${minutes}:${remainingSeconds.toString().padStart(2, '0')}`;
   };
 
+  const formatPauseDuration = (pause: RecordingPause): string => {
+    if (!pause.resumed_at) return '';
+    const seconds = (new Date(pause.resumed_at).getTime() - new Date(pause.paused_at).getTime()) / 1000;
+    return seconds >= 60 ? `${Math.round(seconds / 60)} min` : `${Math.round(seconds)}s`;
+  };
+
   // Filter meetings based on date filter
   const getFilteredMeetings = () => {
     const now = new Date();
@@ -428,6 +667,13 @@ const MeetingsManager: React.FC = () => {
     const monthAgo = new Date(today.getTime() - 30 * 24 * 60 * 60 * 1000);
     
     return meetings.filter(meeting => {
+      if (topicFilter && !meeting.key_topics?.some(topic => topic.toLowerCase() === topicFilter.toLowerCase())) {
+        return false;
+      }
+      if (sentimentFilter !== 'all' && meeting.sentiment !== sentimentFilter) {
+        return false;
+      }
+
       const meetingDate = new Date(meeting.created_at);
       
       switch (dateFilter) {
@@ -558,12 +804,24 @@ const MeetingsManager: React.FC = () => {
   };
 
   // Parse AI-generated metadata from meeting minutes
-  const parseMetadata = (meetingMinutes: string) => {
+  const parseMetadata = (meeting: Meeting) => {
+    const meetingMinutes = meeting.meeting_minutes;
     if (!meetingMinutes) {
       setParsedMetadata(null);
       return;
     }
 
+    // The backend strips the trailer and stores the values on the meeting
+    if (meeting.key_topics?.length || meeting.sentiment || meeting.energy) {
+      setParsedMetadata({
+        keyTopics: meeting.key_topics || [],
+        sentiment: meeting.sentiment || '',
+        energy: meeting.energy || '',
+        cleanedMinutes: meetingMinutes
+      });
+      return;
+    }
+
     // Look for the metadata section at the end
     const metadataMatch = meetingMinutes.match(/---\s*\nKEY_TOPICS:\s*(.+)\s*\nSENTIMENT:\s*(.+)\s*\nENERGY:\s*(.+)\s*$/);
     
@@ -582,17 +840,18 @@ const MeetingsManager: React.FC = () => {
       });
     } else {
       // Fallback for older format or if parsing fails
+      setParsedMetadata(null);
     }
   };
 
   // Parse metadata when selected meeting changes
   useEffect(() => {
     if (selectedMeeting?.meeting_minutes) {
-      parseMetadata(selectedMeeting.meeting_minutes);
+      parseMetadata(selectedMeeting);
     } else {
       setParsedMetadata(null);
     }
-  }, [selectedMeeting?.id, selectedMeeting?.meeting_minutes]); // Include ID to ensure fresh parsing
+  }, [selectedMeeting?.id, selectedMeeting?.meeting_minutes, selectedMeeting?.sentiment]); // Include ID to ensure fresh parsing
 
   // Auto-scroll to current segment when sync is active
   const [lastScrolledSegmentId, setLastScrolledSegmentId] = useState<string | null>(null);
@@ -627,42 +886,76 @@ const MeetingsManager: React.FC = () => {
       return;
     }
 
+    let unlistenActionItems: (() => void) | undefined;
     try {
       setError(null);
       setIsGeneratingMinutes(true);
       
       const languageParam = selectedMeeting.language === 'auto' ? null : selectedMeeting.language;
+
+      let actionItemsError = null as string | null; // Set from the event callback
+      const { listen } = await import('@tauri-apps/api/event');
+      unlistenActionItems = await listen<{ meeting_id: string; error: string }>('action-items-error', (event) => {
+        if (event.payload.meeting_id === selectedMeeting.id) actionItemsError = event.payload.error;
+      });
       
-      const result = await invoke<string>('generate_meeting_minutes', { 
+      const result = await invoke<{
+        meeting_minutes: string;
+        ai_provider: string;
+        model: string;
+        key_topics: string[];
+        sentiment?: Meeting['sentiment'];
+        energy?: Meeting['energy'];
+        action_items: ActionItem[];
+      }>('generate_meeting_minutes', { 
         transcript: selectedMeeting.transcript,
-        language: languageParam 
+        language: languageParam,
+        meetingId: selectedMeeting.id
       });
       
       // Save the generated minutes to database
       await invoke('save_meeting_minutes_to_database', {
         meetingId: selectedMeeting.id,
-        meetingMinutes: result,
-        aiProvider: 'openai'
+        meetingMinutes: result.meeting_minutes,
+        aiProvider: result.ai_provider,
+        keyTopics: result.key_topics,
+        sentiment: result.sentiment,
+        energy: result.energy
       });
       
       // Update the selected meeting with new minutes
-      const updatedMeeting = { ...selectedMeeting, meeting_minutes: result, ai_provider: 'openai' };
+      const updatedMeeting = {
+        ...selectedMeeting,
+        meeting_minutes: result.meeting_minutes,
+        ai_provider: result.ai_provider,
+        key_topics: result.key_topics,
+        sentiment: result.sentiment,
+        energy: result.energy
+      };
       setSelectedMeeting(updatedMeeting);
       
       // Update the meetings list
       setMeetings(prev => prev.map(m => 
         m.id === selectedMeeting.id ? updatedMeeting : m
       ));
+      setTopics(await invoke<TopicCount[]>('get_meeting_topics'));
+      setShowAllOpenItems(false);
+      setActionItems(result.action_items);
       
-      setError(`✅ Meeting minutes generated successfully with OpenAI!`);
-      
-      // Auto-hide success message after 3 seconds
-      setTimeout(() => setError(null), 3000);
+      if (actionItemsError) {
+        setError(`Meeting minutes saved, but action items could not be extracted: ${actionItemsError}`);
+      } else {
+        setError(`✅ Meeting minutes generated successfully with ${result.ai_provider === 'ollama' ? 'Ollama' : 'OpenAI'}!`);
+        
+        // Auto-hide success message after 3 seconds
+        setTimeout(() => setError(null), 3000);
+      }
       
     } catch (error) {
       console.error('Failed to generate meeting minutes:', error);
-      setError(`Failed to generate meeting minutes with OpenAI: ${error}`);
+      setError(`Failed to generate meeting minutes: ${error}`);
     } finally {
+      if (unlistenActionItems) unlistenActionItems();
       setIsGeneratingMinutes(false);
     }
   };
@@ -714,7 +1007,7 @@ const MeetingsManager: React.FC = () => {
                 <div className="flex items-center justify-between mb-4">
                   <h2 className="text-xl font-semibold text-gray-800 flex items-center gap-2">
                     <FileText className="w-5 h-5 text-blue-500" />
-                    Meetings ({filteredMeetings.length}{dateFilter !== 'all' || searchQuery ? ` of ${meetings.length}` : ''})
+                    Meetings ({filteredMeetings.length}{dateFilter !== 'all' || topicFilter || sentimentFilter !== 'all' || searchQuery ? ` of ${meetings.length}` : ''})
                   </h2>
                 </div>
 
@@ -800,6 +1093,53 @@ const MeetingsManager: React.FC = () => {
                 </div>
               </div>
 
+              {/* Topic and Sentiment Filter */}
+              <div className="mb-4 px-4">
+                <div className="flex flex-col space-y-3">
+                  <div className="flex items-center gap-2 mb-2">
+                    <Tag className="h-4 w-4 text-gray-500" />
+                    <span className="text-sm font-medium text-gray-700">Filter by Sentiment</span>
+                  </div>
+
+                  <div className="flex flex-wrap gap-2">
+                    {(['all', 'Positive', 'Neutral', 'Negative'] as const).map(option => (
+                      <button
+                        key={option}
+                        onClick={() => setSentimentFilter(option)}
+                        className={`px-3 py-1.5 text-xs rounded-full border transition-colors ${
+                          sentimentFilter === option
+                            ? 'bg-blue-500 text-white border-blue-500'
+                            : 'bg-white text-gray-600 border-gray-200 hover:border-gray-300'
+                        }`}
+                      >
+                        {option === 'all' ? 'All' : option}
+                      </button>
+                    ))}
+                  </div>
+
+                  {topics.length > 0 && (
+                    <>
+                      <span className="text-sm font-medium text-gray-700">Filter by Topic</span>
+                      <div className="flex flex-wrap gap-2 max-h-24 overflow-y-auto">
+                        {topics.map(({ topic, meeting_count }) => (
+                          <button
+                            key={topic}
+                            onClick={() => setTopicFilter(topicFilter === topic ? null : topic)}
+                            className={`px-3 py-1.5 text-xs rounded-full border transition-colors ${
+                              topicFilter === topic
+                                ? 'bg-purple-500 text-white border-purple-500'
+                                : 'bg-white text-purple-700 border-purple-200 hover:border-purple-300'
+                            }`}
+                          >
+                            {topic} ({meeting_count})
+                          </button>
+                        ))}
+                      </div>
+                    </>
+                  )}
+                </div>
+              </div>
+
               {/* Meetings List */}
               <div className="max-h-[600px] overflow-y-auto">
                 {isLoading ? (
@@ -831,6 +1171,7 @@ const MeetingsManager: React.FC = () => {
                     <p className="text-gray-500">
                       {searchQuery ? 'Try a different search term' : 
                        dateFilter !== 'all' ? 'No meetings found for the selected date range' : 
+                       topicFilter || sentimentFilter !== 'all' ? 'No meetings match the selected topic or sentiment' : 
                        'Create your first meeting to get started'}
                     </p>
                   </div>
@@ -909,6 +1250,21 @@ const MeetingsManager: React.FC = () => {
                                     </span>
                                   )}
                                 </div>
+                                {searchHits[meeting.id]?.slice(0, 3).map((hit, i) => (
+                                  <button
+                                    key={hit.segment_id || `meeting-${i}`}
+                                    onClick={(e) => {
+                                      e.stopPropagation();
+                                      openSearchHit(hit);
+                                    }}
+                                    className="block w-full text-left mt-2 text-xs text-gray-600 hover:text-gray-900"
+                                  >
+                                    {hit.start_time != null && (
+                                      <span className="font-medium text-blue-600 mr-1">{formatTime(hit.start_time)}</span>
+                                    )}
+                                    {renderSnippet(hit.snippet)}
+                                  </button>
+                                ))}
                               </div>
                             )}
                           </div>
@@ -1179,6 +1535,8 @@ const MeetingsManager: React.FC = () => {
                           { id: 'overview', name: 'Overview', icon: FileText },
                           { id: 'transcript', name: 'Full Transcript', icon: MessageSquare },
                           { id: 'segments', name: 'Transcript Segments', icon: TrendingUp },
+                          { id: 'actions', name: 'Action Items', icon: ListChecks },
+                          { id: 'glossary', name: 'Glossary', icon: BookOpen },
                           { id: 'notes', name: 'Notes', icon: StickyNote }
                         ].map((tab) => (
                           <button
@@ -1216,7 +1574,7 @@ const MeetingsManager: React.FC = () => {
                               {/* Generate/Regenerate Button */}
                               <div className="flex items-center gap-3">
                                 <div className="text-sm text-gray-600">
-                                  Meeting minutes generated with OpenAI
+                                  Meeting minutes generated with {selectedMeeting?.ai_provider === 'ollama' ? 'Ollama' : 'OpenAI'}
                                 </div>
                                 
                                 <button
@@ -1439,59 +1797,112 @@ const MeetingsManager: React.FC = () => {
                                     Click any segment to jump to that time in the audio. Currently playing segment is highlighted and auto-scrolled.
                                   </p>
                                 )}
+                                {meetingSpeakers.length > 0 && (
+                                  <div className="flex flex-wrap items-center gap-2 mt-3">
+                                    <span className="text-xs text-gray-500">Speakers:</span>
+                                    {meetingSpeakers.map(speaker => (
+                                      editingSpeaker === speaker.name ? (
+                                        <input
+                                          key={speaker.name}
+                                          autoFocus
+                                          value={speakerNameDraft}
+                                          onChange={(e) => setSpeakerNameDraft(e.target.value)}
+                                          onBlur={saveSpeakerName}
+                                          onKeyDown={(e) => {
+                                            if (e.key === 'Escape') cancelSpeakerRenameRef.current = true;
+                                            if (e.key === 'Enter' || e.key === 'Escape') e.currentTarget.blur();
+                                          }}
+                                          className="text-xs px-2 py-1 border border-purple-300 rounded-full focus:outline-none focus:ring-2 focus:ring-purple-500"
+                                        />
+                                      ) : (
+                                        <button
+                                          key={speaker.name}
+                                          onClick={() => startRenameSpeaker(speaker.name)}
+                                          className="text-xs bg-purple-100 text-purple-700 px-2 py-1 rounded-full font-medium hover:bg-purple-200 transition-colors"
+                                          title="Click to rename this speaker"
+                                        >
+                                          {speaker.name} ({speaker.count})
+                                        </button>
+                                      )
+                                    ))}
+                                  </div>
+                                )}
                               </div>
                               <div className="max-h-96 overflow-y-auto" ref={segmentsContainerRef}>
                                 {segments.map((segment, index) => {
                                   const currentSegment = getCurrentSegment();
                                   const isCurrentSegment = isSyncEnabled && currentSegment?.id === segment.id;
+                                  // Pauses that happened between the previous segment and this one
+                                  const pausesBefore = pauses.filter(pause =>
+                                    pause.audio_offset <= segment.start_time &&
+                                    (index === 0 || pause.audio_offset > segments[index - 1].start_time)
+                                  );
                                   
                                   return (
-                                    <div
-                                      key={segment.id}
-                                      data-segment-id={segment.id}
-                                      className={`p-4 border-b border-gray-100 cursor-pointer transition-colors ${
-                                        isCurrentSegment 
-                                          ? 'bg-blue-50 border-blue-200 shadow-sm' 
-                                          : 'hover:bg-gray-50'
-                                      }`}
-                                      onClick={() => jumpToSegment(segment.start_time)}
-                                    >
-                                      <div className="flex items-start gap-3">
-                                        <div className="flex-shrink-0">
-                                          <span className={`inline-flex items-center justify-center w-8 h-8 rounded-full text-sm font-medium ${
-                                            isCurrentSegment 
-                                              ? 'bg-blue-500 text-white' 
-                                              : 'bg-blue-100 text-blue-600'
-                                          }`}>
-                                            {index + 1}
-                                          </span>
+                                    <React.Fragment key={segment.id}>
+                                      {pausesBefore.map((pause, pauseIndex) => (
+                                        <div key={`pause-${index}-${pauseIndex}`} className="px-4 py-2 bg-yellow-50 border-b border-yellow-100 text-xs text-yellow-800 flex items-center gap-2">
+                                          <Pause className="w-3 h-3" />
+                                          Recording paused at {formatTime(pause.audio_offset)}
+                                          {pause.resumed_at && ` for ${formatPauseDuration(pause)}`}
                                         </div>
-                                        <div className="flex-1">
-                                          <div className="flex items-center gap-2 mb-2">
-                                            <span className={`text-sm font-medium ${
-                                              isCurrentSegment ? 'text-blue-700' : 'text-blue-600'
+                                      ))}
+                                      <div
+                                        data-segment-id={segment.id}
+                                        className={`p-4 border-b border-gray-100 cursor-pointer transition-colors ${
+                                          isCurrentSegment 
+                                            ? 'bg-blue-50 border-blue-200 shadow-sm' 
+                                            : 'hover:bg-gray-50'
+                                        }`}
+                                        onClick={() => jumpToSegment(segment.start_time)}
+                                      >
+                                        <div className="flex items-start gap-3">
+                                          <div className="flex-shrink-0">
+                                            <span className={`inline-flex items-center justify-center w-8 h-8 rounded-full text-sm font-medium ${
+                                              isCurrentSegment 
+                                                ? 'bg-blue-500 text-white' 
+                                                : 'bg-blue-100 text-blue-600'
                                             }`}>
-                                              {formatTime(segment.start_time)} - {formatTime(segment.end_time)}
+                                              {index + 1}
                                             </span>
-                                            {segment.confidence && (
-                                              <span className="text-xs text-gray-500">
-                                                ({Math.round(segment.confidence * 100)}% confidence)
-                                              </span>
-                                            )}
-                                            {isCurrentSegment && (
-                                              <span className="text-xs bg-blue-500 text-white px-2 py-1 rounded-full font-medium">
-                                                Playing
+                                          </div>
+                                          <div className="flex-1">
+                                            <div className="flex items-center gap-2 mb-2">
+                                              <span className={`text-sm font-medium ${
+                                                isCurrentSegment ? 'text-blue-700' : 'text-blue-600'
+                                              }`}>
+                                                {formatTime(segment.start_time)} - {formatTime(segment.end_time)}
                                               </span>
-                                            )}
+                                              {segment.speaker && (
+                                                <span className="text-xs bg-purple-100 text-purple-700 px-2 py-1 rounded-full font-medium">
+                                                  {segment.speaker}
+                                                </span>
+                                              )}
+                                              {segment.source && (
+                                                <span className="text-xs bg-gray-100 text-gray-600 px-2 py-1 rounded-full">
+                                                  {segment.source === 'local' ? 'Local' : 'Remote'}
+                                                </span>
+                                              )}
+                                              {segment.confidence != null && (
+                                                <span className="text-xs text-gray-500">
+                                                  ({Math.round(segment.confidence * 100)}% confidence)
+                                                </span>
+                                              )}
+                                              {isCurrentSegment && (
+                                                <span className="text-xs bg-blue-500 text-white px-2 py-1 rounded-full font-medium">
+                                                  Playing
+                                                </span>
+                                              )}
+                                            </div>
+                                            <p className={`leading-relaxed ${
+                                              isCurrentSegment ? 'text-gray-900 font-medium' : 'text-gray-700'
+                                            }`}>
+                                              <SegmentText text={segment.text} words={segmentWords[segment.id]} />
+                                            </p>
                                           </div>
-                                          <p className={`leading-relaxed ${
-                                            isCurrentSegment ? 'text-gray-900 font-medium' : 'text-gray-700'
-                                          }`}>
-                                            {segment.text}
-                                          </p>
                                         </div>
                                       </div>
-                                    </div>
+                                    </React.Fragment>
                                   );
                                 })}
                               </div>
@@ -1508,6 +1919,102 @@ const MeetingsManager: React.FC = () => {
                         </div>
                       )}
 
+                      {activeTab === 'actions' && (
+                        <div className="space-y-4">
+                          <div className="flex items-center justify-between">
+                            <div className="flex gap-2">
+                              {[
+                                { value: false, label: 'This meeting' },
+                                { value: true, label: 'All open items' }
+                              ].map(option => (
+                                <button
+                                  key={option.label}
+                                  onClick={() => {
+                                    setShowAllOpenItems(option.value);
+                                    loadActionItems(selectedMeeting.id, option.value);
+                                  }}
+                                  className={`px-3 py-1.5 text-xs rounded-full border transition-colors ${
+                                    showAllOpenItems === option.value
+                                      ? 'bg-blue-500 text-white border-blue-500'
+                                      : 'bg-white text-gray-600 border-gray-200 hover:border-gray-300'
+                                  }`}
+                                >
+                                  {option.label}
+                                </button>
+                              ))}
+                            </div>
+                            <button
+                              onClick={extractActionItems}
+                              disabled={isExtractingActionItems || !selectedMeeting.transcript?.trim()}
+                              className="flex items-center gap-2 px-3 py-1.5 text-sm bg-purple-500 text-white rounded-lg hover:bg-purple-600 disabled:opacity-50 disabled:cursor-not-allowed transition-colors"
+                            >
+                              {isExtractingActionItems ? <Loader className="w-4 h-4 animate-spin" /> : <Bot className="w-4 h-4" />}
+                              {isExtractingActionItems ? 'Extracting...' : 'Extract from transcript'}
+                            </button>
+                          </div>
+
+                          {actionItems.length > 0 ? (
+                            <div className="divide-y divide-gray-100 bg-gray-50 rounded-xl">
+                              {actionItems.map((item) => (
+                                <div key={item.id} className="flex items-start gap-3 p-4">
+                                  <button
+                                    onClick={() => toggleActionItem(item)}
+                                    className="flex-shrink-0 mt-0.5 text-gray-500 hover:text-blue-600"
+                                    title={item.status === 'done' ? 'Reopen' : 'Mark done'}
+                                  >
+                                    {item.status === 'done'
+                                      ? <CheckSquare className="w-5 h-5 text-green-500" />
+                                      : <Square className="w-5 h-5" />}
+                                  </button>
+                                  <div className="flex-1">
+                                    <p className={item.status === 'done' ? 'text-gray-400 line-through' : 'text-gray-800'}>
+                                      {item.description}
+                                    </p>
+                                    <div className="flex flex-wrap items-center gap-3 mt-1 text-xs text-gray-500">
+                                      {item.owner && <span>👤 {item.owner}</span>}
+                                      {item.due_date && <span>📅 {item.due_date}</span>}
+                                      {showAllOpenItems && item.meeting_title && <span>📁 {item.meeting_title}</span>}
+                                      {item.source_time != null && item.meeting_id === selectedMeeting.id && (
+                                        <button
+                                          onClick={() => jumpToSegment(item.source_time!)}
+                                          disabled={!audioDataUrl}
+                                          className="text-blue-600 hover:underline disabled:no-underline disabled:text-gray-500"
+                                        >
+                                          ⏱ {formatTime(item.source_time)}
+                                        </button>
+                                      )}
+                                    </div>
+                                  </div>
+                                </div>
+                              ))}
+                            </div>
+                          ) : (
+                            <div className="text-center py-8">
+                              <div className="w-12 h-12 bg-gray-100 rounded-full flex items-center justify-center mx-auto mb-3">
+                                <ListChecks className="w-6 h-6 text-gray-400" />
+                              </div>
+                              <p className="text-gray-600 font-medium">
+                                {showAllOpenItems ? 'No open action items' : 'No action items for this meeting'}
+                              </p>
+                              <p className="text-gray-500 text-sm mt-1">Action items are extracted when meeting minutes are generated</p>
+                            </div>
+                          )}
+                        </div>
+                      )}
+
+                      {activeTab === 'glossary' && (
+                        <GlossaryManager
+                          key={selectedMeeting.id}
+                          meetingId={selectedMeeting.id}
+                          onMessage={(message) => message.startsWith('SUCCESS:') ? setError(null) : setError(message)}
+                          onTranscriptChanged={async () => {
+                            loadMeetingSegments(selectedMeeting.id);
+                            const updated = await invoke<Meeting | null>('get_meeting', { id: selectedMeeting.id });
+                            if (updated) setSelectedMeeting(updated);
+                          }}
+                        />
+                      )}
+
                       {activeTab === 'notes' && (
                         <div className="text-center py-12">
                           <div className="w-16 h-16 bg-yellow-100 rounded-full flex items-center justify-center mx-auto mb-4">
@@ -0,0 +1,254 @@
+import React, { useState, useEffect } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { Download, Trash2, CheckCircle, Loader, Globe, Cpu, Zap } from 'lucide-react';
+
+export interface ModelInfo {
+  name: string;
+  path: string;
+  size_bytes: number;
+  multilingual: boolean;
+  description: string | null;
+  selected: boolean;
+  loaded: boolean;
+  realtime: boolean;
+}
+
+interface LoadedModel {
+  name: string;
+  model_type: string;
+  multilingual: boolean;
+  load_seconds: number;
+}
+
+interface DownloadProgress {
+  name: string;
+  downloaded_bytes: number;
+  total_bytes: number | null;
+}
+
+interface ModelManagerProps {
+  onMessage: (message: string) => void;
+}
+
+// Models offered for download from Hugging Face: the whisper.cpp repository, and
+// the tinydiarize repository for the speaker-turn model
+const DOWNLOADABLE_MODELS = [
+  { name: 'ggml-base.en.bin', label: 'Base English (~150 MB)' },
+  { name: 'ggml-small.bin', label: 'Small Multilingual (~480 MB)' },
+  { name: 'ggml-small.en.bin', label: 'Small English (~480 MB)' },
+  { name: 'ggml-small.en-tdrz.bin', label: 'Small English with speaker turns (~480 MB)' },
+  { name: 'ggml-medium.bin', label: 'Medium Multilingual (~1.5 GB)' },
+  { name: 'ggml-large-v3-turbo.bin', label: 'Large V3 Turbo (~1.6 GB)' },
+  { name: 'ggml-large-v3.bin', label: 'Large V3 Multilingual (~3.1 GB)' },
+];
+
+const formatSize = (bytes: number): string => {
+  if (bytes >= 1024 * 1024 * 1024) return `${(bytes / (1024 * 1024 * 1024)).toFixed(1)} GB`;
+  return `${Math.round(bytes / (1024 * 1024))} MB`;
+};
+
+const ModelManager: React.FC<ModelManagerProps> = ({ onMessage }) => {
+  const [models, setModels] = useState<ModelInfo[]>([]);
+  const [downloadName, setDownloadName] = useState(DOWNLOADABLE_MODELS[0].name);
+  const [downloads, setDownloads] = useState<Record<string, DownloadProgress>>({});
+  const [loading, setLoading] = useState<string | null>(null);
+
+  const loadModels = async () => {
+    try {
+      setModels(await invoke<ModelInfo[]>('list_whisper_models'));
+    } catch (error) {
+      console.error('Failed to list models:', error);
+    }
+  };
+
+  useEffect(() => {
+    loadModels();
+
+    let unlisten: (() => void) | undefined;
+    import('@tauri-apps/api/event').then(async ({ listen }) => {
+      unlisten = await listen<DownloadProgress>('model-download-progress', (event) => {
+        // Ignore progress that arrives after the download finished
+        setDownloads(prev => event.payload.name in prev ? { ...prev, [event.payload.name]: event.payload } : prev);
+      });
+    });
+    return () => {
+      if (unlisten) unlisten();
+    };
+  }, []);
+
+  const downloadModel = async (name: string) => {
+    setDownloads(prev => ({ ...prev, [name]: { name, downloaded_bytes: 0, total_bytes: null } }));
+    try {
+      await invoke<ModelInfo>('download_whisper_model', { name });
+      onMessage(`SUCCESS: Downloaded ${name}`);
+      await loadModels();
+    } catch (error) {
+      console.error('Failed to download model:', error);
+      onMessage(`Failed to download ${name}: ${error}`);
+    } finally {
+      setDownloads(prev => {
+        const next = { ...prev };
+        delete next[name];
+        return next;
+      });
+    }
+  };
+
+  const describeLoaded = (info: LoadedModel) =>
+    `${info.name} (${info.model_type}, ${info.multilingual ? 'multilingual' : 'English only'}) in ${info.load_seconds.toFixed(1)}s`;
+
+  // Waits for running transcriptions, then swaps the model without a restart
+  const switchModel = async (name: string) => {
+    setLoading(name);
+    try {
+      const info = await invoke<LoadedModel>('reload_whisper_model', { name });
+      onMessage(`SUCCESS: Loaded ${describeLoaded(info)}`);
+    } catch (error) {
+      onMessage(`Failed to load ${name}: ${error}`);
+    } finally {
+      setLoading(null);
+      await loadModels();
+    }
+  };
+
+  const toggleRealtimeModel = async (model: ModelInfo) => {
+    setLoading(model.name);
+    try {
+      const info = await invoke<LoadedModel | null>('set_realtime_whisper_model', { name: model.realtime ? null : model.name });
+      onMessage(info ? `SUCCESS: Real-time transcription uses ${describeLoaded(info)}` : 'SUCCESS: Real-time transcription uses the main model');
+    } catch (error) {
+      onMessage(`Failed to change the real-time model: ${error}`);
+    } finally {
+      setLoading(null);
+      await loadModels();
+    }
+  };
+
+  const deleteModel = async (name: string) => {
+    if (!confirm(`Delete ${name}?`)) return;
+    try {
+      await invoke<string>('delete_whisper_model', { name });
+      await loadModels();
+    } catch (error) {
+      onMessage(`Failed to delete ${name}: ${error}`);
+    }
+  };
+
+  const installed = new Set(models.map(model => model.name));
+
+  return (
+    <div className="space-y-4">
+      <h4 className="text-lg font-semibold text-gray-800 flex items-center">
+        <Cpu className="w-5 h-5 mr-2" />
+        Whisper Models
+      </h4>
+
+      {models.length === 0 ? (
+        <p className="text-sm text-gray-500">No models installed yet. Download one below.</p>
+      ) : (
+        <div className="space-y-2">
+          {models.map(model => (
+            <div key={model.name} className="flex items-center justify-between bg-gray-50 border border-gray-200 rounded-lg px-4 py-3">
+              <div className="min-w-0">
+                <div className="flex items-center space-x-2">
+                  <span className="font-medium text-gray-900 truncate" title={model.description || undefined}>{model.name}</span>
+                  {model.loaded && (
+                    <span className="text-xs bg-green-100 text-green-700 px-2 py-0.5 rounded-full">In use</span>
+                  )}
+                  {model.selected && !model.loaded && (
+                    <span className="text-xs bg-blue-100 text-blue-700 px-2 py-0.5 rounded-full">Selected</span>
+                  )}
+                  {model.realtime && (
+                    <span className="text-xs bg-purple-100 text-purple-700 px-2 py-0.5 rounded-full">Real-time</span>
+                  )}
+                </div>
+                <div className="text-xs text-gray-500 flex items-center space-x-3 mt-1">
+                  <span>{formatSize(model.size_bytes)}</span>
+                  <span className="flex items-center">
+                    <Globe className="w-3 h-3 mr-1" />
+                    {model.multilingual ? 'Multilingual' : 'English only'}
+                  </span>
+                </div>
+              </div>
+              <div className="flex items-center space-x-2 ml-4">
+                <button
+                  className="text-sm px-3 py-1.5 rounded-lg bg-blue-50 text-blue-700 hover:bg-blue-100 disabled:opacity-50 flex items-center"
+                  onClick={() => switchModel(model.name)}
+                  disabled={model.loaded || loading !== null}
+                >
+                  {loading === model.name ? <Loader className="w-4 h-4 mr-1 animate-spin" /> : <CheckCircle className="w-4 h-4 mr-1" />}
+                  {model.loaded ? 'In use' : 'Use'}
+                </button>
+                <button
+                  className={`p-1.5 rounded-lg hover:bg-purple-50 disabled:opacity-50 ${model.realtime ? 'text-purple-700' : 'text-gray-500'}`}
+                  onClick={() => toggleRealtimeModel(model)}
+                  disabled={loading !== null}
+                  title={model.realtime ? 'Stop using for real-time transcription' : 'Use for real-time transcription'}
+                >
+                  <Zap className="w-4 h-4" />
+                </button>
+                <button
+                  className="p-1.5 rounded-lg text-red-600 hover:bg-red-50 disabled:opacity-50"
+                  onClick={() => deleteModel(model.name)}
+                  disabled={model.loaded || model.realtime}
+                  title={model.loaded || model.realtime ? 'In use' : 'Delete model'}
+                >
+                  <Trash2 className="w-4 h-4" />
+                </button>
+              </div>
+            </div>
+          ))}
+        </div>
+      )}
+
+      <div className="flex items-center space-x-2">
+        <select
+          value={downloadName}
+          onChange={(e) => setDownloadName(e.target.value)}
+          className="flex-1 px-3 py-2 border border-gray-300 rounded-lg focus:ring-2 focus:ring-blue-500 focus:border-blue-500 bg-white"
+        >
+          {DOWNLOADABLE_MODELS.map(model => (
+            <option key={model.name} value={model.name} disabled={installed.has(model.name)}>
+              {model.label}{installed.has(model.name) ? ' (installed)' : ''}
+            </option>
+          ))}
+        </select>
+        <button
+          className="bg-blue-600 hover:bg-blue-700 text-white px-4 py-2 rounded-lg text-sm font-medium disabled:opacity-50 flex items-center"
+          onClick={() => downloadModel(downloadName)}
+          disabled={installed.has(downloadName) || downloadName in downloads}
+        >
+          <Download className="w-4 h-4 mr-2" />
+          Download
+        </button>
+      </div>
+
+      {Object.values(downloads).map(download => {
+        const percent = download.total_bytes ? (download.downloaded_bytes / download.total_bytes) * 100 : 0;
+        return (
+          <div key={download.name} className="space-y-1">
+            <div className="flex items-center justify-between text-xs text-gray-600">
+              <span className="flex items-center">
+                <Loader className="w-3 h-3 mr-1 animate-spin" />
+                {download.name}
+              </span>
+              <span>
+                {formatSize(download.downloaded_bytes)}
+                {download.total_bytes ? ` / ${formatSize(download.total_bytes)}` : ''}
+              </span>
+            </div>
+            <div className="w-full bg-gray-200 rounded-full h-2">
+              <div className="bg-blue-600 h-2 rounded-full transition-all" style={{ width: `${percent}%` }}></div>
+            </div>
+          </div>
+        );
+      })}
+
+      <p className="text-xs text-gray-500">
+        Downloads are checked against their published SHA-256 and resume where they stopped. Switching models waits for running transcriptions. A small real-time model (⚡) keeps up with speech while the main model handles full transcriptions.
+      </p>
+    </div>
+  );
+};
+
+export default ModelManager;
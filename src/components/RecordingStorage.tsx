@@ -0,0 +1,116 @@
+import React, { useState, useEffect } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { Archive, Loader } from 'lucide-react';
+
+type RecordingStorageMode = 'wav' | 'flac';
+
+interface CompressionProgress {
+  meeting_id: string;
+  done: number;
+  total: number;
+  saved_bytes: number;
+  error: string | null;
+}
+
+interface RecordingStorageProps {
+  onMessage: (message: string) => void;
+}
+
+const formatMegabytes = (bytes: number): string => `${(bytes / (1024 * 1024)).toFixed(1)} MB`;
+
+const RecordingStorage: React.FC<RecordingStorageProps> = ({ onMessage }) => {
+  const [storage, setStorage] = useState<RecordingStorageMode>('wav');
+  const [progress, setProgress] = useState<CompressionProgress | null>(null);
+  const [failures, setFailures] = useState(0);
+
+  useEffect(() => {
+    invoke<RecordingStorageMode>('get_recording_storage')
+      .then(setStorage)
+      .catch(error => console.error('Failed to load recording storage:', error));
+
+    let unlisten: (() => void) | undefined;
+    import('@tauri-apps/api/event').then(async ({ listen }) => {
+      unlisten = await listen<CompressionProgress>('recording-compression-progress', (event) => {
+        setProgress(event.payload);
+        if (event.payload.error) setFailures(prev => prev + 1);
+      });
+    });
+    return () => {
+      if (unlisten) unlisten();
+    };
+  }, []);
+
+  const finished = progress !== null && progress.done === progress.total;
+
+  useEffect(() => {
+    if (!finished || !progress) return;
+    const failed = failures > 0 ? `, ${failures} could not be compressed` : '';
+    onMessage(`SUCCESS: Compressed ${progress.total - failures} recordings, saving ${formatMegabytes(progress.saved_bytes)}${failed}`);
+  }, [finished]);
+
+  const updateStorage = async (mode: RecordingStorageMode) => {
+    try {
+      await invoke('set_recording_storage', { storage: mode });
+      setStorage(mode);
+    } catch (error) {
+      onMessage(`Failed to update recording storage: ${error}`);
+    }
+  };
+
+  const compressExisting = async () => {
+    try {
+      setFailures(0);
+      const total = await invoke<number>('compress_recordings');
+      if (total === 0) {
+        setProgress(null);
+        onMessage('SUCCESS: No WAV recordings left to compress');
+      } else {
+        setProgress({ meeting_id: '', done: 0, total, saved_bytes: 0, error: null });
+      }
+    } catch (error) {
+      onMessage(`Failed to compress recordings: ${error}`);
+    }
+  };
+
+  const compressing = progress !== null && !finished;
+
+  return (
+    <div className="space-y-4">
+      <h4 className="text-lg font-semibold text-gray-800 flex items-center">
+        <Archive className="w-5 h-5 mr-2" />
+        Recording Storage
+      </h4>
+
+      <select
+        value={storage}
+        onChange={(e) => updateStorage(e.target.value as RecordingStorageMode)}
+        className="w-full px-3 py-2 border border-gray-300 rounded-lg focus:ring-2 focus:ring-blue-500 focus:border-blue-500 bg-white"
+      >
+        <option value="wav">Keep WAV files</option>
+        <option value="flac">Compress to FLAC after transcription</option>
+      </select>
+
+      <div className="flex items-center space-x-3">
+        <button
+          className="text-sm px-3 py-2 rounded-lg bg-gray-100 text-gray-700 hover:bg-gray-200 disabled:opacity-50 flex items-center"
+          onClick={compressExisting}
+          disabled={compressing}
+        >
+          {compressing ? <Loader className="w-4 h-4 mr-1 animate-spin" /> : <Archive className="w-4 h-4 mr-1" />}
+          Compress existing recordings
+        </button>
+        {compressing && progress && (
+          <span className="text-sm text-gray-600">
+            {progress.done} of {progress.total} · {formatMegabytes(progress.saved_bytes)} saved
+          </span>
+        )}
+      </div>
+
+      <p className="text-xs text-gray-500">
+        FLAC is lossless and takes about half the space of WAV. Compressed recordings play back and can be transcribed again as before.
+      </p>
+    </div>
+  );
+};
+
+export default RecordingStorage;
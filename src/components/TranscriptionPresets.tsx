@@ -0,0 +1,243 @@
+import React, { useState, useEffect } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { SlidersHorizontal, Save, RotateCcw, Trash2, CheckCircle } from 'lucide-react';
+
+export interface TranscriptionOptions {
+  beam_size: number;
+  threads: number | null;
+  initial_prompt: string | null;
+  no_speech_threshold: number;
+  temperature_fallback: boolean;
+  suppress_non_speech: boolean;
+  translate: boolean;
+  max_segment_length: number;
+  channel_mix: ChannelMix;
+}
+
+// How files with more than one channel become mono; channels count from 0
+export type ChannelMix = 'average' | 'loudest' | { channel: number };
+
+interface TranscriptionPreset {
+  name: string;
+  options: TranscriptionOptions;
+  builtin: boolean;
+}
+
+interface TranscriptionPresetList {
+  active: string;
+  presets: TranscriptionPreset[];
+}
+
+interface TranscriptionPresetsProps {
+  onMessage: (message: string) => void;
+  onActiveChange: (options: TranscriptionOptions) => void;
+}
+
+// Options of the preset chosen in settings, to pass along with transcriptions
+export const loadActiveTranscriptionOptions = async (): Promise<TranscriptionOptions | null> => {
+  const list = await invoke<TranscriptionPresetList>('get_transcription_presets');
+  return list.presets.find(preset => preset.name === list.active)?.options ?? null;
+};
+
+const TranscriptionPresets: React.FC<TranscriptionPresetsProps> = ({ onMessage, onActiveChange }) => {
+  const [list, setList] = useState<TranscriptionPresetList>({ active: '', presets: [] });
+  const [editing, setEditing] = useState('');
+  const [draft, setDraft] = useState<TranscriptionOptions | null>(null);
+  const [newName, setNewName] = useState('');
+
+  const loadPresets = async (select?: string) => {
+    try {
+      const result = await invoke<TranscriptionPresetList>('get_transcription_presets');
+      setList(result);
+      const name = select ?? result.active;
+      const preset = result.presets.find(p => p.name === name) ?? result.presets.find(p => p.name === result.active);
+      if (preset) {
+        setEditing(preset.name);
+        setDraft(preset.options);
+      }
+      const active = result.presets.find(p => p.name === result.active);
+      if (active) onActiveChange(active.options);
+    } catch (error) {
+      console.error('Failed to load transcription presets:', error);
+    }
+  };
+
+  useEffect(() => {
+    loadPresets();
+  }, []);
+
+  const selectPreset = (name: string) => {
+    const preset = list.presets.find(p => p.name === name);
+    if (preset) {
+      setEditing(name);
+      setDraft(preset.options);
+    }
+  };
+
+  const activate = async () => {
+    try {
+      onActiveChange(await invoke<TranscriptionOptions>('set_active_transcription_preset', { name: editing }));
+      await loadPresets(editing);
+    } catch (error) {
+      onMessage(`Failed to use preset ${editing}: ${error}`);
+    }
+  };
+
+  const save = async (name: string) => {
+    if (!draft) return;
+    try {
+      await invoke('save_transcription_preset', { name, options: draft });
+      setNewName('');
+      await loadPresets(name.trim());
+      onMessage(`SUCCESS: Saved preset ${name.trim()}`);
+    } catch (error) {
+      onMessage(`Failed to save preset: ${error}`);
+    }
+  };
+
+  const remove = async () => {
+    const preset = list.presets.find(p => p.name === editing);
+    if (!preset || !confirm(preset.builtin ? `Reset ${editing} to its defaults?` : `Delete preset ${editing}?`)) return;
+    try {
+      await invoke('delete_transcription_preset', { name: editing });
+      await loadPresets(preset.builtin ? editing : undefined);
+    } catch (error) {
+      onMessage(`Failed to delete preset: ${error}`);
+    }
+  };
+
+  const update = <K extends keyof TranscriptionOptions>(key: K, value: TranscriptionOptions[K]) => {
+    setDraft(prev => prev ? { ...prev, [key]: value } : prev);
+  };
+
+  const editingPreset = list.presets.find(p => p.name === editing);
+  const inputClass = "w-full px-3 py-2 border border-gray-300 rounded-lg focus:ring-2 focus:ring-blue-500 focus:border-blue-500 bg-white";
+
+  return (
+    <div className="space-y-4">
+      <h4 className="text-lg font-semibold text-gray-800 flex items-center">
+        <SlidersHorizontal className="w-5 h-5 mr-2" />
+        Transcription Quality
+      </h4>
+
+      <div className="flex items-center space-x-2">
+        <select value={editing} onChange={(e) => selectPreset(e.target.value)} className={`flex-1 ${inputClass}`}>
+          {list.presets.map(preset => (
+            <option key={preset.name} value={preset.name}>
+              {preset.name}{preset.name === list.active ? ' (in use)' : ''}
+            </option>
+          ))}
+        </select>
+        <button
+          className="text-sm px-3 py-2 rounded-lg bg-blue-50 text-blue-700 hover:bg-blue-100 disabled:opacity-50 flex items-center"
+          onClick={activate}
+          disabled={!editing || editing === list.active}
+        >
+          <CheckCircle className="w-4 h-4 mr-1" />
+          Use
+        </button>
+      </div>
+
+      {draft && (
+        <div className="grid grid-cols-2 gap-4 text-sm">
+          <label className="space-y-1">
+            <span className="text-gray-700">Beam size (1 = greedy)</span>
+            <input type="number" min={1} max={10} value={draft.beam_size}
+              onChange={(e) => update('beam_size', Math.max(1, parseInt(e.target.value) || 1))} className={inputClass} />
+          </label>
+          <label className="space-y-1">
+            <span className="text-gray-700">Threads (empty = all cores)</span>
+            <input type="number" min={1} value={draft.threads ?? ''}
+              onChange={(e) => update('threads', e.target.value ? Math.max(1, parseInt(e.target.value) || 1) : null)} className={inputClass} />
+          </label>
+          <label className="space-y-1">
+            <span className="text-gray-700">No-speech threshold</span>
+            <input type="number" min={0} max={1} step={0.05} value={draft.no_speech_threshold}
+              onChange={(e) => update('no_speech_threshold', parseFloat(e.target.value) || 0)} className={inputClass} />
+          </label>
+          <label className="space-y-1">
+            <span className="text-gray-700">Max segment length (characters, 0 = off)</span>
+            <input type="number" min={0} value={draft.max_segment_length}
+              onChange={(e) => update('max_segment_length', Math.max(0, parseInt(e.target.value) || 0))} className={inputClass} />
+          </label>
+          <label className="space-y-1">
+            <span className="text-gray-700">Channels in multichannel files</span>
+            <select value={typeof draft.channel_mix === 'string' ? draft.channel_mix : 'channel'}
+              onChange={(e) => update('channel_mix', e.target.value === 'channel' ? { channel: 0 } : e.target.value as ChannelMix)} className={inputClass}>
+              <option value="average">Average all channels</option>
+              <option value="loudest">Loudest channel</option>
+              <option value="channel">Single channel</option>
+            </select>
+          </label>
+          {typeof draft.channel_mix === 'object' && (
+            <label className="space-y-1">
+              <span className="text-gray-700">Channel number</span>
+              <input type="number" min={1} value={draft.channel_mix.channel + 1}
+                onChange={(e) => update('channel_mix', { channel: Math.max(1, parseInt(e.target.value) || 1) - 1 })} className={inputClass} />
+            </label>
+          )}
+          <label className="col-span-2 space-y-1">
+            <span className="text-gray-700">Initial prompt (names and terms to expect)</span>
+            <textarea rows={2} value={draft.initial_prompt ?? ''}
+              onChange={(e) => update('initial_prompt', e.target.value || null)} className={inputClass} />
+          </label>
+          <label className="flex items-center space-x-2">
+            <input type="checkbox" checked={draft.temperature_fallback}
+              onChange={(e) => update('temperature_fallback', e.target.checked)} />
+            <span className="text-gray-700">Retry unclear passages</span>
+          </label>
+          <label className="flex items-center space-x-2">
+            <input type="checkbox" checked={draft.suppress_non_speech}
+              onChange={(e) => update('suppress_non_speech', e.target.checked)} />
+            <span className="text-gray-700">Suppress sound tags like [music]</span>
+          </label>
+          <label className="flex items-center space-x-2">
+            <input type="checkbox" checked={draft.translate}
+              onChange={(e) => update('translate', e.target.checked)} />
+            <span className="text-gray-700">Translate to English</span>
+          </label>
+        </div>
+      )}
+
+      <div className="flex items-center space-x-2">
+        <button
+          className="text-sm px-3 py-2 rounded-lg bg-blue-600 text-white hover:bg-blue-700 disabled:opacity-50 flex items-center"
+          onClick={() => save(editing)}
+          disabled={!draft || !editing}
+        >
+          <Save className="w-4 h-4 mr-1" />
+          Save
+        </button>
+        <input
+          type="text"
+          placeholder="New preset name"
+          value={newName}
+          onChange={(e) => setNewName(e.target.value)}
+          className={`flex-1 ${inputClass}`}
+        />
+        <button
+          className="text-sm px-3 py-2 rounded-lg bg-gray-100 text-gray-700 hover:bg-gray-200 disabled:opacity-50"
+          onClick={() => save(newName)}
+          disabled={!draft || !newName.trim()}
+        >
+          Save as new
+        </button>
+        {editingPreset && (
+          <button
+            className="p-2 rounded-lg text-red-600 hover:bg-red-50"
+            onClick={remove}
+            title={editingPreset.builtin ? 'Reset to defaults' : 'Delete preset'}
+          >
+            {editingPreset.builtin ? <RotateCcw className="w-4 h-4" /> : <Trash2 className="w-4 h-4" />}
+          </button>
+        )}
+      </div>
+
+      <p className="text-xs text-gray-500">
+        The preset in use applies to real-time and full transcriptions. Beam search is slower but makes fewer mistakes.
+      </p>
+    </div>
+  );
+};
+
+export default TranscriptionPresets;
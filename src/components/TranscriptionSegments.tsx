@@ -1,14 +1,27 @@
 import React from 'react';
 
+export interface TranscriptionWord {
+  start: number;
+  end: number;
+  text: string;
+  probability: number;
+}
+
 export interface TranscriptionSegment {
   start: number;
   end: number;
   text: string;
+  speaker?: string | null;
+  source?: 'local' | 'remote' | null;
+  confidence?: number | null;
+  words?: TranscriptionWord[];
 }
 
 export interface TranscriptionResult {
   segments: TranscriptionSegment[];
   full_text: string;
+  language?: string | null;
+  language_probability?: number | null; // Only set when the language was auto-detected
 }
 
 interface TranscriptionSegmentsProps {
@@ -16,6 +29,31 @@ interface TranscriptionSegmentsProps {
   isLoading?: boolean;
 }
 
+// Words below this probability are highlighted, as in the exports
+export const LOW_CONFIDENCE_PROBABILITY = 0.5;
+
+// Segment text with the words Whisper was unsure of highlighted. Text edited
+// since transcription no longer matches its words and is shown as is.
+export const SegmentText: React.FC<{ text: string; words?: { text: string; probability: number }[] }> = ({ text, words }) => {
+  if (!words || words.length === 0 || words.map(word => word.text).join(' ') !== text) {
+    return <>{text}</>;
+  }
+  return (
+    <>
+      {words.map((word, index) => (
+        <React.Fragment key={index}>
+          {index > 0 && ' '}
+          {word.probability < LOW_CONFIDENCE_PROBABILITY ? (
+            <span className="bg-yellow-100 underline decoration-dotted decoration-yellow-600" title={`${Math.round(word.probability * 100)}% confidence`}>
+              {word.text}
+            </span>
+          ) : word.text}
+        </React.Fragment>
+      ))}
+    </>
+  );
+};
+
 const formatTime = (seconds: number): string => {
   const minutes = Math.floor(seconds / 60);
   const remainingSeconds = Math.floor(seconds % 60);
@@ -56,6 +94,12 @@ const TranscriptionSegments: React.FC<TranscriptionSegmentsProps> = ({ result, i
           <div className="flex space-x-4">
             <span>{result.segments.length} segments</span>
             <span>{formatTime(result.segments[result.segments.length - 1]?.end || 0)} total</span>
+            {result.language && (
+              <span title={result.language_probability != null ? 'Detected automatically' : undefined}>
+                🌐 {result.language.toUpperCase()}
+                {result.language_probability != null && ` (${Math.round(result.language_probability * 100)}%)`}
+              </span>
+            )}
           </div>
         </div>
       </div>
@@ -80,11 +124,26 @@ const TranscriptionSegments: React.FC<TranscriptionSegmentsProps> = ({ result, i
                     <span className="ml-2 text-gray-500">
                       ({(segment.end - segment.start).toFixed(1)}s)
                     </span>
+                    {segment.speaker && (
+                      <span className="ml-2 text-xs bg-purple-100 text-purple-700 px-2 py-1 rounded-full font-medium">
+                        {segment.speaker}
+                      </span>
+                    )}
+                    {segment.source && (
+                      <span className="ml-2 text-xs bg-gray-100 text-gray-600 px-2 py-1 rounded-full">
+                        {segment.source === 'local' ? 'Local' : 'Remote'}
+                      </span>
+                    )}
+                    {segment.confidence != null && (
+                      <span className="ml-2 text-xs text-gray-500">
+                        {Math.round(segment.confidence * 100)}% confidence
+                      </span>
+                    )}
                   </div>
                 </div>
               </div>
               <p className="text-gray-800 leading-relaxed pl-11">
-                {segment.text}
+                <SegmentText text={segment.text} words={segment.words} />
               </p>
             </div>
           ))}